@@ -0,0 +1,38 @@
+//! Feeds arbitrary bytes to `SimpleScriptCodec`, which must never panic.
+//!
+//! Run it with `cargo fuzz run decode_script ../tests/data/decoder_regressions` from this directory,
+//! so that the regression corpus is used as a seed. Any crashing input that is found should be
+//! added to that corpus once fixed.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scriptful::codecs::dec::{Decode, Decoder};
+use scriptful::codecs::enc::{Encode, Encoder};
+use scriptful::codecs::simple::SimpleScriptCodec;
+use scriptful::core::value::Value;
+use scriptful::op_systems::bitwise::BitwiseOperator;
+use scriptful::op_systems::simple_math::MathOperator;
+use scriptful::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(script) = <&mut SimpleScriptCodec>::from_vec::<MathOperator, Value>(data.to_vec()) {
+        assert_round_trip(&script);
+    }
+    if let Ok(script) = <&mut SimpleScriptCodec>::from_vec::<BitwiseOperator, Value>(data.to_vec())
+    {
+        assert_round_trip(&script);
+    }
+});
+
+/// Decoders accept non-minimal encodings, so only the encoding of a decoded script is expected to
+/// be stable.
+fn assert_round_trip<Op>(script: &Script<Op, Value>)
+where
+    Op: core::fmt::Debug + Decode + Encode,
+{
+    let encoded = <&mut SimpleScriptCodec>::to_vec(script).unwrap();
+    let decoded = <&mut SimpleScriptCodec>::from_vec::<Op, Value>(encoded.clone()).unwrap();
+
+    assert_eq!(<&mut SimpleScriptCodec>::to_vec(&decoded).unwrap(), encoded);
+}
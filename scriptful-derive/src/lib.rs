@@ -0,0 +1,420 @@
+//! Derive macros for the [`Encode`][Encode] and [`Decode`][Decode] traits of [`scriptful`][scriptful],
+//! so that operator and value enums do not need hand-written codec implementations.
+//!
+//! These macros are re-exported by `scriptful` itself when its `derive` feature is enabled, which
+//! is the recommended way of using them.
+//!
+//! Every variant is encoded as an operator identified by a single opcode byte and by the name of the
+//! variant, carrying its fields, if any, in declaration order. How exactly that looks like depends
+//! on the codec: the simple codec writes the opcode followed by the encoding of each field, while
+//! textual codecs may use the name instead. Opcodes are set with the `#[opcode = ...]` attribute.
+//! Variants without it take the opcode of the previous variant plus one, or `0x00` if they are the
+//! first one, much like Rust enum discriminants do.
+//!
+//! Note that opcodes from `0x00` to `0x7f` are reserved for values by the simple codec, which
+//! refuses to encode operators using them. Thus operator enums need to set the opcode of their
+//! first variant to `0x80` or above, while enums that are only ever fields of other operators, such
+//! as `Condition` below, can use any opcode.
+//!
+//! ```rust
+//! use scriptful::codecs::dec::{Decode, Decoder};
+//! use scriptful::codecs::enc::{Encode, Encoder};
+//! use scriptful::codecs::simple::SimpleScriptCodec;
+//! use scriptful::core::value::Value;
+//! use scriptful::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, Encode, Decode)]
+//! enum MyOperator {
+//!     #[opcode = 0xa0]
+//!     Add,
+//!     // Takes `0xa1`.
+//!     Sub,
+//!     #[opcode = 0xb0]
+//!     PushTwice(Value),
+//! }
+//!
+//! let script = Vec::from([
+//!     Item::Operator(MyOperator::PushTwice(Value::Integer(1))),
+//!     Item::Operator(MyOperator::Sub),
+//! ]);
+//! let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+//! assert_eq!(encoded, Vec::from([0xb0, 0x03, 0x01, 0xa1]));
+//!
+//! let decoded: Script<MyOperator> = <&mut SimpleScriptCodec>::from_vec(encoded).unwrap();
+//! assert_eq!(decoded, script);
+//! ```
+//!
+//! Fields can be of any type implementing `Encode` and `Decode`, including other derived enums:
+//!
+//! ```rust
+//! use scriptful::codecs::enc::{Encode, Encoder};
+//! use scriptful::codecs::simple::SimpleScriptCodec;
+//! use scriptful::codecs::EncodingError;
+//! use scriptful::core::value::Value;
+//! use scriptful::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, Encode)]
+//! enum Condition {
+//!     // Takes `0x00`.
+//!     Always,
+//!     Never,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Encode)]
+//! enum MyOperator {
+//!     #[opcode = 0x80]
+//!     Jump(Condition),
+//! }
+//!
+//! let script = Vec::from([Item::<MyOperator, Value>::Operator(MyOperator::Jump(Condition::Never))]);
+//! let encoded = <&mut SimpleScriptCodec>::to_vec(&script);
+//! assert_eq!(encoded, Ok(Vec::from([0x80, 0x01])));
+//!
+//! // Yet `Condition` cannot be an item on its own.
+//! let script = Vec::from([Item::<Condition, Value>::Operator(Condition::Never)]);
+//! let encoded = <&mut SimpleScriptCodec>::to_vec(&script);
+//! assert_eq!(
+//!     encoded,
+//!     Err(EncodingError::InvalidOpcode {
+//!         index: 0,
+//!         opcode: 0x01
+//!     })
+//! );
+//! ```
+//!
+//! Two variants cannot share the same opcode, as decoding would be ambiguous:
+//!
+//! ```rust,compile_fail
+//! use scriptful::codecs::enc::Encode;
+//!
+//! #[derive(Encode)]
+//! enum MyOperator {
+//!     #[opcode = 0xa0]
+//!     Add,
+//!     #[opcode = 0xa0]
+//!     Sub,
+//! }
+//! ```
+//!
+//! Neither can implicit opcodes go beyond `0xff`:
+//!
+//! ```rust,compile_fail
+//! use scriptful::codecs::dec::Decode;
+//!
+//! #[derive(Decode)]
+//! enum MyOperator {
+//!     #[opcode = 0xff]
+//!     Add,
+//!     Sub,
+//! }
+//! ```
+//!
+//! [Encode]: https://docs.rs/scriptful/latest/scriptful/codecs/enc/trait.Encode.html
+//! [Decode]: https://docs.rs/scriptful/latest/scriptful/codecs/dec/trait.Decode.html
+//! [scriptful]: https://docs.rs/scriptful
+
+use std::collections::BTreeMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Expr, ExprLit, Fields, Generics,
+    Ident, Lit,
+};
+
+/// Derives `scriptful::codecs::enc::Encode` for an enum.
+///
+/// See the [crate documentation](index.html) for details.
+#[proc_macro_derive(Encode, attributes(opcode))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_encode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `scriptful::codecs::dec::Decode` for an enum.
+///
+/// See the [crate documentation](index.html) for details.
+#[proc_macro_derive(Decode, attributes(opcode))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_decode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// A variant of the enum being derived, together with its resolved opcode.
+struct Variant<'a> {
+    ident: &'a Ident,
+    fields: &'a Fields,
+    opcode: u8,
+}
+
+impl Variant<'_> {
+    /// The names to which the fields of this variant are bound in patterns, so that they never
+    /// collide with the names used in the generated code.
+    fn bindings(&self) -> Vec<Ident> {
+        (0..self.fields.len())
+            .map(|index| format_ident!("__field{}", index))
+            .collect()
+    }
+
+    /// The `OperatorInfo` describing this variant to codecs, which is named after it.
+    fn info(&self) -> TokenStream2 {
+        let opcode = self.opcode;
+        let name = self.ident.to_string();
+        let fields = self.fields.len();
+
+        quote! {
+            ::scriptful::codecs::OperatorInfo {
+                opcode: #opcode,
+                name: #name,
+                fields: #fields,
+            }
+        }
+    }
+
+    /// A pattern matching this variant and binding its fields, if any, to
+    /// [`bindings`][Variant::bindings].
+    fn pattern(&self) -> TokenStream2 {
+        let ident = self.ident;
+        let bindings = self.bindings();
+
+        match self.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote!(Self::#ident { #(#names: #bindings),* })
+            }
+            Fields::Unnamed(_) => quote!(Self::#ident(#(#bindings),*)),
+            Fields::Unit => quote!(Self::#ident),
+        }
+    }
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let variants = variants(input)?;
+    let name = &input.ident;
+    let generics = with_bounds(
+        &input.generics,
+        parse_quote!(::scriptful::codecs::enc::Encode),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let arms = variants.iter().map(|variant| {
+        let pattern = variant.pattern();
+        let info = variant.info();
+
+        if variant.fields.is_empty() {
+            quote! {
+                #pattern => ::scriptful::codecs::enc::EncodeSequence::end(
+                    ::scriptful::codecs::enc::Encoder::encode_operator(encoder, &#info)?,
+                ),
+            }
+        } else {
+            let bindings = variant.bindings();
+            quote! {
+                #pattern => {
+                    let mut sequence =
+                        ::scriptful::codecs::enc::Encoder::encode_operator(encoder, &#info)?;
+                    #(
+                        ::scriptful::codecs::enc::EncodeSequence::encode_element(
+                            &mut sequence,
+                            #bindings,
+                        )?;
+                    )*
+                    ::scriptful::codecs::enc::EncodeSequence::end(sequence)
+                }
+            }
+        }
+    });
+
+    // Empty enums have no values, so they can only be matched by dereferencing.
+    let scrutinee = if variants.is_empty() {
+        quote!(*self)
+    } else {
+        quote!(self)
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::scriptful::codecs::enc::Encode for #name #ty_generics #where_clause {
+            fn encode<__E>(
+                &self,
+                encoder: __E,
+            ) -> ::core::result::Result<
+                <__E as ::scriptful::codecs::enc::Encoder>::Ok,
+                <__E as ::scriptful::codecs::enc::Encoder>::Error,
+            >
+            where
+                __E: ::scriptful::codecs::enc::Encoder,
+            {
+                match #scrutinee {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let variants = variants(input)?;
+    let name = &input.ident;
+    let generics = with_bounds(
+        &input.generics,
+        parse_quote!(::scriptful::codecs::dec::Decode),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let infos = variants.iter().map(Variant::info);
+    let arms = variants.iter().map(|variant| {
+        let ident = variant.ident;
+        let opcode = variant.opcode;
+        let decode = quote!(::scriptful::codecs::dec::Decode::decode(decoder)?);
+
+        let constructor = match variant.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote!(Self::#ident { #(#names: #decode),* })
+            }
+            Fields::Unnamed(fields) => {
+                let decodes = fields.unnamed.iter().map(|_| &decode);
+                quote!(Self::#ident(#(#decodes),*))
+            }
+            Fields::Unit => quote!(Self::#ident),
+        };
+
+        quote! {
+            #opcode => ::core::result::Result::Ok(#constructor),
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::scriptful::codecs::dec::Decode for #name #ty_generics #where_clause {
+            fn decode<__D>(
+                decoder: &mut __D,
+            ) -> ::core::result::Result<Self, <__D as ::scriptful::codecs::dec::Decoder>::Error>
+            where
+                __D: ::scriptful::codecs::dec::Decoder,
+            {
+                let offset = ::scriptful::codecs::dec::Decoder::position(decoder);
+
+                let operators = [#(#infos),*];
+
+                match ::scriptful::codecs::dec::Decoder::decode_operator(decoder, &operators)? {
+                    #(#arms)*
+                    byte => ::core::result::Result::Err(::core::convert::From::from(
+                        ::scriptful::codecs::DecodingError::InvalidDiscriminant { offset, byte },
+                    )),
+                }
+            }
+        }
+    })
+}
+
+/// Resolves the opcodes of all the variants of an enum, making sure that there are no duplicates.
+fn variants(input: &DeriveInput) -> syn::Result<Vec<Variant<'_>>> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`Encode` and `Decode` can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut variants = Vec::new();
+    let mut used = BTreeMap::<u8, &Ident>::new();
+    let mut next = Some(0u8);
+
+    for variant in &data.variants {
+        let opcode = match opcode_attribute(variant)? {
+            Some(opcode) => opcode,
+            None => next.ok_or_else(|| {
+                Error::new_spanned(
+                    &variant.ident,
+                    "implicit opcode exceeds 0xff, please set one with `#[opcode = ...]`",
+                )
+            })?,
+        };
+
+        if let Some(previous) = used.insert(opcode, &variant.ident) {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "opcode {:#04x} is already used by variant `{}`",
+                    opcode, previous
+                ),
+            ));
+        }
+
+        next = opcode.checked_add(1);
+        variants.push(Variant {
+            ident: &variant.ident,
+            fields: &variant.fields,
+            opcode,
+        });
+    }
+
+    Ok(variants)
+}
+
+/// Reads the `#[opcode = ...]` attribute of a variant, if any.
+fn opcode_attribute(variant: &syn::Variant) -> syn::Result<Option<u8>> {
+    let mut opcode = None;
+
+    for attribute in &variant.attrs {
+        if !attribute.path().is_ident("opcode") {
+            continue;
+        }
+        if opcode.is_some() {
+            return Err(Error::new_spanned(
+                attribute,
+                "only one `#[opcode = ...]` attribute is allowed per variant",
+            ));
+        }
+
+        let value = &attribute.meta.require_name_value()?.value;
+        opcode = match value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(int), ..
+            }) => Some(int.base10_parse::<u8>().map_err(|_| {
+                Error::new(
+                    int.span(),
+                    "opcodes must fit into a single byte (0x00 to 0xff)",
+                )
+            })?),
+            _ => {
+                return Err(Error::new(
+                    value.span(),
+                    "expected an integer literal, e.g. `#[opcode = 0x05]`",
+                ))
+            }
+        };
+    }
+
+    Ok(opcode)
+}
+
+/// Requires every type parameter of the enum to implement `bound`, as their values may appear in
+/// the fields of its variants.
+fn with_bounds(generics: &Generics, bound: syn::TraitBound) -> Generics {
+    let mut generics = generics.clone();
+    let parameters: Vec<Ident> = generics
+        .type_params()
+        .map(|parameter| parameter.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+
+    for parameter in parameters {
+        where_clause
+            .predicates
+            .push(parse_quote!(#parameter: #bound));
+    }
+
+    generics
+}
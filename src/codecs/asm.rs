@@ -0,0 +1,674 @@
+//! A human-readable assembly format for scripts, along with a parser and a printer for it.
+//!
+//! Scripts are written as a sequence of words separated by whitespace, where every word is either
+//! the name of an operator or a literal value. Names of operators are resolved through a table
+//! that maps them to the variants of any operator type, so that this format can be used with any
+//! operator system:
+//!
+//! ```rust
+//! use scriptful::codecs::asm;
+//! use scriptful::core::value::Value::*;
+//! use scriptful::op_systems::simple_math::MathOperator;
+//! use scriptful::prelude::*;
+//!
+//! const OPERATORS: &[(&str, MathOperator)] = &[
+//!     ("ADD", MathOperator::Add),
+//!     ("EQUAL", MathOperator::Equal),
+//!     ("MUL", MathOperator::Mul),
+//!     ("NOT", MathOperator::Not),
+//!     ("SUB", MathOperator::Sub),
+//! ];
+//!
+//! let script = asm::parse(
+//!     r#"
+//!     ## Sums two numbers and compares the result.
+//!     1 2 ADD
+//!     3 EQUAL
+//!     "#,
+//!     OPERATORS,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(
+//!     script,
+//!     Vec::from([
+//!         Item::Value(Integer(1)),
+//!         Item::Value(Integer(2)),
+//!         Item::Operator(MathOperator::Add),
+//!         Item::Value(Integer(3)),
+//!         Item::Operator(MathOperator::Equal),
+//!     ])
+//! );
+//! assert_eq!(asm::print(&script, OPERATORS).unwrap(), "1 2 ADD 3 EQUAL");
+//! ```
+//!
+//! Comments start with `#` and span until the end of the line. Words are first looked up in the
+//! table of operators, and otherwise parsed as one of these literals:
+//!
+//! | Value       | Syntax                                                            | Examples                      |
+//! |-------------|-------------------------------------------------------------------|-------------------------------|
+//! | `Boolean`   | `true` or `false`.                                                | `true`                        |
+//! | `Null`      | `null`.                                                           | `null`                        |
+//! | `Integer`   | Decimal digits, optionally preceded by a sign.                    | `42`, `-7`                    |
+//! | `Float`     | Decimal digits with a fraction or exponent, `inf`, `-inf`, `NaN`. | `3.14`, `-1e-7`, `inf`        |
+//! | `Decimal`   | Decimal digits, optionally with a fraction, followed by `d`.      | `-12.34d`, `5d`               |
+//! | `BigInt`    | Decimal digits followed by `n`, with the `bigint` feature.        | `18446744073709551616n`       |
+//! | `String`    | Text between double quotes, with escapes (see below).             | `"Hello, World!"`             |
+//! | `Bytes`     | `0x` followed by pairs of hexadecimal digits.                     | `0xcafe`, `0x`                |
+//! | `Timestamp` | An RFC 3339 date and time in UTC, with no fractional seconds.     | `2009-01-03T18:15:05Z`        |
+//!
+//! Strings can contain any character other than `"` and `\`, which need to be escaped as `\"` and
+//! `\\`. The `\n`, `\r`, `\t` and `\0` escapes are also supported, as well as `\u{...}` for any
+//! Unicode scalar value given in hexadecimal.
+//!
+//! [`print`][print] writes every item in a single line, separated by single spaces, in a way that
+//! [`parse`][parse] reads back into the very same script, as long as no operator is named like a
+//! literal.
+//!
+//! [parse]: fn.parse.html
+//! [print]: fn.print.html
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::codecs::EncodingError;
+use crate::core::decimal::Decimal;
+use crate::core::value::{civil_from_days, days_from_civil, Value};
+use crate::core::ScriptRef;
+use crate::prelude::*;
+
+/// An error found while parsing assembly, along with its position.
+///
+/// Lines and columns start at `1`, and columns are counted in characters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line where the offending word or string starts.
+    pub line: usize,
+    /// The column where the offending word or string starts.
+    pub column: usize,
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+}
+
+/// The different reasons why assembly cannot be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A word is neither a literal nor the name of any operator.
+    UnknownOperator(String),
+    /// A word looks like a literal, but it is not a valid one, e.g. an integer that overflows.
+    InvalidLiteral(String),
+    /// A string is missing its closing quote.
+    UnterminatedString,
+    /// A string contains an unsupported escape sequence.
+    InvalidEscape,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl core::fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ParseErrorKind::UnknownOperator(word) => write!(f, "Unknown operator `{}`", word),
+            ParseErrorKind::InvalidLiteral(word) => write!(f, "Invalid literal `{}`", word),
+            ParseErrorKind::UnterminatedString => write!(f, "Unterminated string"),
+            ParseErrorKind::InvalidEscape => write!(f, "Invalid escape sequence"),
+        }
+    }
+}
+
+/// Parses assembly into a script, resolving the names of operators through `operators`.
+pub fn parse<Op>(source: &str, operators: &[(&str, Op)]) -> Result<Script<Op>, ParseError>
+where
+    Op: core::fmt::Debug + Clone,
+{
+    let mut lexer = Lexer::new(source);
+    let mut script = Script::new();
+
+    while let Some((token, line, column)) = lexer.next_token()? {
+        let item = match token {
+            Token::String(string) => Item::Value(Value::String(string)),
+            Token::Word(word) => parse_word(word, operators).ok_or_else(|| {
+                let kind = if looks_like_literal(word) {
+                    ParseErrorKind::InvalidLiteral(word.into())
+                } else {
+                    ParseErrorKind::UnknownOperator(word.into())
+                };
+
+                ParseError { line, column, kind }
+            })?,
+        };
+
+        script.push(item);
+    }
+
+    Ok(script)
+}
+
+/// Prints a script as assembly, naming its operators after `operators`.
+///
+/// Fails if an operator is missing from `operators`.
+pub fn print<Op>(script: ScriptRef<Op>, operators: &[(&str, Op)]) -> Result<String, EncodingError>
+where
+    Op: core::fmt::Debug + PartialEq,
+{
+    let mut output = String::new();
+
+    for (index, item) in script.iter().enumerate() {
+        if index > 0 {
+            output.push(' ');
+        }
+
+        match item {
+            Item::Operator(operator) => {
+                let (name, _) = operators
+                    .iter()
+                    .find(|(_, candidate)| candidate == operator)
+                    .ok_or(EncodingError::UnknownOperator { index })?;
+                output.push_str(name);
+            }
+            Item::Value(value) => print_value(&mut output, value),
+        }
+    }
+
+    Ok(output)
+}
+
+fn print_value(output: &mut String, value: &Value) {
+    // Writing into a `String` never fails.
+    let _ = match value {
+        Value::Float(x) => write!(output, "{:?}", x),
+        Value::Decimal(x) => write!(output, "{}d", x),
+        Value::String(x) => {
+            output.push('"');
+            for character in x.chars() {
+                match character {
+                    '"' => output.push_str("\\\""),
+                    '\\' => output.push_str("\\\\"),
+                    '\n' => output.push_str("\\n"),
+                    '\r' => output.push_str("\\r"),
+                    '\t' => output.push_str("\\t"),
+                    '\0' => output.push_str("\\0"),
+                    x if x.is_control() => {
+                        let _ = write!(output, "\\u{{{:x}}}", u32::from(x));
+                    }
+                    x => output.push(x),
+                }
+            }
+            output.push('"');
+
+            Ok(())
+        }
+        #[cfg(feature = "bigint")]
+        Value::BigInt(x) => write!(output, "{}n", x),
+        other => write!(output, "{}", other),
+    };
+}
+
+/// Turns a word into an operator, if found in `operators`, or into a literal value otherwise.
+fn parse_word<Op>(word: &str, operators: &[(&str, Op)]) -> Option<Item<Op, Value>>
+where
+    Op: core::fmt::Debug + Clone,
+{
+    if let Some((_, operator)) = operators.iter().find(|(name, _)| *name == word) {
+        return Some(Item::Operator(operator.clone()));
+    }
+
+    let value = match word {
+        "true" => Value::Boolean(true),
+        "false" => Value::Boolean(false),
+        "null" => Value::Null,
+        "inf" | "+inf" => Value::Float(f64::INFINITY),
+        "-inf" => Value::Float(f64::NEG_INFINITY),
+        "NaN" => Value::Float(f64::NAN),
+        _ => {
+            if let Some(hex) = word.strip_prefix("0x") {
+                Value::Bytes(parse_hex(hex)?)
+            } else if word.ends_with('Z') && word.contains('T') {
+                Value::Timestamp(parse_timestamp(word)?)
+            } else if let Some(decimal) = word.strip_suffix('d') {
+                Value::Decimal(parse_decimal(decimal)?)
+            } else if let Some(bigint) = word.strip_suffix('n') {
+                parse_bigint(bigint)?
+            } else if is_integer(word) {
+                Value::Integer(word.parse().ok()?)
+            } else if word
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'))
+            {
+                Value::Float(word.parse().ok()?)
+            } else {
+                return None;
+            }
+        }
+    };
+
+    Some(Item::Value(value))
+}
+
+/// Tells whether a word is meant to be a literal, so that parsing errors are reported as invalid
+/// literals rather than unknown operators.
+fn looks_like_literal(word: &str) -> bool {
+    let unsigned = word.strip_prefix(['+', '-']).unwrap_or(word);
+
+    unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+}
+
+/// Tells whether a word is made of decimal digits only, optionally preceded by a sign.
+pub(crate) fn is_integer(word: &str) -> bool {
+    let unsigned = word.strip_prefix(['+', '-']).unwrap_or(word);
+
+    is_digits(unsigned)
+}
+
+/// Tells whether a word is made of at least one decimal digit and nothing else.
+fn is_digits(word: &str) -> bool {
+    !word.is_empty() && word.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+pub(crate) fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(feature = "bigint")]
+pub(crate) fn parse_bigint(bigint: &str) -> Option<Value> {
+    if !is_integer(bigint) {
+        return None;
+    }
+
+    bigint.parse().ok().map(Value::BigInt)
+}
+
+#[cfg(not(feature = "bigint"))]
+pub(crate) fn parse_bigint(_bigint: &str) -> Option<Value> {
+    None
+}
+
+pub(crate) fn parse_decimal(decimal: &str) -> Option<Decimal> {
+    let (integer, fraction) = decimal.split_once('.').unwrap_or((decimal, ""));
+    if !is_integer(integer) || (decimal.contains('.') && !is_digits(fraction)) {
+        return None;
+    }
+    let scale = u8::try_from(fraction.len()).ok()?;
+    let mantissa = format!("{}{}", integer, fraction).parse().ok()?;
+
+    Decimal::new(mantissa, scale)
+}
+
+/// Parses an RFC 3339 timestamp in UTC, such as `2009-01-03T18:15:05Z`, into seconds since the
+/// Unix epoch, rejecting dates that do not exist.
+pub(crate) fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    let (negative, date) = match date.strip_prefix('-') {
+        Some(date) => (true, date),
+        None => (false, date),
+    };
+
+    let mut date_parts = date.splitn(3, '-');
+    let year = date_parts.next().filter(|x| x.len() >= 4)?;
+    let month = date_parts.next().filter(|x| x.len() == 2)?;
+    let day = date_parts.next().filter(|x| x.len() == 2)?;
+    let mut time_parts = time.splitn(3, ':');
+    let hours = time_parts.next().filter(|x| x.len() == 2)?;
+    let minutes = time_parts.next().filter(|x| x.len() == 2)?;
+    let seconds = time_parts.next().filter(|x| x.len() == 2)?;
+
+    if ![year, month, day, hours, minutes, seconds]
+        .iter()
+        .all(|part| is_digits(part))
+    {
+        return None;
+    }
+
+    let year = year.parse::<i64>().ok()? * if negative { -1 } else { 1 };
+    let month = month.parse().ok()?;
+    let day = day.parse().ok()?;
+    let hours: i64 = hours.parse().ok()?;
+    let minutes: i64 = minutes.parse().ok()?;
+    let seconds: i64 = seconds.parse().ok()?;
+
+    let days = days_from_civil(year, month, day)?;
+    if civil_from_days(days) != (year, month, day) || hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+
+    // The seconds in a day can bring a timestamp back into range, e.g. for `i64::MIN`.
+    i64::try_from(i128::from(days) * 86_400 + i128::from(hours * 3600 + minutes * 60 + seconds))
+        .ok()
+}
+
+/// The tokens in assembly source code.
+enum Token<'a> {
+    /// A string literal, already unescaped.
+    String(String),
+    /// Anything else, delimited by whitespace or comments.
+    Word(&'a str),
+}
+
+/// Splits assembly source code into tokens, keeping track of their positions.
+struct Lexer<'a> {
+    source: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.offset += character.len_utf8();
+        if character == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(character)
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            kind,
+        }
+    }
+
+    /// Reads the next token along with the line and column where it starts, or `None` once the end
+    /// of the source code is reached.
+    fn next_token(&mut self) -> Result<Option<(Token<'a>, usize, usize)>, ParseError> {
+        loop {
+            match self.peek() {
+                Some('#') => while !matches!(self.bump(), Some('\n') | None) {},
+                Some(character) if character.is_whitespace() => {
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+
+        let (line, column) = (self.line, self.column);
+        let token = match self.peek() {
+            None => return Ok(None),
+            Some('"') => Token::String(self.read_string()?),
+            Some(_) => {
+                let start = self.offset;
+                while matches!(self.peek(), Some(c) if !c.is_whitespace() && c != '#') {
+                    self.bump();
+                }
+
+                Token::Word(&self.source[start..self.offset])
+            }
+        };
+
+        Ok(Some((token, line, column)))
+    }
+
+    fn read_string(&mut self) -> Result<String, ParseError> {
+        let start = self.error(ParseErrorKind::UnterminatedString);
+        let mut string = String::new();
+        self.bump();
+
+        loop {
+            let escape = self.error(ParseErrorKind::InvalidEscape);
+            match self.bump().ok_or_else(|| start.clone())? {
+                '"' => return Ok(string),
+                '\\' => {
+                    let character = match self.bump().ok_or_else(|| start.clone())? {
+                        '"' => '"',
+                        '\\' => '\\',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        '0' => '\0',
+                        'u' => self.read_unicode_escape().ok_or(escape)?,
+                        _ => return Err(escape),
+                    };
+                    string.push(character);
+                }
+                character => string.push(character),
+            }
+        }
+    }
+
+    /// Reads the `{...}` part of a `\u{...}` escape sequence.
+    fn read_unicode_escape(&mut self) -> Option<char> {
+        if self.bump()? != '{' {
+            return None;
+        }
+        let start = self.offset;
+        while self.peek()?.is_ascii_hexdigit() {
+            self.bump();
+        }
+        let hex = &self.source[start..self.offset];
+        if self.bump()? != '}' || hex.is_empty() || hex.len() > 6 {
+            return None;
+        }
+
+        char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use crate::codecs::asm::{parse, print, ParseError, ParseErrorKind};
+    use crate::codecs::EncodingError;
+    use crate::core::decimal::Decimal;
+    use crate::core::item::Item::*;
+    use crate::core::value::{CanonicalValue, Value::*};
+    use crate::op_systems::simple_math::MathOperator::{self, *};
+    use crate::prelude::*;
+
+    const OPERATORS: &[(&str, MathOperator)] = &[
+        ("ADD", Add),
+        ("EQUAL", Equal),
+        ("MUL", Mul),
+        ("NOT", Not),
+        ("SUB", Sub),
+        ("-", Sub),
+    ];
+
+    fn round_trip(source: &str) -> Script<MathOperator> {
+        let script = parse(source, OPERATORS).unwrap();
+        let printed = print(&script, OPERATORS).unwrap();
+        assert_eq!(printed, source);
+
+        let reparsed = parse(&printed, OPERATORS).unwrap();
+        let canonical = |script: &Script<MathOperator>| {
+            script
+                .iter()
+                .map(|item| match item {
+                    Operator(operator) => Operator(operator.clone()),
+                    Value(value) => Value(CanonicalValue(value.clone())),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(canonical(&reparsed), canonical(&script));
+
+        script
+    }
+
+    #[test]
+    fn test_literals() {
+        let script = round_trip(
+            r#"true false null 0 -42 3.14 -0.0 1e300 inf -inf NaN -12.34d 5d "" "a \"b\" \\ \n\t\u{7f}" 0x 0xcafe 2009-01-03T18:15:05Z 1969-12-31T23:59:59Z"#,
+        );
+
+        assert_eq!(script.len(), 19);
+        assert_eq!(script[3], Value(Integer(0)));
+        assert_eq!(script[4], Value(Integer(-42)));
+        assert_eq!(script[11], Value(Decimal(Decimal::new(-1234, 2).unwrap())));
+        assert_eq!(script[12], Value(Decimal(Decimal::new(5, 0).unwrap())));
+        assert_eq!(script[14], Value(String("a \"b\" \\ \n\t\u{7f}".into())));
+        assert_eq!(script[16], Value(Bytes([0xca, 0xfe].into())));
+        assert_eq!(script[17], Value(Timestamp(1_231_006_505)));
+        assert_eq!(script[18], Value(Timestamp(-1)));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_literal() {
+        let script = round_trip("-18446744073709551616n");
+
+        assert_eq!(
+            script,
+            Vec::from([Value(BigInt(-num_bigint::BigInt::from(u64::MAX) - 1))])
+        );
+    }
+
+    #[test]
+    fn test_extremes() {
+        round_trip(
+            "170141183460469231731687303715884105727 -170141183460469231731687303715884105728",
+        );
+        round_trip("-292277022657-01-27T08:29:52Z 292277026596-12-04T15:30:07Z");
+        round_trip("-0001-01-01T00:00:00Z -0999-12-31T23:59:59Z 0000-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_operators_and_comments() {
+        let script = parse(
+            "# Full line comment.\n1 2 ADD# Trailing comment.\n\t3 EQUAL NOT\n5 -",
+            OPERATORS,
+        )
+        .unwrap();
+
+        assert_eq!(
+            script,
+            Vec::from([
+                Value(Integer(1)),
+                Value(Integer(2)),
+                Operator(Add),
+                Value(Integer(3)),
+                Operator(Equal),
+                Operator(Not),
+                Value(Integer(5)),
+                Operator(Sub),
+            ])
+        );
+        assert_eq!(
+            print(&script, OPERATORS).unwrap(),
+            "1 2 ADD 3 EQUAL NOT 5 SUB"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (source, line, column, kind) in [
+            (
+                "1 2 ADD\n  FOO",
+                2,
+                3,
+                ParseErrorKind::UnknownOperator("FOO".into()),
+            ),
+            (
+                "1\n2 340282366920938463463374607431768211456",
+                2,
+                3,
+                ParseErrorKind::InvalidLiteral("340282366920938463463374607431768211456".into()),
+            ),
+            (
+                "0xcaf",
+                1,
+                1,
+                ParseErrorKind::InvalidLiteral("0xcaf".into()),
+            ),
+            (
+                "2023-02-29T00:00:00Z",
+                1,
+                1,
+                ParseErrorKind::InvalidLiteral("2023-02-29T00:00:00Z".into()),
+            ),
+            (
+                "1.2.3d",
+                1,
+                1,
+                ParseErrorKind::InvalidLiteral("1.2.3d".into()),
+            ),
+            ("ADD \"abc", 1, 5, ParseErrorKind::UnterminatedString),
+            ("\"ab\\q\"", 1, 4, ParseErrorKind::InvalidEscape),
+            ("\"\\u{d800}\"", 1, 2, ParseErrorKind::InvalidEscape),
+        ] {
+            assert_eq!(
+                parse(source, OPERATORS),
+                Err(ParseError { line, column, kind }),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error = parse("1 FOO", OPERATORS).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Unknown operator `FOO` at line 1, column 3"
+        );
+    }
+
+    #[test]
+    fn test_print_unknown_operator() {
+        let script = Vec::from([Value(Integer(1)), Operator(StrictEqual)]);
+
+        assert_eq!(
+            print(&script, OPERATORS),
+            Err(EncodingError::UnknownOperator { index: 1 })
+        );
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use proptest::prelude::*;
+
+    use crate::codecs::asm::{parse, print};
+    use crate::prelude::*;
+    use crate::test_utils::{canonical, value};
+
+    proptest! {
+        #[test]
+        fn test_script_round_trip(values in prop::collection::vec(value(), 0..16)) {
+            let script: Script<()> = values.into_iter().map(Item::Value).collect();
+            let printed = print(&script, &[]).unwrap();
+            let parsed: Script<()> = parse(&printed, &[]).unwrap();
+
+            prop_assert_eq!(print(&parsed, &[]).unwrap(), printed);
+            prop_assert_eq!(canonical(parsed), canonical(script));
+        }
+    }
+}
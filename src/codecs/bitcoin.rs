@@ -0,0 +1,274 @@
+//! A codec for reading and writing raw Bitcoin scripts, i.e. the byte format used by Bitcoin
+//! transactions, for the [`bitcoin`][bitcoin] operator system.
+//!
+//! Data pushes and constants are represented as `Bytes` values, and every other opcode as a
+//! [`BitcoinOperator`][BitcoinOperator]:
+//!
+//! | Opcode                         | Item                            |
+//! |--------------------------------|---------------------------------|
+//! | `OP_0`                         | `Value(Bytes([]))`              |
+//! | `0x01` to `0x4b`               | `Value(Bytes(data))`            |
+//! | `OP_PUSHDATA1/2/4`             | `Value(Bytes(data))`            |
+//! | `OP_1NEGATE`                   | `Value(Bytes([0x81]))`          |
+//! | `OP_1` to `OP_16`              | `Value(Bytes([1]))` to `Value(Bytes([16]))` |
+//! | Any other opcode               | `Operator(BitcoinOperator)`     |
+//!
+//! Values are always encoded using the shortest possible push, as required by Bitcoin's
+//! `MINIMALDATA` policy. Therefore, scripts using non-minimal pushes do not round-trip
+//! byte-for-byte, even though they are decoded into the exact same items.
+//!
+//! This module is only available when the `bitcoin` feature is enabled.
+//!
+//! [bitcoin]: ../../op_systems/bitcoin/index.html
+//! [BitcoinOperator]: ../../op_systems/bitcoin/enum.BitcoinOperator.html
+
+use alloc::vec::Vec;
+
+use crate::codecs::{DecodingError, EncodingError};
+use crate::core::value::Value;
+use crate::core::ScriptRef;
+use crate::op_systems::bitcoin::BitcoinOperator;
+use crate::prelude::*;
+
+const OP_0: u8 = 0x00;
+const OP_PUSHDATA1: u8 = 0x4c;
+const OP_PUSHDATA2: u8 = 0x4d;
+const OP_PUSHDATA4: u8 = 0x4e;
+const OP_1NEGATE: u8 = 0x4f;
+const OP_1: u8 = 0x51;
+const OP_16: u8 = 0x60;
+
+/// Encodes a script into raw Bitcoin script bytes.
+///
+/// Fails if the script contains any value other than `Bytes`, or a value that is too big to be
+/// pushed.
+pub fn to_vec(script: ScriptRef<BitcoinOperator>) -> Result<Vec<u8>, EncodingError> {
+    let mut bytes = Vec::new();
+
+    for (index, item) in script.iter().enumerate() {
+        match item {
+            Item::Operator(operator) => bytes.push(operator.opcode()),
+            Item::Value(Value::Bytes(data)) => {
+                push_data(&mut bytes, data).ok_or(EncodingError::LengthOverflow { index })?
+            }
+            Item::Value(other) => {
+                return Err(EncodingError::UnsupportedValue {
+                    index,
+                    found: other.type_name(),
+                })
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Decodes raw Bitcoin script bytes into a script.
+///
+/// Fails if a data push is truncated, i.e. if the script ends before all the bytes announced by
+/// a push opcode.
+pub fn from_slice(input: &[u8]) -> Result<Script<BitcoinOperator>, DecodingError> {
+    let mut script = Script::new();
+    let mut cursor = 0;
+
+    while let Some(&opcode) = input.get(cursor) {
+        cursor += 1;
+
+        let length = match opcode {
+            OP_0 => Some(0),
+            0x01..=0x4b => Some(opcode as usize),
+            OP_PUSHDATA1 => Some(read_length::<1>(input, &mut cursor)?),
+            OP_PUSHDATA2 => Some(read_length::<2>(input, &mut cursor)?),
+            OP_PUSHDATA4 => Some(read_length::<4>(input, &mut cursor)?),
+            _ => None,
+        };
+
+        let item = match (length, opcode) {
+            (Some(length), _) => {
+                let data = cursor
+                    .checked_add(length)
+                    .and_then(|end| input.get(cursor..end))
+                    .ok_or(DecodingError::UnexpectedEof {
+                        offset: cursor,
+                        needed: length,
+                    })?;
+                cursor += length;
+
+                Item::Value(Value::Bytes(data.into()))
+            }
+            (None, OP_1NEGATE) => Item::Value(Value::Bytes([0x81].into())),
+            (None, OP_1..=OP_16) => Item::Value(Value::Bytes([opcode - OP_1 + 1].into())),
+            (None, _) => Item::Operator(
+                BitcoinOperator::from_opcode(opcode)
+                    .expect("All opcodes other than pushes and constants are operators"),
+            ),
+        };
+
+        script.push(item);
+    }
+
+    Ok(script)
+}
+
+/// Appends the shortest possible push of `data` to `bytes`, if its length fits in a push.
+fn push_data(bytes: &mut Vec<u8>, data: &[u8]) -> Option<()> {
+    match data {
+        [] => bytes.push(OP_0),
+        [0x81] => bytes.push(OP_1NEGATE),
+        [n @ 1..=16] => bytes.push(OP_1 + n - 1),
+        _ => {
+            let length = data.len();
+            if length < OP_PUSHDATA1 as usize {
+                bytes.push(length as u8);
+            } else if let Ok(length) = u8::try_from(length) {
+                bytes.push(OP_PUSHDATA1);
+                bytes.push(length);
+            } else if let Ok(length) = u16::try_from(length) {
+                bytes.push(OP_PUSHDATA2);
+                bytes.extend_from_slice(&length.to_le_bytes());
+            } else if let Ok(length) = u32::try_from(length) {
+                bytes.push(OP_PUSHDATA4);
+                bytes.extend_from_slice(&length.to_le_bytes());
+            } else {
+                return None;
+            }
+            bytes.extend_from_slice(data);
+        }
+    }
+
+    Some(())
+}
+
+/// Reads the `N` bytes long length that follows `OP_PUSHDATA1/2/4`.
+fn read_length<const N: usize>(input: &[u8], cursor: &mut usize) -> Result<usize, DecodingError> {
+    let offset = *cursor;
+    let bytes = input
+        .get(offset..offset + N)
+        .ok_or(DecodingError::UnexpectedEof { offset, needed: N })?;
+    *cursor += N;
+
+    let mut four_bytes = [0u8; 4];
+    four_bytes[..N].copy_from_slice(bytes);
+
+    usize::try_from(u32::from_le_bytes(four_bytes))
+        .map_err(|_| DecodingError::LengthOverflow { offset })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::codecs::bitcoin::{from_slice, to_vec};
+    use crate::codecs::{DecodingError, EncodingError};
+    use crate::core::item::Item::*;
+    use crate::core::value::Value::*;
+    use crate::op_systems::bitcoin::BitcoinOperator;
+
+    #[test]
+    fn test_pay_to_pubkey_hash() {
+        let bytes = Vec::from([
+            0x76, 0xa9, 0x14, 0x89, 0xab, 0xcd, 0xef, 0xab, 0xba, 0xab, 0xba, 0xab, 0xba, 0xab,
+            0xba, 0xab, 0xba, 0xab, 0xba, 0xab, 0xba, 0xab, 0xba, 0x88, 0xac,
+        ]);
+        let script = Vec::from([
+            Operator(BitcoinOperator::Dup),
+            Operator(BitcoinOperator::Hash160),
+            Value(Bytes(bytes[3..23].into())),
+            Operator(BitcoinOperator::EqualVerify),
+            Operator(BitcoinOperator::CheckSig),
+        ]);
+
+        assert_eq!(from_slice(&bytes), Ok(script.clone()));
+        assert_eq!(to_vec(&script), Ok(bytes));
+    }
+
+    #[test]
+    fn test_constants() {
+        let bytes = Vec::from([0x00, 0x4f, 0x51, 0x60, 0x50, 0xba]);
+        let script = Vec::from([
+            Value(Bytes(Vec::new())),
+            Value(Bytes([0x81].into())),
+            Value(Bytes([1].into())),
+            Value(Bytes([16].into())),
+            Operator(BitcoinOperator::Reserved),
+            Operator(BitcoinOperator::Invalid(0xba)),
+        ]);
+
+        assert_eq!(from_slice(&bytes), Ok(script.clone()));
+        assert_eq!(to_vec(&script), Ok(bytes));
+    }
+
+    #[test]
+    fn test_pushes() {
+        for (length, prefix) in [
+            (17usize, Vec::from([0x11])),
+            (75, Vec::from([0x4b])),
+            (76, Vec::from([0x4c, 0x4c])),
+            (255, Vec::from([0x4c, 0xff])),
+            (256, Vec::from([0x4d, 0x00, 0x01])),
+            (65536, Vec::from([0x4e, 0x00, 0x00, 0x01, 0x00])),
+        ] {
+            let data = Vec::from_iter((0..length).map(|i| i as u8));
+            let bytes = [&prefix[..], &data[..]].concat();
+            let script = Vec::from([Value(Bytes(data))]);
+
+            assert_eq!(from_slice(&bytes), Ok(script.clone()));
+            assert_eq!(to_vec(&script), Ok(bytes));
+        }
+    }
+
+    #[test]
+    fn test_non_minimal_pushes() {
+        // `OP_PUSHDATA1` of a single byte, and a direct push of the number 5.
+        let script = from_slice(&[0x4c, 0x01, 0x07, 0x01, 0x05]).unwrap();
+
+        assert_eq!(
+            script,
+            Vec::from([Value(Bytes([7].into())), Value(Bytes([5].into()))])
+        );
+        assert_eq!(to_vec(&script), Ok(Vec::from([0x57, 0x55])));
+    }
+
+    #[test]
+    fn test_truncated_pushes() {
+        assert_eq!(
+            from_slice(&[0x02, 0x01]),
+            Err(DecodingError::UnexpectedEof {
+                offset: 1,
+                needed: 2
+            })
+        );
+        assert_eq!(
+            from_slice(&[0x4c]),
+            Err(DecodingError::UnexpectedEof {
+                offset: 1,
+                needed: 1
+            })
+        );
+        assert_eq!(
+            from_slice(&[0x4d, 0x01]),
+            Err(DecodingError::UnexpectedEof {
+                offset: 1,
+                needed: 2
+            })
+        );
+        assert_eq!(
+            from_slice(&[0x4e, 0xff, 0xff, 0xff, 0xff]),
+            Err(DecodingError::UnexpectedEof {
+                offset: 5,
+                needed: 0xffff_ffff
+            })
+        );
+    }
+
+    #[test]
+    fn test_non_bytes_values() {
+        assert_eq!(
+            to_vec(&[Value(Bytes([].into())), Value(Integer(1))]),
+            Err(EncodingError::UnsupportedValue {
+                index: 1,
+                found: "integer"
+            })
+        );
+    }
+}
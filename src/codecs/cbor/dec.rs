@@ -0,0 +1,463 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    codecs::{
+        cbor::{
+            f64_from_f16, CborCodec, BREAK, DOUBLE, FALSE, HALF, MAJOR_ARRAY, MAJOR_BYTES,
+            MAJOR_MAP, MAJOR_NEGATIVE, MAJOR_SIMPLE, MAJOR_TAG, MAJOR_TEXT, MAJOR_UNSIGNED, NULL,
+            OPERATOR_TAG, SINGLE, TAG_DECIMAL_FRACTION, TAG_EPOCH, TAG_NEGATIVE_BIGNUM,
+            TAG_POSITIVE_BIGNUM, TRUE,
+        },
+        dec::{Decode, Decoder},
+        DecodingError, OperatorInfo,
+    },
+    core::{decimal::Decimal, value::Value},
+    prelude::*,
+};
+
+impl Decoder for &mut CborCodec {
+    type Error = DecodingError;
+
+    fn decode_i128(&mut self) -> Result<i128, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::Integer(integer) => Ok(integer),
+            other => Err(unexpected_type(offset, "integer", &other)),
+        }
+    }
+
+    fn decode_f64(&mut self) -> Result<f64, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::Float(float) => Ok(float),
+            other => Err(unexpected_type(offset, "float", &other)),
+        }
+    }
+
+    fn decode_string(&mut self) -> Result<String, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::String(string) => Ok(string),
+            other => Err(unexpected_type(offset, "string", &other)),
+        }
+    }
+
+    fn decode_bytes(&mut self) -> Result<Vec<u8>, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::Bytes(bytes) => Ok(bytes),
+            other => Err(unexpected_type(offset, "bytes", &other)),
+        }
+    }
+
+    fn decode_value(&mut self) -> Result<Value, Self::Error> {
+        let offset = self.cursor;
+        let (initial, argument) = self.read_head()?;
+
+        match (initial >> 5, argument) {
+            (MAJOR_UNSIGNED, Some(argument)) => Ok(Value::Integer(i128::from(argument))),
+            (MAJOR_NEGATIVE, Some(argument)) => Ok(Value::Integer(-1 - i128::from(argument))),
+            (MAJOR_BYTES, length) => self.read_string(MAJOR_BYTES, length).map(Value::Bytes),
+            (MAJOR_TEXT, length) => {
+                let bytes = self.read_string(MAJOR_TEXT, length)?;
+                // Every chunk has already been checked to be valid UTF-8 on its own.
+                String::from_utf8(bytes)
+                    .map(Value::String)
+                    .map_err(|_| DecodingError::InvalidUtf8 { offset })
+            }
+            (MAJOR_TAG, Some(TAG_EPOCH)) => {
+                let offset = self.cursor;
+                let (initial, argument) = self.read_head()?;
+                let seconds = match (initial >> 5, argument) {
+                    (MAJOR_UNSIGNED, Some(argument)) => i128::from(argument),
+                    (MAJOR_NEGATIVE, Some(argument)) => -1 - i128::from(argument),
+                    _ => {
+                        return Err(DecodingError::InvalidDiscriminant {
+                            offset,
+                            byte: initial,
+                        })
+                    }
+                };
+
+                i64::try_from(seconds)
+                    .map(Value::Timestamp)
+                    .map_err(|_| DecodingError::OutOfRange { offset })
+            }
+            (MAJOR_TAG, Some(TAG_POSITIVE_BIGNUM)) => self.read_bignum(false),
+            (MAJOR_TAG, Some(TAG_NEGATIVE_BIGNUM)) => self.read_bignum(true),
+            (MAJOR_TAG, Some(TAG_DECIMAL_FRACTION)) => {
+                self.read_decimal(offset).map(Value::Decimal)
+            }
+            (MAJOR_SIMPLE, argument) => match (initial, argument) {
+                (FALSE, _) => Ok(Value::Boolean(false)),
+                (TRUE, _) => Ok(Value::Boolean(true)),
+                (NULL, _) => Ok(Value::Null),
+                (HALF, Some(bits)) => Ok(Value::Float(f64_from_f16(bits as u16))),
+                (SINGLE, Some(bits)) => Ok(Value::Float(f64::from(f32::from_bits(bits as u32)))),
+                (DOUBLE, Some(bits)) => Ok(Value::Float(f64::from_bits(bits))),
+                _ => Err(DecodingError::InvalidDiscriminant {
+                    offset,
+                    byte: initial,
+                }),
+            },
+            _ => Err(DecodingError::InvalidDiscriminant {
+                offset,
+                byte: initial,
+            }),
+        }
+    }
+
+    fn decode_operator(&mut self, operators: &[OperatorInfo]) -> Result<u8, Self::Error> {
+        let offset = self.cursor;
+        let (initial, argument) = self.read_head()?;
+        if initial >> 5 != MAJOR_TAG || argument != Some(OPERATOR_TAG) {
+            return Err(DecodingError::InvalidDiscriminant {
+                offset,
+                byte: initial,
+            });
+        }
+
+        // Operators with fields wrap an array, whose first element is the opcode.
+        let wrapper_offset = self.cursor;
+        let (wrapper_initial, wrapper_length) = self.peek_head()?;
+        let wrapped = wrapper_initial >> 5 == MAJOR_ARRAY;
+        if wrapped {
+            self.read_head()?;
+        }
+
+        let offset = self.cursor;
+        let (initial, argument) = self.read_head()?;
+        let opcode = match (initial >> 5, argument) {
+            (MAJOR_UNSIGNED, Some(opcode)) => {
+                u8::try_from(opcode).map_err(|_| DecodingError::OutOfRange { offset })?
+            }
+            _ => {
+                return Err(DecodingError::InvalidDiscriminant {
+                    offset,
+                    byte: initial,
+                })
+            }
+        };
+        let operator = operators
+            .iter()
+            .find(|operator| operator.opcode == opcode)
+            .ok_or(DecodingError::UnknownOperator { offset })?;
+
+        // The array, if any, must hold exactly the opcode and the fields of the operator.
+        let expected_length = (operator.fields > 0).then_some(Some(operator.fields as u64 + 1));
+        let found_length = wrapped.then_some(wrapper_length);
+        if found_length != expected_length {
+            return Err(DecodingError::InvalidDiscriminant {
+                offset: wrapper_offset,
+                byte: wrapper_initial,
+            });
+        }
+
+        Ok(opcode)
+    }
+
+    fn decode_item<Op, Val>(&mut self) -> Result<Item<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        let (initial, argument) = self.peek_head()?;
+        if initial >> 5 == MAJOR_TAG && argument == Some(OPERATOR_TAG) {
+            Op::decode(&mut *self).map(Item::Operator)
+        } else {
+            Val::decode(&mut *self).map(Item::Value)
+        }
+    }
+
+    fn decode_script<Op, Val>(&mut self) -> Result<Script<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        let offset = self.cursor;
+        let (initial, length) = self.read_head()?;
+        if initial >> 5 != MAJOR_ARRAY {
+            return Err(DecodingError::InvalidDiscriminant {
+                offset,
+                byte: initial,
+            });
+        }
+
+        // The length is not trusted for allocating memory, as it may be arbitrarily large.
+        let mut script = Script::<Op, Val>::new();
+
+        match length {
+            Some(length) => {
+                for _ in 0..length {
+                    script.push(self.decode_item()?);
+                }
+            }
+            None => {
+                while *self.peek_byte()? != BREAK {
+                    script.push(self.decode_item()?);
+                }
+                self.read_byte()?;
+            }
+        }
+
+        Ok(script)
+    }
+
+    fn from_vec<Op, Val>(input: Vec<u8>) -> Result<Script<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        let mut codec = CborCodec::from_data(input);
+        let script = <&mut CborCodec as Decoder>::decode_script(&mut &mut codec)?;
+
+        if codec.bytes_left() > 0 {
+            return Err(DecodingError::TrailingBytes {
+                offset: codec.cursor,
+            });
+        }
+
+        Ok(script)
+    }
+
+    fn position(&self) -> usize {
+        self.cursor
+    }
+
+    fn peek_byte(&self) -> Result<&u8, Self::Error> {
+        self.data
+            .get(self.cursor)
+            .ok_or(DecodingError::UnexpectedEof {
+                offset: self.cursor,
+                needed: 1,
+            })
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Self::Error> {
+        let byte = *self.peek_byte()?;
+        self.cursor += 1;
+
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&[u8], Self::Error> {
+        let start = self.cursor;
+        let bytes = start
+            .checked_add(length)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or(DecodingError::UnexpectedEof {
+                offset: start,
+                needed: length,
+            })?;
+        self.cursor += length;
+
+        Ok(bytes)
+    }
+}
+
+impl CborCodec {
+    /// Reads the initial byte of a data item, together with its argument, which is `None` for
+    /// indefinite lengths and for the "break" stop code.
+    fn read_head(&mut self) -> Result<(u8, Option<u64>), DecodingError> {
+        let offset = self.cursor;
+        let mut codec = self;
+        let initial = codec.read_byte()?;
+
+        let argument = match initial & 0x1f {
+            info @ 0..=23 => Some(u64::from(info)),
+            info @ 24..=27 => {
+                let length = 1 << (info - 24);
+                let mut eight_bytes = [0u8; 8];
+                eight_bytes[8 - length..].copy_from_slice(codec.read_bytes(length)?);
+
+                Some(u64::from_be_bytes(eight_bytes))
+            }
+            31 if matches!(
+                initial >> 5,
+                MAJOR_BYTES | MAJOR_TEXT | MAJOR_ARRAY | MAJOR_MAP | MAJOR_SIMPLE
+            ) =>
+            {
+                None
+            }
+            _ => {
+                return Err(DecodingError::InvalidDiscriminant {
+                    offset,
+                    byte: initial,
+                })
+            }
+        };
+
+        Ok((initial, argument))
+    }
+
+    /// Same as [`read_head`][CborCodec::read_head], but leaves the cursor untouched.
+    fn peek_head(&mut self) -> Result<(u8, Option<u64>), DecodingError> {
+        let cursor = self.cursor;
+        let head = self.read_head();
+        self.cursor = cursor;
+
+        head
+    }
+
+    /// Reads the contents of a byte or text string of `length` bytes, or, if the length is
+    /// indefinite, the concatenation of the chunks that follow until the "break" stop code.
+    fn read_string(&mut self, major: u8, length: Option<u64>) -> Result<Vec<u8>, DecodingError> {
+        let mut contents = Vec::new();
+
+        if let Some(length) = length {
+            self.read_chunk(major, length, &mut contents)?;
+
+            return Ok(contents);
+        }
+
+        loop {
+            let offset = self.cursor;
+            let (initial, argument) = self.read_head()?;
+
+            match argument {
+                None if initial == BREAK => return Ok(contents),
+                Some(length) if initial >> 5 == major => {
+                    self.read_chunk(major, length, &mut contents)?
+                }
+                _ => {
+                    return Err(DecodingError::InvalidDiscriminant {
+                        offset,
+                        byte: initial,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Appends a single chunk of a byte or text string to `contents`, making sure that text is
+    /// valid UTF-8.
+    fn read_chunk(
+        &mut self,
+        major: u8,
+        length: u64,
+        contents: &mut Vec<u8>,
+    ) -> Result<(), DecodingError> {
+        let offset = self.cursor;
+        let length =
+            usize::try_from(length).map_err(|_| DecodingError::LengthOverflow { offset })?;
+        let mut codec = self;
+        let bytes = codec.read_bytes(length)?;
+
+        if major == MAJOR_TEXT && core::str::from_utf8(bytes).is_err() {
+            return Err(DecodingError::InvalidUtf8 { offset });
+        }
+        contents.extend_from_slice(bytes);
+
+        Ok(())
+    }
+
+    /// Reads the byte string of a bignum, which is decoded as an `Integer` if it fits into an
+    /// `i128`, or as a `BigInt` otherwise.
+    fn read_bignum(&mut self, negative: bool) -> Result<Value, DecodingError> {
+        let offset = self.cursor;
+        let (initial, length) = self.read_head()?;
+        if initial >> 5 != MAJOR_BYTES {
+            return Err(DecodingError::InvalidDiscriminant {
+                offset,
+                byte: initial,
+            });
+        }
+        let bytes = self.read_string(MAJOR_BYTES, length)?;
+
+        // Leading zeros are allowed, so they need to be skipped before checking the length.
+        let significant = bytes
+            .iter()
+            .position(|byte| *byte != 0)
+            .map_or(&bytes[..0], |start| &bytes[start..]);
+
+        if significant.len() <= 16 {
+            let mut sixteen_bytes = [0u8; 16];
+            sixteen_bytes[16 - significant.len()..].copy_from_slice(significant);
+
+            if let Ok(magnitude) = i128::try_from(u128::from_be_bytes(sixteen_bytes)) {
+                let integer = if negative { -1 - magnitude } else { magnitude };
+
+                return Ok(Value::Integer(integer));
+            }
+        }
+
+        #[cfg(feature = "bigint")]
+        {
+            let magnitude = num_bigint::BigInt::from_bytes_be(num_bigint::Sign::Plus, significant);
+            let bigint = if negative { -1 - magnitude } else { magnitude };
+
+            Ok(Value::BigInt(bigint))
+        }
+        #[cfg(not(feature = "bigint"))]
+        Err(DecodingError::OutOfRange { offset })
+    }
+
+    /// Reads an integer that must fit into an `i128`, be it a plain integer or a bignum.
+    fn read_integer(&mut self) -> Result<i128, DecodingError> {
+        let offset = self.cursor;
+        let (initial, argument) = self.read_head()?;
+
+        let value = match (initial >> 5, argument) {
+            (MAJOR_UNSIGNED, Some(argument)) => Value::Integer(i128::from(argument)),
+            (MAJOR_NEGATIVE, Some(argument)) => Value::Integer(-1 - i128::from(argument)),
+            (MAJOR_TAG, Some(TAG_POSITIVE_BIGNUM)) => self.read_bignum(false)?,
+            (MAJOR_TAG, Some(TAG_NEGATIVE_BIGNUM)) => self.read_bignum(true)?,
+            _ => {
+                return Err(DecodingError::InvalidDiscriminant {
+                    offset,
+                    byte: initial,
+                })
+            }
+        };
+
+        match value {
+            Value::Integer(integer) => Ok(integer),
+            _ => Err(DecodingError::OutOfRange { offset }),
+        }
+    }
+
+    /// Reads the `[exponent, mantissa]` array of a decimal fraction whose tag started at `offset`.
+    ///
+    /// Positive exponents are supported as long as the resulting decimal does not overflow.
+    fn read_decimal(&mut self, offset: usize) -> Result<Decimal, DecodingError> {
+        let array_offset = self.cursor;
+        let (initial, length) = self.read_head()?;
+        if initial >> 5 != MAJOR_ARRAY || length != Some(2) {
+            return Err(DecodingError::InvalidDiscriminant {
+                offset: array_offset,
+                byte: initial,
+            });
+        }
+
+        let exponent = self.read_integer()?;
+        let mantissa = self.read_integer()?;
+
+        let decimal = if exponent > 0 {
+            u32::try_from(exponent)
+                .ok()
+                .and_then(|exponent| 10i128.checked_pow(exponent))
+                .and_then(|factor| mantissa.checked_mul(factor))
+                .and_then(|mantissa| Decimal::new(mantissa, 0))
+        } else {
+            exponent
+                .checked_neg()
+                .and_then(|scale| u8::try_from(scale).ok())
+                .and_then(|scale| Decimal::new(mantissa, scale))
+        };
+
+        decimal.ok_or(DecodingError::OutOfRange { offset })
+    }
+}
+
+fn unexpected_type(offset: usize, expected: &'static str, found: &Value) -> DecodingError {
+    DecodingError::UnexpectedType {
+        offset,
+        expected,
+        found: found.type_name(),
+    }
+}
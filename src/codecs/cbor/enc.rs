@@ -0,0 +1,196 @@
+use alloc::vec::Vec;
+
+use crate::{
+    codecs::{
+        cbor::{
+            f16_from_f64, CborCodec, DOUBLE, FALSE, HALF, MAJOR_ARRAY, MAJOR_BYTES, MAJOR_NEGATIVE,
+            MAJOR_TAG, MAJOR_TEXT, MAJOR_UNSIGNED, NULL, OPERATOR_TAG, SINGLE,
+            TAG_DECIMAL_FRACTION, TAG_EPOCH, TAG_NEGATIVE_BIGNUM, TAG_POSITIVE_BIGNUM, TRUE,
+        },
+        enc::{Encode, EncodeSequence, Encoder},
+        EncodingError, OperatorInfo,
+    },
+    core::value::Value,
+    prelude::*,
+};
+
+impl Encoder for &mut CborCodec {
+    type Ok = ();
+    type Error = EncodingError;
+    type EncodeSequence = Self;
+
+    fn to_vec<Op, Val>(input: &Script<Op, Val>) -> Result<Vec<u8>, Self::Error>
+    where
+        Op: core::fmt::Debug + Encode,
+        Val: core::fmt::Debug + Encode,
+    {
+        let mut codec = CborCodec::default();
+        input.encode(&mut codec)?;
+
+        Ok(codec.data())
+    }
+
+    fn write_u8(self, input: u8) -> Result<Self::Ok, Self::Error> {
+        self.data.push(input);
+
+        Ok(())
+    }
+
+    fn write_bytes(self, input: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.data.extend_from_slice(input);
+
+        Ok(())
+    }
+
+    fn encode_value(self, input: &Value) -> Result<Self::Ok, Self::Error> {
+        match input {
+            Value::Boolean(false) => self.data.push(FALSE),
+            Value::Boolean(true) => self.data.push(TRUE),
+            Value::Integer(val) => self.write_integer(*val),
+            Value::Float(val) => self.write_float(*val),
+            Value::String(val) => {
+                self.write_head(MAJOR_TEXT, val.len() as u64);
+                self.data.extend_from_slice(val.as_bytes());
+            }
+            Value::Null => self.data.push(NULL),
+            Value::Decimal(val) => {
+                self.write_head(MAJOR_TAG, TAG_DECIMAL_FRACTION);
+                self.write_head(MAJOR_ARRAY, 2);
+                self.write_integer(-i128::from(val.scale()));
+                self.write_integer(val.mantissa());
+            }
+            Value::Bytes(val) => {
+                self.write_head(MAJOR_BYTES, val.len() as u64);
+                self.data.extend_from_slice(val);
+            }
+            Value::Timestamp(val) => {
+                self.write_head(MAJOR_TAG, TAG_EPOCH);
+                self.write_integer(i128::from(*val));
+            }
+            #[cfg(feature = "bigint")]
+            Value::BigInt(val) => self.write_bigint(val),
+        }
+
+        Ok(())
+    }
+
+    /// Operators are encoded as their opcode wrapped in [`OPERATOR_TAG`][OPERATOR_TAG], or as an
+    /// array with the opcode followed by the fields, if there are any.
+    ///
+    /// [OPERATOR_TAG]: constant.OPERATOR_TAG.html
+    fn encode_operator(self, operator: &OperatorInfo) -> Result<Self::EncodeSequence, Self::Error> {
+        self.write_head(MAJOR_TAG, OPERATOR_TAG);
+        if operator.fields > 0 {
+            self.write_head(MAJOR_ARRAY, operator.fields as u64 + 1);
+        }
+        self.write_head(MAJOR_UNSIGNED, u64::from(operator.opcode));
+
+        Ok(self)
+    }
+
+    fn encode_item<Op, Val>(self, input: &Item<Op, Val>) -> Result<Self::Ok, Self::Error>
+    where
+        Op: core::fmt::Debug + Encode,
+        Val: core::fmt::Debug + Encode,
+    {
+        input.encode(self)
+    }
+
+    /// Sequences are encoded as definite-length arrays.
+    fn encode_seq(self, length: usize) -> Result<Self::EncodeSequence, Self::Error> {
+        self.write_head(MAJOR_ARRAY, length as u64);
+
+        Ok(self)
+    }
+}
+
+impl EncodeSequence for &mut CborCodec {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn encode_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Encode + Sized,
+    {
+        value.encode(&mut **self)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+impl CborCodec {
+    /// Writes the initial byte of a data item of `major` type, followed by its `argument` in the
+    /// fewest possible bytes.
+    fn write_head(&mut self, major: u8, argument: u64) {
+        let bytes = argument.to_be_bytes();
+        let (info, length) = match argument {
+            0..=23 => (argument as u8, 0),
+            24..=0xff => (24, 1),
+            0x100..=0xffff => (25, 2),
+            0x1_0000..=0xffff_ffff => (26, 4),
+            _ => (27, 8),
+        };
+
+        self.data.push(major << 5 | info);
+        self.data.extend_from_slice(&bytes[8 - length..]);
+    }
+
+    /// Integers that do not fit into 64 bits are encoded as bignums, i.e. tagged byte strings with
+    /// the big endian magnitude of the integer, without leading zeros.
+    fn write_integer(&mut self, input: i128) {
+        let (major, tag, magnitude) = if input >= 0 {
+            (MAJOR_UNSIGNED, TAG_POSITIVE_BIGNUM, input as u128)
+        } else {
+            // CBOR encodes negative integers as `-1 - n`, which cannot overflow for `i128::MIN`.
+            (MAJOR_NEGATIVE, TAG_NEGATIVE_BIGNUM, (-1 - input) as u128)
+        };
+
+        match u64::try_from(magnitude) {
+            Ok(argument) => self.write_head(major, argument),
+            Err(_) => {
+                let bytes = magnitude.to_be_bytes();
+                let leading_zeros = magnitude.leading_zeros() as usize / 8;
+
+                self.write_head(MAJOR_TAG, tag);
+                self.write_head(MAJOR_BYTES, (16 - leading_zeros) as u64);
+                self.data.extend_from_slice(&bytes[leading_zeros..]);
+            }
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    fn write_bigint(&mut self, input: &num_bigint::BigInt) {
+        if let Ok(integer) = i128::try_from(input) {
+            return self.write_integer(integer);
+        }
+
+        let (tag, magnitude) = if input.sign() == num_bigint::Sign::Minus {
+            (TAG_NEGATIVE_BIGNUM, -input - 1u8)
+        } else {
+            (TAG_POSITIVE_BIGNUM, input.clone())
+        };
+        let (_, bytes) = magnitude.to_bytes_be();
+
+        self.write_head(MAJOR_TAG, tag);
+        self.write_head(MAJOR_BYTES, bytes.len() as u64);
+        self.data.extend_from_slice(&bytes);
+    }
+
+    /// Floats are encoded in the shortest precision that represents them exactly.
+    fn write_float(&mut self, input: f64) {
+        let single = input as f32;
+
+        if let Some(half) = f16_from_f64(input) {
+            self.data.push(HALF);
+            self.data.extend_from_slice(&half.to_be_bytes());
+        } else if f64::from(single) == input {
+            self.data.push(SINGLE);
+            self.data.extend_from_slice(&single.to_be_bytes());
+        } else {
+            self.data.push(DOUBLE);
+            self.data.extend_from_slice(&input.to_be_bytes());
+        }
+    }
+}
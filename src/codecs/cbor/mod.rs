@@ -0,0 +1,777 @@
+//! A codec for exchanging scripts as [CBOR] (RFC 8949), so that they can be produced and consumed
+//! by any of the many CBOR implementations out there, in any language.
+//!
+//! A script is encoded as a CBOR array of items. Values are mapped to the closest CBOR data item:
+//!
+//! | Value       | CBOR                                                                          |
+//! |-------------|-------------------------------------------------------------------------------|
+//! | `Boolean`   | `false` or `true` (major type 7).                                             |
+//! | `Integer`   | Unsigned or negative integer (major types 0 and 1), or a bignum (tags 2 and 3) if it does not fit into 64 bits. |
+//! | `BigInt`    | Same as `Integer`.                                                            |
+//! | `Float`     | Half, single or double precision float (major type 7).                        |
+//! | `String`    | Text string (major type 3).                                                   |
+//! | `Null`      | `null` (major type 7).                                                        |
+//! | `Decimal`   | Decimal fraction (tag 4), i.e. `[exponent, mantissa]` with `exponent = -scale`. |
+//! | `Bytes`     | Byte string (major type 2).                                                   |
+//! | `Timestamp` | Epoch-based date/time (tag 1) with an integer number of seconds.              |
+//!
+//! Operators have no counterpart in CBOR, so they are encoded as their opcode wrapped in tag
+//! [`OPERATOR_TAG`][OPERATOR_TAG], which tells them apart from values. Operators carrying fields,
+//! such as those derived with `#[derive(Encode)]`, wrap an array with the opcode followed by the
+//! fields instead. Opcodes are the same ones used by the [simple codec][simple], e.g. `MathOperator::Add`
+//! is `0x80`:
+//!
+//! | Item                                 | CBOR                       | Diagnostic notation  |
+//! |--------------------------------------|----------------------------|----------------------|
+//! | `Operator(MathOperator::Add)`        | `D9C0DE1880`               | `49374(128)`         |
+//! | `Operator(PushTwice(Integer(1)))`    | `D9C0DE8218B001`           | `49374([176, 1])`    |
+//!
+//! Encoders always use the preferred serialization described in section 4.1 of RFC 8949: integers,
+//! lengths and tags take the fewest possible bytes, and floats take the shortest precision that
+//! represents them exactly. Decoders, however, accept any well-formed encoding of the supported
+//! data items, including indefinite-length arrays and strings. As integers and big integers share
+//! the same representation, `BigInt` values that fit into an `i128` are decoded as `Integer`.
+//! Any other big integer can only be decoded when the `bigint` feature is enabled.
+//!
+//! Maps, nested arrays, `undefined`, other simple values and tags other than the ones above are
+//! rejected with a [`DecodingError`][DecodingError]. Decoding never panics, no matter the input.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scriptful::codecs::cbor::CborCodec;
+//! use scriptful::codecs::dec::Decoder;
+//! use scriptful::codecs::enc::Encoder;
+//! use scriptful::core::value::Value;
+//! use scriptful::op_systems::simple_math::MathOperator;
+//! use scriptful::prelude::*;
+//!
+//! let script = Vec::from([
+//!     Item::Value(Value::Integer(1)),
+//!     Item::Value(Value::Float(1.5)),
+//!     Item::Operator(MathOperator::Add),
+//! ]);
+//! let encoded = <&mut CborCodec>::to_vec(&script).unwrap();
+//! assert_eq!(encoded, Vec::from([0x83, 0x01, 0xf9, 0x3e, 0x00, 0xd9, 0xc0, 0xde, 0x18, 0x80]));
+//!
+//! let decoded: Script<MathOperator> = <&mut CborCodec>::from_vec(encoded).unwrap();
+//! assert_eq!(decoded, script);
+//! ```
+//!
+//! [CBOR]: https://www.rfc-editor.org/rfc/rfc8949
+//! [OPERATOR_TAG]: constant.OPERATOR_TAG.html
+//! [simple]: ../simple/index.html
+//! [DecodingError]: ../enum.DecodingError.html
+
+use alloc::vec::Vec;
+
+use crate::codecs::dec::Decode;
+use crate::codecs::DecodingError;
+
+pub mod dec;
+pub mod enc;
+
+/// The tag that wraps operators, so that they can be told apart from values.
+///
+/// This is `49374`, i.e. `0xC0DE`, from the first come first served range of the IANA registry of
+/// CBOR tags. It is not registered, so it is only meaningful to parties using this codec.
+pub const OPERATOR_TAG: u64 = 0xc0de;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const TAG_EPOCH: u64 = 1;
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+const TAG_DECIMAL_FRACTION: u64 = 4;
+
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+const NULL: u8 = 0xf6;
+const HALF: u8 = 0xf9;
+const SINGLE: u8 = 0xfa;
+const DOUBLE: u8 = 0xfb;
+const BREAK: u8 = 0xff;
+
+/// Encodes and decodes scripts as CBOR, as described in the [module documentation](index.html).
+#[derive(Default)]
+pub struct CborCodec {
+    data: Vec<u8>,
+    cursor: usize,
+}
+
+impl CborCodec {
+    pub fn data(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub fn from_data(data: Vec<u8>) -> Self {
+        Self { data, cursor: 0 }
+    }
+
+    pub fn bytes_left(&self) -> usize {
+        self.data.len() - self.cursor
+    }
+
+    /// Decodes a single data item that must take up the whole of `data`, e.g. a [`Value`][Value]
+    /// received on its own rather than as part of a script.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::codecs::cbor::CborCodec;
+    /// use scriptful::codecs::DecodingError;
+    /// use scriptful::core::value::Value;
+    ///
+    /// let value: Result<Value, _> = CborCodec::decode_exact(Vec::from([0x18, 0xff]));
+    /// assert_eq!(value, Ok(Value::Integer(255)));
+    ///
+    /// let value: Result<Value, _> = CborCodec::decode_exact(Vec::from([0xf5, 0xf5]));
+    /// assert_eq!(value, Err(DecodingError::TrailingBytes { offset: 1 }));
+    /// ```
+    ///
+    /// [Value]: ../../core/value/enum.Value.html
+    pub fn decode_exact<T>(data: Vec<u8>) -> Result<T, DecodingError>
+    where
+        T: Decode,
+    {
+        let mut codec = CborCodec::from_data(data);
+        let decoded = T::decode(&mut &mut codec)?;
+
+        if codec.bytes_left() > 0 {
+            return Err(DecodingError::TrailingBytes {
+                offset: codec.cursor,
+            });
+        }
+
+        Ok(decoded)
+    }
+}
+
+/// Converts a float into the bits of a half precision float, as long as no precision is lost.
+///
+/// All `NaN`s are converted into the same quiet `NaN`, as preferred by RFC 8949.
+fn f16_from_f64(input: f64) -> Option<u16> {
+    if input.is_nan() {
+        return Some(0x7e00);
+    }
+
+    let single = input as f32;
+    if f64::from(single) != input {
+        return None;
+    }
+
+    let bits = single.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    match (exponent, mantissa) {
+        // Zero.
+        (0, 0) => Some(sign),
+        // Infinity, as `NaN` was already ruled out.
+        (0xff, _) => Some(sign | 0x7c00),
+        _ => match exponent - 127 {
+            // Normal half precision floats, which have 13 fewer bits of mantissa.
+            unbiased @ -14..=15 if mantissa & 0x1fff == 0 => {
+                Some(sign | ((unbiased + 15) as u16) << 10 | (mantissa >> 13) as u16)
+            }
+            // Subnormal half precision floats, which are multiples of `2^-24`.
+            unbiased @ -24..=-15 => {
+                let significand = mantissa | 0x80_0000;
+                let shift = (-1 - unbiased) as u32;
+
+                if significand & ((1 << shift) - 1) == 0 {
+                    Some(sign | (significand >> shift) as u16)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Converts the bits of a half precision float into a float.
+fn f64_from_f16(input: u16) -> f64 {
+    let sign = if input & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = u64::from((input >> 10) & 0x1f);
+    let mantissa = u64::from(input & 0x3ff);
+
+    let magnitude = match exponent {
+        0 => mantissa as f64 / 16_777_216.0,
+        0x1f if mantissa == 0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        // Rebias the exponent from 15 to 1023, and widen the mantissa from 10 to 52 bits.
+        _ => f64::from_bits((exponent + 1008) << 52 | mantissa << 42),
+    };
+
+    sign * magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::codecs::cbor::CborCodec;
+    use crate::codecs::dec::Decoder;
+    use crate::codecs::enc::{Encode, Encoder};
+    use crate::codecs::DecodingError;
+    use crate::core::decimal::Decimal;
+    use crate::core::value::{CanonicalValue, Value};
+    use crate::op_systems::simple_math::MathOperator;
+    use crate::prelude::*;
+    use crate::test_utils::hex;
+
+    fn encode_value(value: &Value) -> Vec<u8> {
+        let mut codec = CborCodec::default();
+        value.encode(&mut codec).unwrap();
+
+        codec.data()
+    }
+
+    /// Checks that `value` is encoded exactly as `encoded`, and that decoding it back yields the
+    /// very same value.
+    fn assert_round_trip(value: Value, encoded: &str) {
+        assert_eq!(encode_value(&value), hex(encoded), "encoding {:?}", value);
+
+        let decoded: Value = CborCodec::decode_exact(hex(encoded)).unwrap();
+        assert_eq!(
+            CanonicalValue(decoded),
+            CanonicalValue(value),
+            "decoding {}",
+            encoded
+        );
+    }
+
+    fn decode(encoded: &str) -> Result<Value, DecodingError> {
+        CborCodec::decode_exact(hex(encoded))
+    }
+
+    #[test]
+    fn test_rfc_integers() {
+        let vectors: [(i128, &str); 20] = [
+            (0, "00"),
+            (1, "01"),
+            (10, "0a"),
+            (23, "17"),
+            (24, "1818"),
+            (25, "1819"),
+            (100, "1864"),
+            (1000, "1903e8"),
+            (1000000, "1a000f4240"),
+            (1000000000000, "1b000000e8d4a51000"),
+            (18446744073709551615, "1bffffffffffffffff"),
+            (18446744073709551616, "c249010000000000000000"),
+            (-18446744073709551616, "3bffffffffffffffff"),
+            (-18446744073709551617, "c349010000000000000000"),
+            (-1, "20"),
+            (-10, "29"),
+            (-100, "3863"),
+            (-1000, "3903e7"),
+            (i128::MAX, "c2507fffffffffffffffffffffffffffffff"),
+            (i128::MIN, "c3507fffffffffffffffffffffffffffffff"),
+        ];
+
+        for (integer, encoded) in vectors {
+            assert_round_trip(Value::Integer(integer), encoded);
+        }
+    }
+
+    #[test]
+    fn test_rfc_floats() {
+        let vectors: [(f64, &str); 18] = [
+            (0.0, "f90000"),
+            (-0.0, "f98000"),
+            (1.0, "f93c00"),
+            (1.1, "fb3ff199999999999a"),
+            (1.5, "f93e00"),
+            (65504.0, "f97bff"),
+            (100000.0, "fa47c35000"),
+            (3.4028234663852886e+38, "fa7f7fffff"),
+            (1.0e+300, "fb7e37e43c8800759c"),
+            (5.960464477539063e-8, "f90001"),
+            (0.00006103515625, "f90400"),
+            (-4.0, "f9c400"),
+            (-4.1, "fbc010666666666666"),
+            (f64::INFINITY, "f97c00"),
+            (f64::NAN, "f97e00"),
+            (f64::NEG_INFINITY, "f9fc00"),
+            // Not from the RFC: the largest subnormal and the smallest normal half floats.
+            (6.097555160522461e-5, "f903ff"),
+            (-0.0000152587890625, "f98100"),
+        ];
+
+        for (float, encoded) in vectors {
+            assert_round_trip(Value::Float(float), encoded);
+        }
+
+        // Non-preferred serializations, which decode to the same floats.
+        let vectors: [(f64, &str); 7] = [
+            (f64::INFINITY, "fa7f800000"),
+            (f64::NAN, "fa7fc00000"),
+            (f64::NEG_INFINITY, "faff800000"),
+            (f64::INFINITY, "fb7ff0000000000000"),
+            (f64::NAN, "fb7ff8000000000000"),
+            (f64::NEG_INFINITY, "fbfff0000000000000"),
+            (1.5, "fb3ff8000000000000"),
+        ];
+
+        for (float, encoded) in vectors {
+            let decoded = decode(encoded).unwrap();
+            assert_eq!(CanonicalValue(decoded), CanonicalValue(Value::Float(float)));
+        }
+    }
+
+    #[test]
+    fn test_rfc_simple_values() {
+        assert_round_trip(Value::Boolean(false), "f4");
+        assert_round_trip(Value::Boolean(true), "f5");
+        assert_round_trip(Value::Null, "f6");
+
+        // `undefined`, `simple(16)`, `simple(255)` and `simple(24)` have no counterpart.
+        for (encoded, byte) in [("f7", 0xf7), ("f0", 0xf0), ("f8ff", 0xf8), ("f818", 0xf8)] {
+            assert_eq!(
+                decode(encoded),
+                Err(DecodingError::InvalidDiscriminant { offset: 0, byte })
+            );
+        }
+    }
+
+    #[test]
+    fn test_rfc_strings() {
+        assert_round_trip(Value::Bytes(Vec::new()), "40");
+        assert_round_trip(Value::Bytes(Vec::from([1, 2, 3, 4])), "4401020304");
+        assert_round_trip(Value::String(String::new()), "60");
+        assert_round_trip(Value::String("a".into()), "6161");
+        assert_round_trip(Value::String("IETF".into()), "6449455446");
+        assert_round_trip(Value::String("\"\\".into()), "62225c");
+        assert_round_trip(Value::String("\u{00fc}".into()), "62c3bc");
+        assert_round_trip(Value::String("\u{6c34}".into()), "63e6b0b4");
+        assert_round_trip(Value::String("\u{10151}".into()), "64f0908591");
+
+        // Indefinite-length strings are the concatenation of their chunks.
+        assert_eq!(
+            decode("5f42010243030405ff"),
+            Ok(Value::Bytes(Vec::from([1, 2, 3, 4, 5])))
+        );
+        assert_eq!(
+            decode("7f657374726561646d696e67ff"),
+            Ok(Value::String("streaming".into()))
+        );
+        assert_eq!(decode("5fff"), Ok(Value::Bytes(Vec::new())));
+    }
+
+    #[test]
+    fn test_rfc_tags() {
+        // Epoch-based date/time.
+        assert_round_trip(Value::Timestamp(1363896240), "c11a514b67b0");
+        assert_round_trip(Value::Timestamp(-1), "c120");
+        // Fractional seconds are not supported.
+        assert_eq!(
+            decode("c1fb41d452d9ec200000"),
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 1,
+                byte: 0xfb
+            })
+        );
+        // Neither are standard date/time strings, nor any other tag.
+        assert_eq!(
+            decode("c074323031332d30332d32315432303a30343a30305a"),
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 0,
+                byte: 0xc0
+            })
+        );
+        assert_eq!(
+            decode("d74401020304"),
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 0,
+                byte: 0xd7
+            })
+        );
+
+        // Decimal fractions, including the example in section 3.4.4 of the RFC.
+        assert_round_trip(
+            Value::Decimal(Decimal::new(27315, 2).unwrap()),
+            "c48221196ab3",
+        );
+        assert_round_trip(
+            Value::Decimal(Decimal::new(-1234, 2).unwrap()),
+            "c482213904d1",
+        );
+        assert_round_trip(
+            Value::Decimal(Decimal::new(i128::MIN, Decimal::MAX_SCALE).unwrap()),
+            "c4823825c3507fffffffffffffffffffffffffffffff",
+        );
+        // Positive exponents are turned into a mantissa with no scale.
+        assert_eq!(
+            decode("c482021903e8"),
+            Ok(Value::Decimal(Decimal::new(100000, 0).unwrap()))
+        );
+        assert_eq!(
+            decode("c482182701"),
+            Err(DecodingError::OutOfRange { offset: 0 })
+        );
+        assert_eq!(
+            decode("c4823826f6"),
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 4,
+                byte: 0xf6
+            })
+        );
+    }
+
+    #[test]
+    fn test_rfc_arrays() {
+        let decoded: Script<MathOperator> = <&mut CborCodec>::from_vec(hex("80")).unwrap();
+        assert!(decoded.is_empty());
+
+        let decoded: Script<MathOperator> = <&mut CborCodec>::from_vec(hex("83010203")).unwrap();
+        let expected: Script<MathOperator> = Vec::from([
+            Item::Value(Value::Integer(1)),
+            Item::Value(Value::Integer(2)),
+            Item::Value(Value::Integer(3)),
+        ]);
+        assert_eq!(decoded, expected);
+        assert_eq!(
+            <&mut CborCodec>::to_vec(&expected).unwrap(),
+            hex("83010203")
+        );
+
+        let encoded = hex("98190102030405060708090a0b0c0d0e0f101112131415161718181819");
+        let decoded: Script<MathOperator> = <&mut CborCodec>::from_vec(encoded.clone()).unwrap();
+        let expected: Script<MathOperator> =
+            (1..=25).map(|i| Item::Value(Value::Integer(i))).collect();
+        assert_eq!(decoded, expected);
+        assert_eq!(<&mut CborCodec>::to_vec(&expected).unwrap(), encoded);
+
+        // Indefinite-length arrays.
+        let decoded: Script<MathOperator> = <&mut CborCodec>::from_vec(hex("9fff")).unwrap();
+        assert!(decoded.is_empty());
+        let decoded: Script<MathOperator> = <&mut CborCodec>::from_vec(hex("9f0102ff")).unwrap();
+        assert_eq!(decoded.len(), 2);
+
+        // Nested arrays and maps are not items.
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("8301820203820405"));
+        assert_eq!(
+            decoded,
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 2,
+                byte: 0x82
+            })
+        );
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("81a201020304"));
+        assert_eq!(
+            decoded,
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 1,
+                byte: 0xa2
+            })
+        );
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("a0"));
+        assert_eq!(
+            decoded,
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 0,
+                byte: 0xa0
+            })
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let script: Script<MathOperator> = Vec::from([
+            Item::Value(Value::Integer(1)),
+            Item::Value(Value::Integer(2)),
+            Item::Operator(MathOperator::Add),
+            Item::Operator(MathOperator::StrictEqual),
+        ]);
+        let encoded = hex("840102d9c0de1880d9c0de1885");
+
+        assert_eq!(<&mut CborCodec>::to_vec(&script).unwrap(), encoded);
+        assert_eq!(<&mut CborCodec>::from_vec(encoded), Ok(script));
+
+        // Opcodes must be known to the operator system, and fit into a byte.
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("81d9c0de1890"));
+        assert_eq!(decoded, Err(DecodingError::UnknownOperator { offset: 4 }));
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("81d9c0de190100"));
+        assert_eq!(decoded, Err(DecodingError::OutOfRange { offset: 4 }));
+
+        // Operators without fields must not be wrapped in an array, not even a complete one.
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("82d9c0de82188001"));
+        assert_eq!(
+            decoded,
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 4,
+                byte: 0x82
+            })
+        );
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("81d9c0de851880"));
+        assert_eq!(
+            decoded,
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 4,
+                byte: 0x85
+            })
+        );
+        let decoded = <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("81d9c0de811880"));
+        assert_eq!(
+            decoded,
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 4,
+                byte: 0x81
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn test_bigint_codec() {
+        use num_bigint::BigInt;
+
+        let huge = BigInt::from(1u8) << 200u8;
+        let encoded = "c2581a0100000000000000000000000000000000000000000000000000";
+        assert_round_trip(Value::BigInt(huge.clone()), encoded);
+        assert_round_trip(
+            Value::BigInt(-huge - 1u8),
+            "c3581a0100000000000000000000000000000000000000000000000000",
+        );
+
+        // Big integers that fit into an `i128` are indistinguishable from integers.
+        assert_eq!(
+            encode_value(&Value::BigInt(BigInt::from(-1000))),
+            hex("3903e7")
+        );
+        assert_eq!(
+            decode("c2510100000000000000000000000000000000"),
+            Ok(Value::BigInt(BigInt::from(1u8) << 128u8))
+        );
+        assert_eq!(decode("c243000001"), Ok(Value::Integer(1)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "bigint"))]
+    fn test_bigint_unsupported() {
+        assert_eq!(
+            decode("c2510100000000000000000000000000000000"),
+            Err(DecodingError::OutOfRange { offset: 1 })
+        );
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let vectors = [
+            // Truncated arguments and contents.
+            (
+                "19",
+                DecodingError::UnexpectedEof {
+                    offset: 1,
+                    needed: 2,
+                },
+            ),
+            (
+                "6449",
+                DecodingError::UnexpectedEof {
+                    offset: 1,
+                    needed: 4,
+                },
+            ),
+            (
+                "fb3ff1",
+                DecodingError::UnexpectedEof {
+                    offset: 1,
+                    needed: 8,
+                },
+            ),
+            (
+                "5f4101",
+                DecodingError::UnexpectedEof {
+                    offset: 3,
+                    needed: 1,
+                },
+            ),
+            // Reserved additional information, and indefinite lengths where they make no sense.
+            (
+                "1c",
+                DecodingError::InvalidDiscriminant {
+                    offset: 0,
+                    byte: 0x1c,
+                },
+            ),
+            (
+                "1f",
+                DecodingError::InvalidDiscriminant {
+                    offset: 0,
+                    byte: 0x1f,
+                },
+            ),
+            (
+                "df",
+                DecodingError::InvalidDiscriminant {
+                    offset: 0,
+                    byte: 0xdf,
+                },
+            ),
+            // A lonely "break".
+            (
+                "ff",
+                DecodingError::InvalidDiscriminant {
+                    offset: 0,
+                    byte: 0xff,
+                },
+            ),
+            // Chunks of the wrong type or of indefinite length.
+            (
+                "5f6161ff",
+                DecodingError::InvalidDiscriminant {
+                    offset: 1,
+                    byte: 0x61,
+                },
+            ),
+            (
+                "5f5fffff",
+                DecodingError::InvalidDiscriminant {
+                    offset: 1,
+                    byte: 0x5f,
+                },
+            ),
+            // Invalid UTF-8, also when split across chunks.
+            ("62c328", DecodingError::InvalidUtf8 { offset: 1 }),
+            ("7f61c361bcff", DecodingError::InvalidUtf8 { offset: 2 }),
+            // Huge lengths.
+            (
+                "5bffffffffffffffff",
+                DecodingError::UnexpectedEof {
+                    offset: 9,
+                    needed: usize::MAX,
+                },
+            ),
+            // Timestamps out of the range of `i64`.
+            (
+                "c11b8000000000000000",
+                DecodingError::OutOfRange { offset: 1 },
+            ),
+            // Nested tags.
+            (
+                "c1c101",
+                DecodingError::InvalidDiscriminant {
+                    offset: 1,
+                    byte: 0xc1,
+                },
+            ),
+            // Trailing bytes.
+            ("0101", DecodingError::TrailingBytes { offset: 1 }),
+        ];
+
+        for (encoded, error) in vectors {
+            assert_eq!(decode(encoded), Err(error), "decoding {}", encoded);
+        }
+
+        // An array claiming far more items than there are.
+        let decoded =
+            <&mut CborCodec>::from_vec::<MathOperator, Value>(hex("9bffffffffffffffff01"));
+        assert_eq!(
+            decoded,
+            Err(DecodingError::UnexpectedEof {
+                offset: 10,
+                needed: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_unexpected_type() {
+        let decimal: Result<Decimal, _> = CborCodec::decode_exact(hex("6161"));
+        assert_eq!(
+            decimal,
+            Err(DecodingError::UnexpectedType {
+                offset: 0,
+                expected: "decimal",
+                found: "string"
+            })
+        );
+
+        let mut codec = CborCodec::from_data(hex("f5"));
+        assert_eq!(
+            (&mut codec).decode_i128(),
+            Err(DecodingError::UnexpectedType {
+                offset: 0,
+                expected: "integer",
+                found: "boolean"
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use proptest::prelude::*;
+
+    use crate::codecs::cbor::{f16_from_f64, f64_from_f16, CborCodec};
+    use crate::codecs::dec::Decoder;
+    use crate::codecs::enc::Encoder;
+    use crate::core::value::Value;
+    use crate::op_systems::simple_math::MathOperator;
+    use crate::prelude::*;
+    use crate::test_utils::{self, canonical, script};
+
+    /// Values of every type, plus floats of every precision and shorter integers, so that all of
+    /// their encodings are exercised.
+    fn value() -> impl Strategy<Value = Value> {
+        let values = test_utils::value();
+
+        // Only big integers beyond `i128` round-trip as such.
+        #[cfg(feature = "bigint")]
+        let values = values.prop_filter(
+            "fits into an i128",
+            |value| !matches!(value, Value::BigInt(bigint) if i128::try_from(bigint).is_ok()),
+        );
+
+        prop_oneof![
+            values,
+            prop_oneof![
+                any::<f32>().prop_map(f64::from),
+                any::<u16>().prop_map(f64_from_f16),
+            ]
+            .prop_map(Value::Float),
+            any::<i64>().prop_map(|integer| Value::Integer(integer.into())),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_script_round_trip(script in script(value())) {
+            let encoded = <&mut CborCodec>::to_vec(&script).unwrap();
+            let decoded: Script<MathOperator> =
+                <&mut CborCodec>::from_vec(encoded.clone()).unwrap();
+
+            prop_assert_eq!(<&mut CborCodec>::to_vec(&decoded).unwrap(), encoded);
+            prop_assert_eq!(canonical(decoded), canonical(script));
+        }
+
+        #[test]
+        fn test_half_floats_round_trip(bits in any::<u16>()) {
+            let float = f64_from_f16(bits);
+
+            if float.is_nan() {
+                prop_assert_eq!(f16_from_f64(float), Some(0x7e00));
+            } else {
+                prop_assert_eq!(f16_from_f64(float), Some(bits));
+            }
+        }
+
+        #[test]
+        fn test_arbitrary_bytes_never_panic(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+            let _ = <&mut CborCodec>::from_vec::<MathOperator, Value>(bytes.clone());
+            let _ = CborCodec::decode_exact::<Value>(bytes);
+        }
+    }
+}
@@ -8,10 +8,25 @@
 use alloc::string::String;
 use alloc::vec::Vec;
 
+use crate::codecs::{DecodingError, OperatorInfo};
+use crate::core::{decimal::Decimal, value::Value};
 use crate::prelude::*;
 
+/// Derives [`Decode`][Decode] for operator and value enums, as documented in the
+/// [`scriptful-derive`][scriptful_derive] crate.
+///
+/// [Decode]: trait.Decode.html
+/// [scriptful_derive]: https://docs.rs/scriptful-derive
+#[cfg(feature = "derive")]
+pub use scriptful_derive::Decode;
+
 pub trait Decoder: Sized {
-    type Error: Error;
+    /// Any error type for the decoder, as long as the [`DecodingError`][DecodingError]s raised by
+    /// the [`Decode`][Decode] implementations in this crate can be converted into it.
+    ///
+    /// [DecodingError]: ../enum.DecodingError.html
+    /// [Decode]: trait.Decode.html
+    type Error: Error + From<DecodingError>;
 
     fn decode_i128(&mut self) -> Result<i128, Self::Error>;
 
@@ -19,6 +34,20 @@ pub trait Decoder: Sized {
 
     fn decode_string(&mut self) -> Result<String, Self::Error>;
 
+    fn decode_bytes(&mut self) -> Result<Vec<u8>, Self::Error>;
+
+    /// Decodes a single value of any type, in whatever representation the codec uses for it.
+    fn decode_value(&mut self) -> Result<Value, Self::Error>;
+
+    /// Decodes the opcode of an operator, as written by [`Encoder::encode_operator`][encode].
+    /// Its fields, if any, shall be decoded right after it, in order.
+    ///
+    /// `operators` describes all the operators that can be decoded, for the sake of codecs that do
+    /// not identify operators by their opcode.
+    ///
+    /// [encode]: ../enc/trait.Encoder.html#tymethod.encode_operator
+    fn decode_operator(&mut self, operators: &[OperatorInfo]) -> Result<u8, Self::Error>;
+
     fn decode_item<Op, Val>(&mut self) -> Result<Item<Op, Val>, Self::Error>
     where
         Op: core::fmt::Debug + Decode,
@@ -34,6 +63,9 @@ pub trait Decoder: Sized {
         Op: core::fmt::Debug + Decode,
         Val: core::fmt::Debug + Decode;
 
+    /// The offset of the next byte to be read, so that errors can tell where they happened.
+    fn position(&self) -> usize;
+
     fn peek_byte(&self) -> Result<&u8, Self::Error>;
 
     fn read_byte(&mut self) -> Result<u8, Self::Error>;
@@ -46,3 +78,51 @@ pub trait Decode: Sized {
     where
         D: Decoder;
 }
+
+impl Decode for Value {
+    fn decode<D>(decoder: &mut D) -> Result<Self, <D as Decoder>::Error>
+    where
+        D: Decoder,
+    {
+        decoder.decode_value()
+    }
+}
+
+impl Decode for Decimal {
+    fn decode<D>(decoder: &mut D) -> Result<Self, <D as Decoder>::Error>
+    where
+        D: Decoder,
+    {
+        let offset = decoder.position();
+
+        match decoder.decode_value()? {
+            Value::Decimal(decimal) => Ok(decimal),
+            other => Err(DecodingError::UnexpectedType {
+                offset,
+                expected: "decimal",
+                found: other.type_name(),
+            }
+            .into()),
+        }
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Decode for num_bigint::BigInt {
+    fn decode<D>(decoder: &mut D) -> Result<Self, <D as Decoder>::Error>
+    where
+        D: Decoder,
+    {
+        let offset = decoder.position();
+
+        match decoder.decode_value()? {
+            Value::BigInt(bigint) => Ok(bigint),
+            other => Err(DecodingError::UnexpectedType {
+                offset,
+                expected: "bigint",
+                found: other.type_name(),
+            }
+            .into()),
+        }
+    }
+}
@@ -8,39 +8,125 @@
 use alloc::vec::Vec;
 use core::marker::Sized;
 
+use crate::codecs::{EncodingError, OperatorInfo};
+use crate::core::{decimal::Decimal, value::Value};
 use crate::prelude::*;
 
+/// Derives [`Encode`][Encode] for operator and value enums, as documented in the
+/// [`scriptful-derive`][scriptful_derive] crate.
+///
+/// [Encode]: trait.Encode.html
+/// [scriptful_derive]: https://docs.rs/scriptful-derive
+#[cfg(feature = "derive")]
+pub use scriptful_derive::Encode;
+
 pub trait Encode {
-    fn encode<E>(&self, encoder: E) -> E::Ok
+    fn encode<E>(&self, encoder: E) -> Result<E::Ok, E::Error>
     where
         E: Encoder;
 }
 
 pub trait EncodeSequence {
     type Ok;
+    type Error: Error;
 
-    fn encode_element<T: Sized>(&mut self, value: &T)
+    fn encode_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Encode;
+        T: Encode + Sized;
 
-    fn end(self) -> Self::Ok;
+    fn end(self) -> Result<Self::Ok, Self::Error>;
 }
 
 pub trait Encoder: Sized {
     type Ok;
-    type EncodeSequence: EncodeSequence<Ok = Self::Ok>;
+    /// Any error type for the encoder, as long as the [`EncodingError`][EncodingError]s raised by
+    /// the [`Encode`][Encode] implementations in this crate can be converted into it.
+    ///
+    /// [EncodingError]: ../enum.EncodingError.html
+    /// [Encode]: trait.Encode.html
+    type Error: Error + From<EncodingError>;
+    type EncodeSequence: EncodeSequence<Ok = Self::Ok, Error = Self::Error>;
 
-    fn to_vec<Op, Val>(input: &Script<Op, Val>) -> Vec<u8>
+    fn to_vec<Op, Val>(input: &Script<Op, Val>) -> Result<Vec<u8>, Self::Error>
     where
         Op: core::fmt::Debug + Encode,
         Val: core::fmt::Debug + Encode;
 
-    fn write_u8(self, input: u8) -> Self::Ok;
-    fn write_bytes(self, input: &[u8]) -> Self::Ok;
+    fn write_u8(self, input: u8) -> Result<Self::Ok, Self::Error>;
+    fn write_bytes(self, input: &[u8]) -> Result<Self::Ok, Self::Error>;
 
-    fn encode_item<Op, Val>(self, input: &Item<Op, Val>) -> Self::Ok
+    /// Encodes a single value in whatever representation the codec uses for it.
+    fn encode_value(self, input: &Value) -> Result<Self::Ok, Self::Error>;
+    /// Starts encoding an operator, whose fields shall then be encoded as the elements of the
+    /// returned sequence, in order.
+    ///
+    /// Operators with no fields simply end the sequence right away.
+    fn encode_operator(self, operator: &OperatorInfo) -> Result<Self::EncodeSequence, Self::Error>;
+    fn encode_item<Op, Val>(self, input: &Item<Op, Val>) -> Result<Self::Ok, Self::Error>
     where
         Op: core::fmt::Debug + Encode,
         Val: core::fmt::Debug + Encode;
-    fn encode_seq(self) -> Self::EncodeSequence;
+    /// Starts encoding a sequence of `length` elements, such as the items of a script.
+    fn encode_seq(self, length: usize) -> Result<Self::EncodeSequence, Self::Error>;
+}
+
+impl<Op, Val> Encode for Script<Op, Val>
+where
+    Op: core::fmt::Debug + Encode,
+    Val: core::fmt::Debug + Encode,
+{
+    fn encode<E>(&self, encoder: E) -> Result<<E as Encoder>::Ok, <E as Encoder>::Error>
+    where
+        E: Encoder,
+    {
+        let mut seq = encoder.encode_seq(self.len())?;
+        for item in self {
+            seq.encode_element(item)?;
+        }
+        seq.end()
+    }
+}
+
+impl<Op, Val> Encode for Item<Op, Val>
+where
+    Op: core::fmt::Debug + Encode,
+    Val: core::fmt::Debug + Encode,
+{
+    fn encode<E>(&self, encoder: E) -> Result<<E as Encoder>::Ok, <E as Encoder>::Error>
+    where
+        E: Encoder,
+    {
+        match self {
+            Item::Operator(op) => op.encode(encoder),
+            Item::Value(val) => val.encode(encoder),
+        }
+    }
+}
+
+impl Encode for Value {
+    fn encode<E>(&self, encoder: E) -> Result<<E as Encoder>::Ok, <E as Encoder>::Error>
+    where
+        E: Encoder,
+    {
+        encoder.encode_value(self)
+    }
+}
+
+impl Encode for Decimal {
+    fn encode<E>(&self, encoder: E) -> Result<<E as Encoder>::Ok, <E as Encoder>::Error>
+    where
+        E: Encoder,
+    {
+        encoder.encode_value(&Value::Decimal(*self))
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Encode for num_bigint::BigInt {
+    fn encode<E>(&self, encoder: E) -> Result<<E as Encoder>::Ok, <E as Encoder>::Error>
+    where
+        E: Encoder,
+    {
+        encoder.encode_value(&Value::BigInt(self.clone()))
+    }
 }
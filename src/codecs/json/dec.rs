@@ -0,0 +1,445 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    codecs::{
+        asm::{is_integer, parse_bigint, parse_decimal, parse_hex, parse_timestamp},
+        dec::{Decode, Decoder},
+        json::{Frame, JsonCodec},
+        DecodingError, OperatorInfo,
+    },
+    core::value::Value,
+    prelude::*,
+};
+
+impl Decoder for &mut JsonCodec {
+    type Error = DecodingError;
+
+    fn decode_i128(&mut self) -> Result<i128, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::Integer(integer) => Ok(integer),
+            other => Err(unexpected_type(offset, "integer", &other)),
+        }
+    }
+
+    fn decode_f64(&mut self) -> Result<f64, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::Float(float) => Ok(float),
+            other => Err(unexpected_type(offset, "float", &other)),
+        }
+    }
+
+    fn decode_string(&mut self) -> Result<String, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::String(string) => Ok(string),
+            other => Err(unexpected_type(offset, "string", &other)),
+        }
+    }
+
+    fn decode_bytes(&mut self) -> Result<Vec<u8>, Self::Error> {
+        let offset = self.cursor;
+
+        match self.decode_value()? {
+            Value::Bytes(bytes) => Ok(bytes),
+            other => Err(unexpected_type(offset, "bytes", &other)),
+        }
+    }
+
+    fn decode_value(&mut self) -> Result<Value, Self::Error> {
+        self.begin_element()?;
+        self.expect(b'{')?;
+        let key_offset = self.skip_whitespace();
+        let key = self.read_string()?;
+        self.expect(b':')?;
+        let offset = self.skip_whitespace();
+
+        let value = match key.as_str() {
+            "bool" => {
+                if self.peek_byte()? == &b't' {
+                    self.expect_literal("true").map(|_| Value::Boolean(true))?
+                } else {
+                    self.expect_literal("false")
+                        .map(|_| Value::Boolean(false))?
+                }
+            }
+            "int" => {
+                let integer = self.read_string()?;
+                if !is_integer(&integer) {
+                    return Err(DecodingError::InvalidToken { offset });
+                }
+
+                integer
+                    .parse()
+                    .map(Value::Integer)
+                    .map_err(|_| DecodingError::OutOfRange { offset })?
+            }
+            "float" => {
+                let float = if self.peek_byte()? == &b'"' {
+                    match self.read_string()?.as_str() {
+                        "NaN" => f64::NAN,
+                        "inf" => f64::INFINITY,
+                        "-inf" => f64::NEG_INFINITY,
+                        _ => return Err(DecodingError::InvalidToken { offset }),
+                    }
+                } else {
+                    // Any JSON number is a valid float, albeit possibly an infinite one.
+                    self.read_number()?
+                        .parse()
+                        .map_err(|_| DecodingError::InvalidToken { offset })?
+                };
+
+                Value::Float(float)
+            }
+            "str" => Value::String(self.read_string()?),
+            "null" => self.expect_literal("null").map(|_| Value::Null)?,
+            "dec" => parse_decimal(&self.read_string()?)
+                .map(Value::Decimal)
+                .ok_or(DecodingError::InvalidToken { offset })?,
+            // Only supported when the `bigint` feature is enabled.
+            "bigint" => {
+                parse_bigint(&self.read_string()?).ok_or(DecodingError::InvalidToken { offset })?
+            }
+            "bytes" => parse_hex(&self.read_string()?)
+                .map(Value::Bytes)
+                .ok_or(DecodingError::InvalidToken { offset })?,
+            "time" => parse_timestamp(&self.read_string()?)
+                .map(Value::Timestamp)
+                .ok_or(DecodingError::InvalidToken { offset })?,
+            _ => return Err(DecodingError::InvalidToken { offset: key_offset }),
+        };
+
+        self.expect(b'}')?;
+        self.end_element()?;
+
+        Ok(value)
+    }
+
+    /// Operators are looked up by name in `operators`.
+    fn decode_operator(&mut self, operators: &[OperatorInfo]) -> Result<u8, Self::Error> {
+        self.begin_element()?;
+        self.expect(b'{')?;
+        self.expect_key("op")?;
+        let offset = self.skip_whitespace();
+        let name = self.read_string()?;
+        let operator = operators
+            .iter()
+            .find(|operator| operator.name == name)
+            .ok_or(DecodingError::UnknownOperator { offset })?;
+
+        if operator.fields == 0 {
+            self.expect(b'}')?;
+            self.end_element()?;
+        } else {
+            // The array is closed by `end_element` once the last field has been decoded.
+            self.expect(b',')?;
+            self.expect_key("args")?;
+            self.expect(b'[')?;
+            self.frames.push(Frame {
+                decoded: 0,
+                fields: operator.fields,
+            });
+        }
+
+        Ok(operator.opcode)
+    }
+
+    fn decode_item<Op, Val>(&mut self) -> Result<Item<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        // Peek at the key, which tells operators apart from values.
+        let cursor = self.cursor;
+        let is_operator =
+            self.expect(b'{').is_ok() && self.read_string().is_ok_and(|key| key == "op");
+        self.cursor = cursor;
+
+        if is_operator {
+            Op::decode(&mut *self).map(Item::Operator)
+        } else {
+            Val::decode(&mut *self).map(Item::Value)
+        }
+    }
+
+    fn decode_script<Op, Val>(&mut self) -> Result<Script<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        let mut script = Script::<Op, Val>::new();
+
+        self.expect(b'[')?;
+        self.skip_whitespace();
+        if self.peek_byte()? == &b']' {
+            self.cursor += 1;
+
+            return Ok(script);
+        }
+
+        loop {
+            script.push(self.decode_item()?);
+
+            let offset = self.skip_whitespace();
+            match self.read_byte()? {
+                b',' => continue,
+                b']' => return Ok(script),
+                _ => return Err(DecodingError::InvalidToken { offset }),
+            }
+        }
+    }
+
+    fn from_vec<Op, Val>(input: Vec<u8>) -> Result<Script<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        let mut codec = JsonCodec::from_data(input);
+        let script = <&mut JsonCodec as Decoder>::decode_script(&mut &mut codec)?;
+        codec.skip_whitespace();
+
+        if codec.bytes_left() > 0 {
+            return Err(DecodingError::TrailingBytes {
+                offset: codec.cursor,
+            });
+        }
+
+        Ok(script)
+    }
+
+    fn position(&self) -> usize {
+        self.cursor
+    }
+
+    fn peek_byte(&self) -> Result<&u8, Self::Error> {
+        self.data
+            .get(self.cursor)
+            .ok_or(DecodingError::UnexpectedEof {
+                offset: self.cursor,
+                needed: 1,
+            })
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Self::Error> {
+        let byte = *self.peek_byte()?;
+        self.cursor += 1;
+
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&[u8], Self::Error> {
+        let start = self.cursor;
+        let bytes = start
+            .checked_add(length)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or(DecodingError::UnexpectedEof {
+                offset: start,
+                needed: length,
+            })?;
+        self.cursor += length;
+
+        Ok(bytes)
+    }
+}
+
+impl JsonCodec {
+    /// Skips any JSON whitespace, returning the offset of the next byte.
+    pub(super) fn skip_whitespace(&mut self) -> usize {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.data.get(self.cursor) {
+            self.cursor += 1;
+        }
+
+        self.cursor
+    }
+
+    /// Reads `expected` after skipping any whitespace.
+    fn expect(&mut self, expected: u8) -> Result<(), DecodingError> {
+        let offset = self.skip_whitespace();
+
+        if (&mut *self).read_byte()? == expected {
+            Ok(())
+        } else {
+            Err(DecodingError::InvalidToken { offset })
+        }
+    }
+
+    /// Reads one of the `true`, `false` and `null` literals.
+    fn expect_literal(&mut self, literal: &str) -> Result<(), DecodingError> {
+        let offset = self.skip_whitespace();
+
+        if (&mut *self).read_bytes(literal.len())? == literal.as_bytes() {
+            Ok(())
+        } else {
+            Err(DecodingError::InvalidToken { offset })
+        }
+    }
+
+    /// Reads an object key along with the colon that follows it.
+    fn expect_key(&mut self, key: &str) -> Result<(), DecodingError> {
+        let offset = self.skip_whitespace();
+
+        if self.read_string()? != key {
+            return Err(DecodingError::InvalidToken { offset });
+        }
+
+        self.expect(b':')
+    }
+
+    /// Reads the comma that separates the fields of an operator, unless this is the first one.
+    fn begin_element(&mut self) -> Result<(), DecodingError> {
+        match self.frames.last() {
+            Some(frame) if frame.decoded > 0 => self.expect(b','),
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts an element as decoded, closing the `"args"` arrays and the objects of the operators
+    /// whose last field it was.
+    fn end_element(&mut self) -> Result<(), DecodingError> {
+        while let Some(frame) = self.frames.last_mut() {
+            frame.decoded += 1;
+            if frame.decoded < frame.fields {
+                break;
+            }
+
+            // The operator itself is an element of the enclosing frame, if any.
+            self.frames.pop();
+            self.expect(b']')?;
+            self.expect(b'}')?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a JSON string, resolving any escape sequences.
+    fn read_string(&mut self) -> Result<String, DecodingError> {
+        let offset = self.skip_whitespace();
+        let mut codec = self;
+        if codec.read_byte()? != b'"' {
+            return Err(DecodingError::InvalidToken { offset });
+        }
+
+        let mut string = Vec::new();
+        loop {
+            let escape_offset = codec.cursor;
+            match codec.read_byte()? {
+                b'"' => break,
+                b'\\' => {
+                    let character = match codec.read_byte()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{08}',
+                        b'f' => '\u{0c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => codec.read_unicode_escape(escape_offset)?,
+                        _ => {
+                            return Err(DecodingError::InvalidToken {
+                                offset: escape_offset,
+                            })
+                        }
+                    };
+                    let mut buffer = [0u8; 4];
+                    string.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+                }
+                0x00..=0x1f => {
+                    return Err(DecodingError::InvalidToken {
+                        offset: escape_offset,
+                    })
+                }
+                byte => string.push(byte),
+            }
+        }
+
+        String::from_utf8(string).map_err(|_| DecodingError::InvalidUtf8 { offset })
+    }
+
+    /// Reads the four hexadecimal digits of a `\u` escape that started at `offset`, along with
+    /// the low surrogate that must follow a high surrogate.
+    fn read_unicode_escape(&mut self, offset: usize) -> Result<char, DecodingError> {
+        let high = self.read_hex_quad(offset)?;
+        let code_point = match high {
+            0xd800..=0xdbff => {
+                if (&mut *self).read_bytes(2)? != b"\\u" {
+                    return Err(DecodingError::InvalidToken { offset });
+                }
+                match self.read_hex_quad(offset)? {
+                    low @ 0xdc00..=0xdfff => 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00),
+                    _ => return Err(DecodingError::InvalidToken { offset }),
+                }
+            }
+            code_point => code_point,
+        };
+
+        char::from_u32(code_point).ok_or(DecodingError::InvalidToken { offset })
+    }
+
+    fn read_hex_quad(&mut self, offset: usize) -> Result<u32, DecodingError> {
+        let mut codec = self;
+        let digits = codec.read_bytes(4)?;
+
+        core::str::from_utf8(digits)
+            .ok()
+            .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or(DecodingError::InvalidToken { offset })
+    }
+
+    /// Reads a JSON number, making sure that it follows the JSON grammar, which is stricter than
+    /// that of Rust floats.
+    fn read_number(&mut self) -> Result<&str, DecodingError> {
+        let start = self.skip_whitespace();
+        let digits = |codec: &mut Self| {
+            let from = codec.cursor;
+            while codec.data.get(codec.cursor).is_some_and(u8::is_ascii_digit) {
+                codec.cursor += 1;
+            }
+
+            codec.cursor - from
+        };
+
+        if self.data.get(self.cursor) == Some(&b'-') {
+            self.cursor += 1;
+        }
+        let integer_offset = self.cursor;
+        let integer_digits = digits(self);
+        let valid_integer =
+            integer_digits == 1 || (integer_digits > 1 && self.data[integer_offset] != b'0');
+        let mut valid = valid_integer;
+
+        if self.data.get(self.cursor) == Some(&b'.') {
+            self.cursor += 1;
+            valid &= digits(self) > 0;
+        }
+        if let Some(b'e' | b'E') = self.data.get(self.cursor) {
+            self.cursor += 1;
+            if let Some(b'+' | b'-') = self.data.get(self.cursor) {
+                self.cursor += 1;
+            }
+            valid &= digits(self) > 0;
+        }
+
+        if !valid {
+            return Err(DecodingError::InvalidToken { offset: start });
+        }
+
+        // Only ASCII digits, signs, dots and exponents were read.
+        core::str::from_utf8(&self.data[start..self.cursor])
+            .map_err(|_| DecodingError::InvalidToken { offset: start })
+    }
+}
+
+fn unexpected_type(offset: usize, expected: &'static str, found: &Value) -> DecodingError {
+    DecodingError::UnexpectedType {
+        offset,
+        expected,
+        found: found.type_name(),
+    }
+}
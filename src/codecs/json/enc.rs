@@ -0,0 +1,177 @@
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{
+    codecs::{
+        enc::{Encode, EncodeSequence, Encoder},
+        json::JsonCodec,
+        EncodingError, OperatorInfo,
+    },
+    core::value::Value,
+    prelude::*,
+};
+
+impl<'a> Encoder for &'a mut JsonCodec {
+    type Ok = ();
+    type Error = EncodingError;
+    type EncodeSequence = JsonSequence<'a>;
+
+    fn to_vec<Op, Val>(input: &Script<Op, Val>) -> Result<Vec<u8>, Self::Error>
+    where
+        Op: core::fmt::Debug + Encode,
+        Val: core::fmt::Debug + Encode,
+    {
+        let mut codec = JsonCodec::default();
+        input.encode(&mut codec)?;
+
+        Ok(codec.data())
+    }
+
+    fn write_u8(self, input: u8) -> Result<Self::Ok, Self::Error> {
+        self.data.push(input);
+
+        Ok(())
+    }
+
+    fn write_bytes(self, input: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.data.extend_from_slice(input);
+
+        Ok(())
+    }
+
+    fn encode_value(self, input: &Value) -> Result<Self::Ok, Self::Error> {
+        // Writing into a `Vec` never fails.
+        let _ = match input {
+            Value::Boolean(val) => self.write_fmt(format_args!(r#"{{"bool":{}}}"#, val)),
+            Value::Integer(val) => self.write_fmt(format_args!(r#"{{"int":"{}"}}"#, val)),
+            Value::Float(val) if val.is_nan() => self.write_str(r#"{"float":"NaN"}"#),
+            Value::Float(val) if val.is_infinite() && *val > 0.0 => {
+                self.write_str(r#"{"float":"inf"}"#)
+            }
+            Value::Float(val) if val.is_infinite() => self.write_str(r#"{"float":"-inf"}"#),
+            // The shortest representation that parses back into the very same float.
+            Value::Float(val) => self.write_fmt(format_args!(r#"{{"float":{:?}}}"#, val)),
+            Value::String(val) => {
+                self.data.extend_from_slice(br#"{"str":"#);
+                self.write_string(val);
+                self.write_str("}")
+            }
+            Value::Null => self.write_str(r#"{"null":null}"#),
+            Value::Decimal(val) => self.write_fmt(format_args!(r#"{{"dec":"{}"}}"#, val)),
+            Value::Bytes(val) => {
+                self.data.extend_from_slice(br#"{"bytes":""#);
+                val.iter()
+                    .try_for_each(|byte| self.write_fmt(format_args!("{:02x}", byte)))
+                    .and_then(|_| self.write_str(r#""}"#))
+            }
+            // Timestamps are displayed as RFC 3339.
+            Value::Timestamp(_) => self.write_fmt(format_args!(r#"{{"time":"{}"}}"#, input)),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(val) => self.write_fmt(format_args!(r#"{{"bigint":"{}"}}"#, val)),
+        };
+
+        Ok(())
+    }
+
+    /// Operators are encoded as an object with their name, followed by an array with their
+    /// fields, if there are any.
+    fn encode_operator(self, operator: &OperatorInfo) -> Result<Self::EncodeSequence, Self::Error> {
+        self.data.extend_from_slice(br#"{"op":"#);
+        self.write_string(operator.name);
+
+        let closing = if operator.fields > 0 {
+            self.data.extend_from_slice(br#","args":["#);
+            "]}"
+        } else {
+            "}"
+        };
+
+        Ok(JsonSequence::new(self, closing))
+    }
+
+    fn encode_item<Op, Val>(self, input: &Item<Op, Val>) -> Result<Self::Ok, Self::Error>
+    where
+        Op: core::fmt::Debug + Encode,
+        Val: core::fmt::Debug + Encode,
+    {
+        input.encode(self)
+    }
+
+    /// Sequences are encoded as arrays.
+    fn encode_seq(self, _length: usize) -> Result<Self::EncodeSequence, Self::Error> {
+        self.data.push(b'[');
+
+        Ok(JsonSequence::new(self, "]"))
+    }
+}
+
+/// Writes the elements of an array, or the fields of an operator, separated by commas.
+pub struct JsonSequence<'a> {
+    codec: &'a mut JsonCodec,
+    empty: bool,
+    closing: &'static str,
+}
+
+impl<'a> JsonSequence<'a> {
+    fn new(codec: &'a mut JsonCodec, closing: &'static str) -> Self {
+        Self {
+            codec,
+            empty: true,
+            closing,
+        }
+    }
+}
+
+impl EncodeSequence for JsonSequence<'_> {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn encode_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Encode + Sized,
+    {
+        if !self.empty {
+            self.codec.data.push(b',');
+        }
+        self.empty = false;
+
+        value.encode(&mut *self.codec)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.codec.data.extend_from_slice(self.closing.as_bytes());
+
+        Ok(())
+    }
+}
+
+/// Appends text to the encoded data.
+impl Write for JsonCodec {
+    fn write_str(&mut self, input: &str) -> core::fmt::Result {
+        self.data.extend_from_slice(input.as_bytes());
+
+        Ok(())
+    }
+}
+
+impl JsonCodec {
+    /// Writes a JSON string, escaping quotes, backslashes and control characters.
+    fn write_string(&mut self, input: &str) {
+        self.data.push(b'"');
+        for character in input.chars() {
+            // Writing into a `Vec` never fails.
+            let _ = match character {
+                '"' => self.write_str("\\\""),
+                '\\' => self.write_str("\\\\"),
+                '\n' => self.write_str("\\n"),
+                '\r' => self.write_str("\\r"),
+                '\t' => self.write_str("\\t"),
+                '\u{08}' => self.write_str("\\b"),
+                '\u{0c}' => self.write_str("\\f"),
+                x if x.is_control() => self.write_fmt(format_args!("\\u{:04x}", u32::from(x))),
+                x => self.write_char(x),
+            };
+        }
+        self.data.push(b'"');
+    }
+}
@@ -0,0 +1,483 @@
+//! A codec for exchanging scripts as [JSON], e.g. with web frontends, without depending on `serde`
+//! nor `std`.
+//!
+//! A script is encoded as a JSON array of items, where every item is an object with a single key
+//! that tells its type. Numbers that do not fit into a JSON number without loss, such as `i128`
+//! integers, are encoded as strings:
+//!
+//! | Item        | JSON                                                             | Examples                          |
+//! |-------------|------------------------------------------------------------------|-----------------------------------|
+//! | `Boolean`   | `{"bool": ...}` with `true` or `false`.                          | `{"bool":true}`                   |
+//! | `Integer`   | `{"int": ...}` with a string of decimal digits and optional `-`. | `{"int":"-42"}`                   |
+//! | `BigInt`    | `{"bigint": ...}`, same as `Integer`.                            | `{"bigint":"18446744073709551616"}` |
+//! | `Float`     | `{"float": ...}` with a number, or `"inf"`, `"-inf"` or `"NaN"`. | `{"float":1.5}`, `{"float":"NaN"}` |
+//! | `Decimal`   | `{"dec": ...}` with a string of decimal digits and optional `-` and fraction. | `{"dec":"-12.34"}`   |
+//! | `String`    | `{"str": ...}` with a string.                                    | `{"str":"Hello, World!"}`         |
+//! | `Null`      | `{"null":null}`.                                                 | `{"null":null}`                   |
+//! | `Bytes`     | `{"bytes": ...}` with a string of pairs of hexadecimal digits.   | `{"bytes":"cafe"}`                |
+//! | `Timestamp` | `{"time": ...}` with an RFC 3339 date and time in UTC, with no fractional seconds. | `{"time":"2009-01-03T18:15:05Z"}` |
+//! | Operator    | `{"op": ...}` with the name of the operator, followed by `"args"` with an array of its fields, if any. | `{"op":"Add"}`, `{"op":"Push","args":[{"int":"1"}]}` |
+//!
+//! Operators are identified by the name of their variant, e.g. `Add` for `MathOperator::Add`, and
+//! fields are encoded just like items. The `"args"` key is omitted for operators with no fields.
+//!
+//! Encoders write no whitespace and escape `"`, `\` and control characters in strings. Decoders
+//! accept any JSON whitespace and escapes, but keys must appear in the order above, i.e. `"op"`
+//! before `"args"`, so that fields can be decoded without buffering. Decoding never panics, no
+//! matter the input.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scriptful::codecs::dec::Decoder;
+//! use scriptful::codecs::enc::Encoder;
+//! use scriptful::codecs::json::JsonCodec;
+//! use scriptful::core::value::Value;
+//! use scriptful::op_systems::simple_math::MathOperator;
+//! use scriptful::prelude::*;
+//!
+//! let script = Vec::from([
+//!     Item::Value(Value::Integer(1)),
+//!     Item::Value(Value::String("two".into())),
+//!     Item::Operator(MathOperator::Add),
+//! ]);
+//! let encoded = <&mut JsonCodec>::to_vec(&script).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(encoded.clone()).unwrap(),
+//!     r#"[{"int":"1"},{"str":"two"},{"op":"Add"}]"#
+//! );
+//!
+//! let decoded: Script<MathOperator> = <&mut JsonCodec>::from_vec(encoded).unwrap();
+//! assert_eq!(decoded, script);
+//! ```
+//!
+//! [JSON]: https://www.rfc-editor.org/rfc/rfc8259
+
+use alloc::vec::Vec;
+
+use crate::codecs::dec::Decode;
+use crate::codecs::DecodingError;
+
+pub mod dec;
+pub mod enc;
+
+/// Encodes and decodes scripts as JSON, as described in the [module documentation](index.html).
+#[derive(Default)]
+pub struct JsonCodec {
+    data: Vec<u8>,
+    cursor: usize,
+    /// The `"args"` arrays of the operators whose fields are being decoded, innermost last.
+    frames: Vec<Frame>,
+}
+
+/// Keeps track of how many fields of an operator have been decoded, so that the array holding them
+/// can be closed right after the last one.
+struct Frame {
+    decoded: usize,
+    fields: usize,
+}
+
+impl JsonCodec {
+    pub fn data(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub fn from_data(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            ..Self::default()
+        }
+    }
+
+    pub fn bytes_left(&self) -> usize {
+        self.data.len() - self.cursor
+    }
+
+    /// Decodes a single JSON value that must take up the whole of `data`, save for whitespace,
+    /// e.g. a [`Value`][Value] received on its own rather than as part of a script.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::codecs::json::JsonCodec;
+    /// use scriptful::codecs::DecodingError;
+    /// use scriptful::core::value::Value;
+    ///
+    /// let value: Result<Value, _> = JsonCodec::decode_exact(Vec::from(r#" {"int": "255"} "#));
+    /// assert_eq!(value, Ok(Value::Integer(255)));
+    ///
+    /// let value: Result<Value, _> = JsonCodec::decode_exact(Vec::from(r#"{"null":null}]"#));
+    /// assert_eq!(value, Err(DecodingError::TrailingBytes { offset: 13 }));
+    /// ```
+    ///
+    /// [Value]: ../../core/value/enum.Value.html
+    pub fn decode_exact<T>(data: Vec<u8>) -> Result<T, DecodingError>
+    where
+        T: Decode,
+    {
+        let mut codec = JsonCodec::from_data(data);
+        let decoded = T::decode(&mut &mut codec)?;
+        codec.skip_whitespace();
+
+        if codec.bytes_left() > 0 {
+            return Err(DecodingError::TrailingBytes {
+                offset: codec.cursor,
+            });
+        }
+
+        Ok(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::codecs::dec::Decoder;
+    use crate::codecs::enc::{Encode, Encoder};
+    use crate::codecs::json::JsonCodec;
+    use crate::codecs::DecodingError;
+    use crate::core::decimal::Decimal;
+    use crate::core::value::{CanonicalValue, Value};
+    use crate::op_systems::bitwise::BitwiseOperator;
+    use crate::op_systems::simple_math::MathOperator;
+    use crate::prelude::*;
+
+    fn encode_value(value: &Value) -> String {
+        let mut codec = JsonCodec::default();
+        value.encode(&mut codec).unwrap();
+
+        String::from_utf8(codec.data()).unwrap()
+    }
+
+    fn decode(json: &str) -> Result<Value, DecodingError> {
+        JsonCodec::decode_exact(Vec::from(json))
+    }
+
+    /// Checks that `value` is encoded exactly as `json`, and that decoding it back yields the very
+    /// same value.
+    fn assert_round_trip(value: Value, json: &str) {
+        assert_eq!(encode_value(&value), json);
+        assert_eq!(
+            decode(json).map(CanonicalValue),
+            Ok(CanonicalValue(value)),
+            "decoding {}",
+            json
+        );
+    }
+
+    #[test]
+    fn test_values() {
+        assert_round_trip(Value::Boolean(true), r#"{"bool":true}"#);
+        assert_round_trip(Value::Boolean(false), r#"{"bool":false}"#);
+        assert_round_trip(Value::Integer(-42), r#"{"int":"-42"}"#);
+        assert_round_trip(
+            Value::Integer(i128::MAX),
+            r#"{"int":"170141183460469231731687303715884105727"}"#,
+        );
+        assert_round_trip(
+            Value::Integer(i128::MIN),
+            r#"{"int":"-170141183460469231731687303715884105728"}"#,
+        );
+        assert_round_trip(Value::Null, r#"{"null":null}"#);
+        assert_round_trip(
+            Value::Decimal(Decimal::new(-1234, 2).unwrap()),
+            r#"{"dec":"-12.34"}"#,
+        );
+        assert_round_trip(
+            Value::Decimal(Decimal::new(5, 3).unwrap()),
+            r#"{"dec":"0.005"}"#,
+        );
+        assert_round_trip(Value::Bytes(Vec::from([0xca, 0xfe])), r#"{"bytes":"cafe"}"#);
+        assert_round_trip(Value::Bytes(Vec::new()), r#"{"bytes":""}"#);
+        assert_round_trip(
+            Value::Timestamp(1231006505),
+            r#"{"time":"2009-01-03T18:15:05Z"}"#,
+        );
+        assert_round_trip(Value::Timestamp(-1), r#"{"time":"1969-12-31T23:59:59Z"}"#);
+        assert_round_trip(
+            Value::Timestamp(-62_198_755_200),
+            r#"{"time":"-0001-01-01T00:00:00Z"}"#,
+        );
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_round_trip(Value::Float(1.5), r#"{"float":1.5}"#);
+        assert_round_trip(Value::Float(-0.0), r#"{"float":-0.0}"#);
+        assert_round_trip(Value::Float(0.1), r#"{"float":0.1}"#);
+        assert_round_trip(Value::Float(1e300), r#"{"float":1e300}"#);
+        assert_round_trip(Value::Float(-5e-324), r#"{"float":-5e-324}"#);
+        assert_round_trip(
+            Value::Float(f64::MAX),
+            r#"{"float":1.7976931348623157e308}"#,
+        );
+        assert_round_trip(Value::Float(f64::NAN), r#"{"float":"NaN"}"#);
+        assert_round_trip(Value::Float(f64::INFINITY), r#"{"float":"inf"}"#);
+        assert_round_trip(Value::Float(f64::NEG_INFINITY), r#"{"float":"-inf"}"#);
+
+        // Any JSON number is accepted.
+        assert_eq!(decode(r#"{"float":1}"#), Ok(Value::Float(1.0)));
+        assert_eq!(decode(r#"{"float":-2.5E+2}"#), Ok(Value::Float(-250.0)));
+        assert_eq!(decode(r#"{"float":0e0}"#), Ok(Value::Float(0.0)));
+        assert_eq!(
+            decode(r#"{"float":1e999}"#).map(CanonicalValue),
+            Ok(CanonicalValue(Value::Float(f64::INFINITY)))
+        );
+
+        // But nothing else.
+        for json in [
+            r#"{"float":01}"#,
+            r#"{"float":.5}"#,
+            r#"{"float":1.}"#,
+            r#"{"float":+1}"#,
+            r#"{"float":1e}"#,
+            r#"{"float":-}"#,
+            r#"{"float":"Infinity"}"#,
+        ] {
+            assert_eq!(
+                decode(json),
+                Err(DecodingError::InvalidToken { offset: 9 }),
+                "decoding {}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_strings() {
+        assert_round_trip(Value::String(String::new()), r#"{"str":""}"#);
+        assert_round_trip(
+            Value::String("\"Hello\"\\\n\t\r\u{8}\u{c}\u{1}\u{7f}, wörld! 😀".into()),
+            r#"{"str":"\"Hello\"\\\n\t\r\b\f\u0001\u007f, wörld! 😀"}"#,
+        );
+
+        // Escapes that are never written, but are still valid JSON.
+        assert_eq!(
+            decode(r#"{"str":"\/éÉ😀"}"#),
+            Ok(Value::String("/éÉ😀".into()))
+        );
+
+        // Invalid escapes, lone surrogates, raw control characters and invalid UTF-8.
+        assert_eq!(
+            decode(r#"{"str":"\x"}"#),
+            Err(DecodingError::InvalidToken { offset: 8 })
+        );
+        assert_eq!(
+            decode(r#"{"str":"\u12"}"#),
+            Err(DecodingError::InvalidToken { offset: 8 })
+        );
+        assert_eq!(
+            decode(r#"{"str":"\ud83d"}"#),
+            Err(DecodingError::InvalidToken { offset: 8 })
+        );
+        assert_eq!(
+            decode(r#"{"str":"\ude00"}"#),
+            Err(DecodingError::InvalidToken { offset: 8 })
+        );
+        assert_eq!(
+            decode("{\"str\":\"\n\"}"),
+            Err(DecodingError::InvalidToken { offset: 8 })
+        );
+        assert_eq!(
+            JsonCodec::decode_exact::<Value>(Vec::from(&b"{\"str\":\"\xc3\x28\"}"[..])),
+            Err(DecodingError::InvalidUtf8 { offset: 7 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn test_bigint() {
+        let huge = num_bigint::BigInt::from(1u8) << 128u8;
+
+        assert_round_trip(
+            Value::BigInt(-huge),
+            r#"{"bigint":"-340282366920938463463374607431768211456"}"#,
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let script: Script<MathOperator> = Vec::from([
+            Item::Value(Value::Integer(1)),
+            Item::Value(Value::Integer(2)),
+            Item::Operator(MathOperator::Add),
+            Item::Operator(MathOperator::StrictEqual),
+        ]);
+        let json = r#"[{"int":"1"},{"int":"2"},{"op":"Add"},{"op":"StrictEqual"}]"#;
+
+        let encoded = <&mut JsonCodec>::to_vec(&script).unwrap();
+        assert_eq!(String::from_utf8(encoded).unwrap(), json);
+        assert_eq!(<&mut JsonCodec>::from_vec(Vec::from(json)), Ok(script));
+
+        let script: Script<BitwiseOperator> =
+            Vec::from([Item::Operator(BitwiseOperator::PopCount)]);
+        let encoded = <&mut JsonCodec>::to_vec(&script).unwrap();
+        assert_eq!(
+            String::from_utf8(encoded).unwrap(),
+            r#"[{"op":"PopCount"}]"#
+        );
+
+        // Operators of other operator systems are unknown.
+        let decoded =
+            <&mut JsonCodec>::from_vec::<MathOperator, Value>(Vec::from(r#"[{"op":"PopCount"}]"#));
+        assert_eq!(decoded, Err(DecodingError::UnknownOperator { offset: 7 }));
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let json = " [ \n\t{ \"int\" : \"1\" } ,\r\n { \"op\":\"Not\" } ] \n";
+        let decoded: Script<MathOperator> = <&mut JsonCodec>::from_vec(Vec::from(json)).unwrap();
+
+        assert_eq!(
+            decoded,
+            Vec::from([
+                Item::Value(Value::Integer(1)),
+                Item::Operator(MathOperator::Not)
+            ])
+        );
+
+        let decoded: Script<MathOperator> = <&mut JsonCodec>::from_vec(Vec::from(" [ ] ")).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let vectors = [
+            (
+                "",
+                DecodingError::UnexpectedEof {
+                    offset: 0,
+                    needed: 1,
+                },
+            ),
+            (
+                "[",
+                DecodingError::UnexpectedEof {
+                    offset: 1,
+                    needed: 1,
+                },
+            ),
+            ("{}", DecodingError::InvalidToken { offset: 0 }),
+            ("[,]", DecodingError::InvalidToken { offset: 1 }),
+            (
+                r#"[{"null":null},]"#,
+                DecodingError::InvalidToken { offset: 15 },
+            ),
+            (
+                r#"[{"null":null}{"null":null}]"#,
+                DecodingError::InvalidToken { offset: 14 },
+            ),
+            (
+                r#"[{"nul":null}]"#,
+                DecodingError::InvalidToken { offset: 2 },
+            ),
+            (
+                r#"[{"null":nul}]"#,
+                DecodingError::InvalidToken { offset: 9 },
+            ),
+            (
+                r#"[{"null":null,"bool":true}]"#,
+                DecodingError::InvalidToken { offset: 13 },
+            ),
+            (
+                r#"[{"bool":"true"}]"#,
+                DecodingError::InvalidToken { offset: 9 },
+            ),
+            (r#"[{"int":1}]"#, DecodingError::InvalidToken { offset: 8 }),
+            (
+                r#"[{"int":"1.5"}]"#,
+                DecodingError::InvalidToken { offset: 8 },
+            ),
+            (
+                r#"[{"int":"170141183460469231731687303715884105728"}]"#,
+                DecodingError::OutOfRange { offset: 8 },
+            ),
+            (
+                r#"[{"dec":"1.2.3"}]"#,
+                DecodingError::InvalidToken { offset: 8 },
+            ),
+            (
+                r#"[{"bytes":"caf"}]"#,
+                DecodingError::InvalidToken { offset: 10 },
+            ),
+            (
+                r#"[{"time":"2009-02-30T00:00:00Z"}]"#,
+                DecodingError::InvalidToken { offset: 9 },
+            ),
+            (
+                r#"[{"op":"Add","args":[]}]"#,
+                DecodingError::InvalidToken { offset: 12 },
+            ),
+            (r#"[{"op":Add}]"#, DecodingError::InvalidToken { offset: 7 }),
+            (
+                r#"[{"op":"Add"}] ]"#,
+                DecodingError::TrailingBytes { offset: 15 },
+            ),
+        ];
+
+        for (json, error) in vectors {
+            let decoded = <&mut JsonCodec>::from_vec::<MathOperator, Value>(Vec::from(json));
+            assert_eq!(decoded, Err(error), "decoding {}", json);
+        }
+
+        #[cfg(not(feature = "bigint"))]
+        assert_eq!(
+            decode(r#"{"bigint":"1"}"#),
+            Err(DecodingError::InvalidToken { offset: 10 })
+        );
+    }
+
+    #[test]
+    fn test_unexpected_type() {
+        let mut codec = JsonCodec::from_data(Vec::from(r#"{"bool":true}"#));
+
+        assert_eq!(
+            (&mut codec).decode_i128(),
+            Err(DecodingError::UnexpectedType {
+                offset: 0,
+                expected: "integer",
+                found: "boolean"
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use alloc::vec::Vec;
+
+    use proptest::prelude::*;
+
+    use crate::codecs::dec::Decoder;
+    use crate::codecs::enc::Encoder;
+    use crate::codecs::json::JsonCodec;
+    use crate::core::value::Value;
+    use crate::op_systems::simple_math::MathOperator;
+    use crate::prelude::*;
+    use crate::test_utils::{canonical, script, value};
+
+    proptest! {
+        #[test]
+        fn test_script_round_trip(script in script(value())) {
+            let encoded = <&mut JsonCodec>::to_vec(&script).unwrap();
+            let decoded: Script<MathOperator> =
+                <&mut JsonCodec>::from_vec(encoded.clone()).unwrap();
+
+            prop_assert_eq!(<&mut JsonCodec>::to_vec(&decoded).unwrap(), encoded);
+            prop_assert_eq!(canonical(decoded), canonical(script));
+        }
+
+        #[test]
+        fn test_arbitrary_input_never_panics(json in r#"[\[\]{}",:0-9a-z\\ ."-]{0,64}"#) {
+            let _ = <&mut JsonCodec>::from_vec::<MathOperator, Value>(Vec::from(json.clone()));
+            let _ = JsonCodec::decode_exact::<Value>(Vec::from(json));
+        }
+
+        #[test]
+        fn test_arbitrary_bytes_never_panic(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+            let _ = <&mut JsonCodec>::from_vec::<MathOperator, Value>(bytes);
+        }
+    }
+}
@@ -9,16 +9,41 @@ use alloc::string::String;
 
 use crate::prelude::*;
 
+pub mod asm;
+#[cfg(feature = "bitcoin")]
+pub mod bitcoin;
+pub mod cbor;
 pub mod dec;
 pub mod enc;
+pub mod json;
+pub mod simple;
+
+/// Describes an operator to codecs, which may identify it either by its opcode or by its name.
+///
+/// This is what [`Encode`][Encode] and [`Decode`][Decode] implementations for operators hand to
+/// [`Encoder::encode_operator`][encode] and [`Decoder::decode_operator`][decode].
+///
+/// [Encode]: enc/trait.Encode.html
+/// [Decode]: dec/trait.Decode.html
+/// [encode]: enc/trait.Encoder.html#tymethod.encode_operator
+/// [decode]: dec/trait.Decoder.html#tymethod.decode_operator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OperatorInfo {
+    /// The opcode of the operator, as used by binary codecs.
+    pub opcode: u8,
+    /// The name of the operator, as used by textual codecs, e.g. the name of its variant.
+    pub name: &'static str,
+    /// How many fields the operator carries.
+    pub fields: usize,
+}
 
 #[cfg(test)]
 mod tests {
     use alloc::vec::Vec;
 
-    use crate::codecs::codecs::simple;
     use crate::codecs::dec::Decoder;
-    use crate::codecs::enc::Encoder;
+    use crate::codecs::enc::{Encode, Encoder};
+    use crate::codecs::{simple, EncodingError};
     use crate::core::item::Item::*;
     use crate::core::value::Value::*;
     use crate::op_systems::simple_math::MathOperator::{self, *};
@@ -31,6 +56,7 @@ mod tests {
         ])
     }
 
+    #[allow(clippy::approx_constant)]
     fn example_script() -> Script<MathOperator> {
         Vec::from([
             Value(Integer(1)),
@@ -47,12 +73,34 @@ mod tests {
     fn test_encoding() {
         let decoded: Script<MathOperator> = example_script();
 
-        let encoded = <&mut simple::SimpleScriptCodec>::to_vec(&decoded);
+        let encoded = <&mut simple::SimpleScriptCodec>::to_vec(&decoded).unwrap();
         let expected = example_bytes();
 
         assert_eq!(encoded, expected);
     }
 
+    /// An operator that cannot be encoded, for checking that encoding errors are propagated.
+    #[derive(Debug)]
+    struct Unencodable;
+
+    impl Encode for Unencodable {
+        fn encode<E>(&self, _encoder: E) -> Result<E::Ok, E::Error>
+        where
+            E: Encoder,
+        {
+            Err(EncodingError::Other("Unencodable".into()).into())
+        }
+    }
+
+    #[test]
+    fn test_encoding_error() {
+        let script: Script<Unencodable> = Vec::from([Value(Integer(1)), Operator(Unencodable)]);
+
+        let encoded = <&mut simple::SimpleScriptCodec>::to_vec(&script);
+
+        assert_eq!(encoded, Err(EncodingError::Other("Unencodable".into())));
+    }
+
     #[test]
     fn test_decoding() {
         let encoded = example_bytes();
@@ -64,20 +112,190 @@ mod tests {
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct EncodingError(String);
+/// The reasons why a script cannot be encoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodingError {
+    /// The item at `index` is a value of a type that the codec cannot encode, e.g. any value other
+    /// than `Bytes` in Bitcoin scripts.
+    UnsupportedValue {
+        /// The index of the offending item in the script.
+        index: usize,
+        /// The type of the offending value, as told by `Value::type_name`.
+        found: &'static str,
+    },
+    /// The value at `index` is too long for its length to be encoded.
+    LengthOverflow {
+        /// The index of the offending item in the script.
+        index: usize,
+    },
+    /// The operator at `index` is unknown to the codec, e.g. it is missing from the operator table
+    /// used for printing assembly.
+    UnknownOperator {
+        /// The index of the offending item in the script.
+        index: usize,
+    },
+    /// The operator at `index` has an opcode that the codec reserves for something else, e.g. an
+    /// opcode below `0x80` in the simple codec, which would be decoded as a value.
+    InvalidOpcode {
+        /// The index of the offending item in the script.
+        index: usize,
+        /// The offending opcode itself.
+        opcode: u8,
+    },
+    /// Any other error, described in free text.
+    Other(String),
+}
 
-#[derive(Debug, PartialEq)]
-pub struct DecodingError(String);
+/// The reasons why some input cannot be decoded.
+///
+/// All offsets are counted in bytes from the beginning of the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodingError {
+    /// The input ended while reading `needed` bytes starting at `offset`.
+    UnexpectedEof {
+        /// Where the truncated read started.
+        offset: usize,
+        /// How many bytes were being read.
+        needed: usize,
+    },
+    /// The byte at `offset` is not a known discriminant for the item or type being decoded.
+    InvalidDiscriminant {
+        /// The position of the offending byte.
+        offset: usize,
+        /// The offending byte itself.
+        byte: u8,
+    },
+    /// The value at `offset` is well formed, but it is not of the type being decoded, e.g. a
+    /// string where a decimal was expected.
+    UnexpectedType {
+        /// Where the offending value starts.
+        offset: usize,
+        /// The type being decoded, as told by `Value::type_name`.
+        expected: &'static str,
+        /// The type of the offending value, as told by `Value::type_name`.
+        found: &'static str,
+    },
+    /// The token starting at `offset` is malformed or not expected there, e.g. a misplaced
+    /// character or an unknown key in textual formats.
+    InvalidToken {
+        /// Where the offending token starts.
+        offset: usize,
+    },
+    /// The operator at `offset` is unknown to the operator type being decoded, e.g. a misspelled
+    /// name in textual formats.
+    UnknownOperator {
+        /// Where the name or identifier of the operator starts.
+        offset: usize,
+    },
+    /// The string starting at `offset` is not valid UTF-8.
+    InvalidUtf8 {
+        /// Where the contents of the string start.
+        offset: usize,
+    },
+    /// The length at `offset` does not fit into memory on the current target.
+    LengthOverflow {
+        /// Where the length starts.
+        offset: usize,
+    },
+    /// The item at `offset` exceeds one of the limits enforced by the decoder, e.g. the maximum
+    /// length of strings or the maximum number of items in a script.
+    LimitExceeded {
+        /// Where the offending item or length starts.
+        offset: usize,
+        /// The limit that was exceeded.
+        limit: usize,
+    },
+    /// The value at `offset` is well formed, but it is out of the range of its type, e.g. a decimal
+    /// with an unsupported scale.
+    OutOfRange {
+        /// Where the offending value starts.
+        offset: usize,
+    },
+    /// The input was expected to end at `offset`, but there are more bytes after it.
+    TrailingBytes {
+        /// Where the unexpected bytes start.
+        offset: usize,
+    },
+    /// Any other error, described in free text.
+    Other(String),
+}
 
 impl Error for EncodingError {
     fn from_str(input: &str) -> Self {
-        EncodingError(input.into())
+        EncodingError::Other(input.into())
     }
 }
 
 impl Error for DecodingError {
     fn from_str(input: &str) -> Self {
-        DecodingError(input.into())
+        DecodingError::Other(input.into())
+    }
+}
+
+impl core::fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EncodingError::UnsupportedValue { index, found } => write!(
+                f,
+                "Cannot encode a value of type {} at item {}",
+                found, index
+            ),
+            EncodingError::LengthOverflow { index } => {
+                write!(f, "Length of item {} is too big to be encoded", index)
+            }
+            EncodingError::UnknownOperator { index } => {
+                write!(f, "Unknown operator at item {}", index)
+            }
+            EncodingError::InvalidOpcode { index, opcode } => {
+                write!(f, "Invalid opcode {:#04x} at item {}", opcode, index)
+            }
+            EncodingError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl core::fmt::Display for DecodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodingError::UnexpectedEof { offset, needed } => write!(
+                f,
+                "Unexpected end of input at offset {} when reading {} bytes",
+                offset, needed
+            ),
+            DecodingError::InvalidDiscriminant { offset, byte } => {
+                write!(f, "Invalid discriminant {:#04x} at offset {}", byte, offset)
+            }
+            DecodingError::UnexpectedType {
+                offset,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected a value of type {} but found {} at offset {}",
+                expected, found, offset
+            ),
+            DecodingError::InvalidToken { offset } => {
+                write!(f, "Invalid token at offset {}", offset)
+            }
+            DecodingError::UnknownOperator { offset } => {
+                write!(f, "Unknown operator at offset {}", offset)
+            }
+            DecodingError::InvalidUtf8 { offset } => {
+                write!(f, "Not a valid UTF-8 string at offset {}", offset)
+            }
+            DecodingError::LengthOverflow { offset } => {
+                write!(f, "Length at offset {} does not fit into memory", offset)
+            }
+            DecodingError::LimitExceeded { offset, limit } => {
+                write!(f, "Exceeded the limit of {} at offset {}", limit, offset)
+            }
+            DecodingError::OutOfRange { offset } => {
+                write!(f, "Value at offset {} is out of range", offset)
+            }
+            DecodingError::TrailingBytes { offset } => {
+                write!(f, "Unexpected trailing bytes at offset {}", offset)
+            }
+            DecodingError::Other(message) => f.write_str(message),
+        }
     }
 }
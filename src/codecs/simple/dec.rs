@@ -2,50 +2,54 @@ use alloc::string::String;
 use alloc::vec::Vec;
 
 use crate::{
-    core::value::Value,
-    encoding::{
-        codecs::simple::SimpleScriptCodec,
+    codecs::{
         dec::{Decode, Decoder},
-        DecodingError,
+        simple::{sign_extend, SimpleScriptCodec, SliceCodec, BITWISE_OPERATORS, MATH_OPERATORS},
+        DecodingError, OperatorInfo,
     },
-    op_systems::simple_math::MathOperator,
+    core::{
+        decimal::Decimal,
+        value::{Value, ValueRef},
+        ItemRef,
+    },
+    op_systems::{bitwise::BitwiseOperator, simple_math::MathOperator},
     prelude::*,
 };
 
-impl<'a> Decoder for &'a mut SimpleScriptCodec {
+impl<'a> Decoder for &mut SliceCodec<'a> {
     type Error = DecodingError;
 
     fn decode_i128(&mut self) -> Result<i128, Self::Error> {
-        let length = self.read_byte()? as usize - 0x02;
-        let significant_bytes = self.read_bytes(length)?;
-        let mut sixteen_bytes = [0u8; 16];
-        sixteen_bytes[..length].copy_from_slice(&significant_bytes);
-        let integer = i128::from_le_bytes(sixteen_bytes);
+        let length = self.read_discriminant(0x03..=0x12)? - 0x02;
+        let integer = sign_extend(self.read_slice(length)?);
 
         Ok(integer)
     }
 
     fn decode_f64(&mut self) -> Result<f64, Self::Error> {
-        self.read_byte()?;
-        let bytes = self.read_bytes(8)?;
+        self.read_discriminant(0x02..=0x02)?;
+        let bytes = self.read_slice(8)?;
         let mut eight_bytes = [0u8; 8];
-        eight_bytes.copy_from_slice(&bytes);
+        eight_bytes.copy_from_slice(bytes);
         let float = f64::from_le_bytes(eight_bytes);
 
         Ok(float)
     }
 
     fn decode_string(&mut self) -> Result<String, Self::Error> {
-        let length_length = self.read_byte()? as usize - 0x13;
-        let length_bytes = self.read_bytes(length_length)?;
-        let mut eight_length_bytes = [0u8; 8];
-        eight_length_bytes[..length_length].copy_from_slice(&length_bytes);
-        let length = usize::from_le_bytes(eight_length_bytes);
-        let bytes = self.read_bytes(length)?;
-        let string = String::from_utf8(bytes.into())
-            .map_err(|_| DecodingError::from_str("Not a valid UTF-8 string"));
+        self.decode_str().map(Into::into)
+    }
+
+    fn decode_bytes(&mut self) -> Result<Vec<u8>, Self::Error> {
+        self.decode_byte_slice().map(Into::into)
+    }
 
-        string
+    fn decode_value(&mut self) -> Result<Value, Self::Error> {
+        self.decode_value_ref().map(Value::from)
+    }
+
+    fn decode_operator(&mut self, _operators: &[OperatorInfo]) -> Result<u8, Self::Error> {
+        self.read_byte()
     }
 
     fn decode_item<Op, Val>(&mut self) -> Result<Item<Op, Val>, Self::Error>
@@ -69,7 +73,8 @@ impl<'a> Decoder for &'a mut SimpleScriptCodec {
         let mut script = Script::<Op, Val>::new();
 
         while self.bytes_left() > 0 {
-            let item = self.decode_item().unwrap();
+            self.check_limits(script.len())?;
+            let item = self.decode_item()?;
             script.push(item);
         }
 
@@ -81,84 +86,360 @@ impl<'a> Decoder for &'a mut SimpleScriptCodec {
         Op: core::fmt::Debug + Decode,
         Val: core::fmt::Debug + Decode,
     {
-        let mut codec = SimpleScriptCodec::from_data(input);
-        let script = <&mut SimpleScriptCodec as Decoder>::decode_script(&mut &mut codec);
+        let mut codec = SliceCodec::new(&input);
+
+        (&mut codec).decode_script()
+    }
 
-        script
+    fn position(&self) -> usize {
+        self.cursor
     }
 
     fn peek_byte(&self) -> Result<&u8, Self::Error> {
         self.data
             .get(self.cursor)
-            .ok_or_else(|| DecodingError::from_str("Decoder cursor hit end of vector"))
+            .ok_or(DecodingError::UnexpectedEof {
+                offset: self.cursor,
+                needed: 1,
+            })
     }
 
     fn read_byte(&mut self) -> Result<u8, Self::Error> {
-        if self.cursor < self.data.len() {
-            let byte = self.data[self.cursor];
-            self.cursor += 1;
+        let byte = *self.peek_byte()?;
+        self.cursor += 1;
+
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&[u8], Self::Error> {
+        self.read_slice(length)
+    }
+}
+
+impl<'a> SliceCodec<'a> {
+    /// Decodes a single value, borrowing the payload of strings, bytes and big integers from the
+    /// decoded bytes instead of copying it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::codecs::simple::SliceCodec;
+    /// use scriptful::core::value::ValueRef;
+    ///
+    /// let mut codec = SliceCodec::new(&[0x36, 0x02, 0xca, 0xfe, 0x04, 0xff, 0x00]);
+    ///
+    /// assert_eq!(codec.decode_value_ref(), Ok(ValueRef::Bytes(&[0xca, 0xfe])));
+    /// assert_eq!(codec.decode_value_ref(), Ok(ValueRef::Integer(255)));
+    /// assert!(codec.decode_value_ref().is_err());
+    /// ```
+    pub fn decode_value_ref(&mut self) -> Result<ValueRef<'a>, DecodingError> {
+        let mut codec = self;
+        let discriminant = codec.peek_byte()?;
 
-            Ok(byte)
+        match discriminant {
+            0x00 => codec.read_byte().map(|_| ValueRef::Boolean(false)),
+            0x01 => codec.read_byte().map(|_| ValueRef::Boolean(true)),
+            0x02 => codec.decode_f64().map(ValueRef::Float),
+            0x03..=0x012 => codec.decode_i128().map(ValueRef::Integer),
+            0x13..=0x1b => codec.decode_str().map(ValueRef::String),
+            0x1c => codec.read_byte().map(|_| ValueRef::Null),
+            0x1d..=0x2c => codec.decode_decimal().map(ValueRef::Decimal),
+            0x35..=0x3d => codec.decode_byte_slice().map(ValueRef::Bytes),
+            0x3e..=0x45 => codec.decode_timestamp().map(ValueRef::Timestamp),
+            #[cfg(feature = "bigint")]
+            0x2d..=0x34 => codec.decode_bigint().map(ValueRef::BigInt),
+            &byte => Err(DecodingError::InvalidDiscriminant {
+                offset: codec.cursor,
+                byte,
+            }),
+        }
+    }
+
+    /// Decodes a single item, same as [`decode_item`][decode_item], but with its value, if any,
+    /// decoded by [`decode_value_ref`][decode_value_ref].
+    ///
+    /// [decode_item]: ../dec/trait.Decoder.html#tymethod.decode_item
+    /// [decode_value_ref]: #method.decode_value_ref
+    pub fn decode_item_ref<Op>(&mut self) -> Result<ItemRef<'a, Op>, DecodingError>
+    where
+        Op: core::fmt::Debug + Decode,
+    {
+        let mut codec = self;
+        let byte = codec.peek_byte()?;
+        if *byte < 0x80 {
+            codec.decode_value_ref().map(Item::Value)
         } else {
-            Err(DecodingError::from_str(
-                "Decoder cursor hit end of vector when reading a single byte",
-            ))
+            Op::decode(&mut codec).map(Item::Operator)
         }
     }
 
-    fn read_bytes(&mut self, length: usize) -> Result<&[u8], Self::Error> {
-        if self.cursor + length <= self.data.len() {
-            let bytes = &self.data[self.cursor..self.cursor + length];
-            self.cursor += length;
+    /// Fails if decoding one more item after `items` would exceed the limits.
+    pub(super) fn check_limits(&self, items: usize) -> Result<(), DecodingError> {
+        if items == 0 && self.bytes_left() > self.limits.max_script_length {
+            return Err(DecodingError::LimitExceeded {
+                offset: self.cursor,
+                limit: self.limits.max_script_length,
+            });
+        }
+
+        if items == self.limits.max_items {
+            return Err(DecodingError::LimitExceeded {
+                offset: self.cursor,
+                limit: self.limits.max_items,
+            });
+        }
+
+        Ok(())
+    }
 
-            Ok(bytes)
+    /// Reads `length` bytes, borrowing them for as long as the decoded bytes live, rather than
+    /// for as long as the codec does.
+    fn read_slice(&mut self, length: usize) -> Result<&'a [u8], DecodingError> {
+        let start = self.cursor;
+        let bytes = start
+            .checked_add(length)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or(DecodingError::UnexpectedEof {
+                offset: start,
+                needed: length,
+            })?;
+        self.cursor += length;
+
+        Ok(bytes)
+    }
+
+    /// Reads a discriminant byte, making sure that it is in the expected range.
+    fn read_discriminant(
+        &mut self,
+        range: core::ops::RangeInclusive<u8>,
+    ) -> Result<usize, DecodingError> {
+        let offset = self.cursor;
+        let byte = (&mut *self).read_byte()?;
+
+        if range.contains(&byte) {
+            Ok(byte as usize)
         } else {
-            Err(DecodingError::from_str(&alloc::format!(
-                "Decoder cursor hit end of vector when reading {} bytes, while the decoder only had {} in its data vector",
-                length, self.bytes_left()
-            )))
+            Err(DecodingError::InvalidDiscriminant { offset, byte })
+        }
+    }
+
+    /// Reads a little endian unsigned length of `length_length` bytes for a `String` or `Bytes`
+    /// value, making sure that it is within the limits.
+    fn read_string_length(&mut self, length_length: usize) -> Result<usize, DecodingError> {
+        let offset = self.cursor;
+        let mut codec = self;
+        let length = read_length(&mut codec, length_length)?;
+
+        if length > codec.limits.max_string_length {
+            return Err(DecodingError::LimitExceeded {
+                offset,
+                limit: codec.limits.max_string_length,
+            });
         }
+
+        Ok(length)
+    }
+
+    fn decode_str(&mut self) -> Result<&'a str, DecodingError> {
+        let length_length = self.read_discriminant(0x13..=0x1b)? - 0x13;
+        let length = self.read_string_length(length_length)?;
+        let offset = self.cursor;
+        let bytes = self.read_slice(length)?;
+
+        core::str::from_utf8(bytes).map_err(|_| DecodingError::InvalidUtf8 { offset })
+    }
+
+    fn decode_byte_slice(&mut self) -> Result<&'a [u8], DecodingError> {
+        let length_length = self.read_discriminant(0x35..=0x3d)? - 0x35;
+        let length = self.read_string_length(length_length)?;
+
+        self.read_slice(length)
+    }
+
+    /// Timestamps are encoded as a first byte telling the length of the number of seconds, and then
+    /// the number of seconds itself in little endian two's complement, without redundant sign
+    /// bytes.
+    fn decode_timestamp(&mut self) -> Result<i64, DecodingError> {
+        let offset = self.cursor;
+        let length = self.read_discriminant(0x3e..=0x45)? - 0x3d;
+        let seconds = sign_extend(self.read_slice(length)?);
+
+        i64::try_from(seconds).map_err(|_| DecodingError::OutOfRange { offset })
+    }
+
+    /// Decimals are encoded as a first byte telling the length of the mantissa, then the scale,
+    /// and finally the mantissa itself in little endian two's complement, without redundant sign
+    /// bytes.
+    fn decode_decimal(&mut self) -> Result<Decimal, DecodingError> {
+        let offset = self.cursor;
+        let mantissa_length = self.read_discriminant(0x1d..=0x2c)? - 0x1c;
+        let scale = (&mut *self).read_byte()?;
+        let mantissa = sign_extend(self.read_slice(mantissa_length)?);
+
+        Decimal::new(mantissa, scale).ok_or(DecodingError::OutOfRange { offset })
+    }
+
+    /// Big integers are encoded as a first byte telling the length of the length, then the length,
+    /// and finally the number itself in little endian two's complement.
+    #[cfg(feature = "bigint")]
+    fn decode_bigint(&mut self) -> Result<&'a [u8], DecodingError> {
+        let length_length = self.read_discriminant(0x2d..=0x34)? - 0x2c;
+        let length = self.read_string_length(length_length)?;
+
+        self.read_slice(length)
+    }
+}
+
+/// Decoding is done by a [`SliceCodec`][SliceCodec] over the data, starting at the cursor.
+///
+/// [SliceCodec]: struct.SliceCodec.html
+impl Decoder for &mut SimpleScriptCodec {
+    type Error = DecodingError;
+
+    fn decode_i128(&mut self) -> Result<i128, Self::Error> {
+        self.with_slice(|mut codec| codec.decode_i128())
+    }
+
+    fn decode_f64(&mut self) -> Result<f64, Self::Error> {
+        self.with_slice(|mut codec| codec.decode_f64())
+    }
+
+    fn decode_string(&mut self) -> Result<String, Self::Error> {
+        self.with_slice(|mut codec| codec.decode_string())
+    }
+
+    fn decode_bytes(&mut self) -> Result<Vec<u8>, Self::Error> {
+        self.with_slice(|mut codec| codec.decode_bytes())
+    }
+
+    fn decode_value(&mut self) -> Result<Value, Self::Error> {
+        self.with_slice(|mut codec| codec.decode_value())
+    }
+
+    fn decode_operator(&mut self, operators: &[OperatorInfo]) -> Result<u8, Self::Error> {
+        self.with_slice(|mut codec| codec.decode_operator(operators))
+    }
+
+    fn decode_item<Op, Val>(&mut self) -> Result<Item<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        self.with_slice(|mut codec| codec.decode_item())
+    }
+
+    fn decode_script<Op, Val>(&mut self) -> Result<Script<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        self.with_slice(|mut codec| codec.decode_script())
+    }
+
+    fn from_vec<Op, Val>(input: Vec<u8>) -> Result<Script<Op, Val>, Self::Error>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        <&mut SliceCodec as Decoder>::from_vec(input)
+    }
+
+    fn position(&self) -> usize {
+        self.cursor
+    }
+
+    fn peek_byte(&self) -> Result<&u8, Self::Error> {
+        self.data
+            .get(self.cursor)
+            .ok_or(DecodingError::UnexpectedEof {
+                offset: self.cursor,
+                needed: 1,
+            })
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Self::Error> {
+        self.with_slice(|mut codec| codec.read_byte())
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&[u8], Self::Error> {
+        self.with_slice(|codec| codec.read_slice(length))
+    }
+}
+
+impl SimpleScriptCodec {
+    /// Runs `decode` on a [`SliceCodec`][SliceCodec] over the data, and then moves the cursor as
+    /// far as it went.
+    ///
+    /// [SliceCodec]: struct.SliceCodec.html
+    fn with_slice<'a, T, F>(&'a mut self, decode: F) -> T
+    where
+        F: FnOnce(&mut SliceCodec<'a>) -> T,
+    {
+        let mut codec = SliceCodec {
+            data: &self.data,
+            cursor: self.cursor,
+            limits: self.limits,
+        };
+        let decoded = decode(&mut codec);
+        self.cursor = codec.cursor;
+
+        decoded
     }
 }
 
+/// Reads a little endian unsigned length of up to 8 bytes, making sure that it fits into a `usize`
+/// on the current target.
+fn read_length<D>(decoder: &mut D, length_length: usize) -> Result<usize, <D as Decoder>::Error>
+where
+    D: Decoder,
+{
+    let offset = decoder.position();
+    let length_bytes = decoder.read_bytes(length_length)?;
+    let mut eight_length_bytes = [0u8; 8];
+    eight_length_bytes
+        .get_mut(..length_bytes.len())
+        .ok_or(DecodingError::LengthOverflow { offset })?
+        .copy_from_slice(length_bytes);
+    let length = u64::from_le_bytes(eight_length_bytes);
+
+    usize::try_from(length).map_err(|_| DecodingError::LengthOverflow { offset }.into())
+}
+
 impl Decode for MathOperator {
     fn decode<D>(decoder: &mut D) -> Result<Self, <D as Decoder>::Error>
     where
         D: Decoder,
     {
-        let discriminant = decoder.read_byte()? - 0x80;
+        let offset = decoder.position();
 
-        match discriminant {
-            0x00 => Ok(MathOperator::Add),
-            0x01 => Ok(MathOperator::Equal),
-            0x02 => Ok(MathOperator::Mul),
-            0x03 => Ok(MathOperator::Not),
-            0x04 => Ok(MathOperator::Sub),
-            x => Err(<D as Decoder>::Error::from_str(&alloc::format!(
-                "Unsupported MathOperator {}",
-                x
-            ))),
+        match decoder.decode_operator(&MATH_OPERATORS)? {
+            0x80 => Ok(MathOperator::Add),
+            0x81 => Ok(MathOperator::Equal),
+            0x82 => Ok(MathOperator::Mul),
+            0x83 => Ok(MathOperator::Not),
+            0x84 => Ok(MathOperator::Sub),
+            0x85 => Ok(MathOperator::StrictEqual),
+            byte => Err(DecodingError::InvalidDiscriminant { offset, byte }.into()),
         }
     }
 }
 
-impl Decode for Value {
+impl Decode for BitwiseOperator {
     fn decode<D>(decoder: &mut D) -> Result<Self, <D as Decoder>::Error>
     where
         D: Decoder,
     {
-        let discriminant = decoder.peek_byte()?;
+        let offset = decoder.position();
 
-        match discriminant {
-            0x00 => decoder.read_byte().map(|_| Value::Boolean(false)),
-            0x01 => decoder.read_byte().map(|_| Value::Boolean(true)),
-            0x02 => decoder.decode_f64().map(Value::Float),
-            0x03..=0x012 => decoder.decode_i128().map(Value::Integer),
-            0x13..=0x79 => decoder.decode_string().map(Value::String),
-            x => Err(<D as Decoder>::Error::from_str(&alloc::format!(
-                "Unsupported value discriminant {}",
-                x
-            ))),
+        match decoder.decode_operator(&BITWISE_OPERATORS)? {
+            0x90 => Ok(BitwiseOperator::And),
+            0x91 => Ok(BitwiseOperator::Invert),
+            0x92 => Ok(BitwiseOperator::Or),
+            0x93 => Ok(BitwiseOperator::PopCount),
+            0x94 => Ok(BitwiseOperator::ShiftLeft),
+            0x95 => Ok(BitwiseOperator::ShiftRight),
+            0x96 => Ok(BitwiseOperator::Xor),
+            byte => Err(DecodingError::InvalidDiscriminant { offset, byte }.into()),
         }
     }
 }
@@ -1,159 +1,231 @@
 use alloc::vec::Vec;
 
 use crate::{
-    core::value::Value,
-    encoding::{
-        codecs::simple::{significant_bytes_count, SimpleScriptCodec},
+    codecs::{
         enc::{Encode, EncodeSequence, Encoder},
+        simple::{
+            signed_bytes_count, significant_bytes_count, SimpleScriptCodec, BITWISE_OPERATORS,
+            MATH_OPERATORS,
+        },
+        EncodingError, OperatorInfo,
     },
-    op_systems::simple_math::MathOperator,
+    core::{decimal::Decimal, value::Value},
+    op_systems::{bitwise::BitwiseOperator, simple_math::MathOperator},
     prelude::*,
 };
 
-impl<'a> Encoder for &'a mut SimpleScriptCodec {
+impl Encoder for &mut SimpleScriptCodec {
     type Ok = ();
+    type Error = EncodingError;
     type EncodeSequence = Self;
 
-    fn to_vec<Op, Val>(input: &Script<Op, Val>) -> Vec<u8>
+    fn to_vec<Op, Val>(input: &Script<Op, Val>) -> Result<Vec<u8>, Self::Error>
     where
         Op: core::fmt::Debug + Encode,
         Val: core::fmt::Debug + Encode,
     {
         let mut codec = SimpleScriptCodec::default();
-        input.encode(&mut codec);
+        input.encode(&mut codec)?;
 
-        codec.data()
+        Ok(codec.data())
     }
 
-    fn write_u8(self, input: u8) -> Self::Ok {
+    fn write_u8(self, input: u8) -> Result<Self::Ok, Self::Error> {
         self.data_push(input);
+
+        Ok(())
+    }
+
+    fn write_bytes(self, input: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.data.extend_from_slice(input);
+
+        Ok(())
     }
 
-    fn write_bytes(self, input: &[u8]) -> Self::Ok {
-        for byte in input {
-            self.write_u8(*byte);
+    fn encode_value(self, input: &Value) -> Result<Self::Ok, Self::Error> {
+        self.write_bytes(&value_bytes(input))
+    }
+
+    /// Operators are encoded as their opcode, followed by their fields, if any.
+    ///
+    /// Opcodes below `0x80` are only allowed for operators that are fields of other operators, as
+    /// they would otherwise be decoded as values.
+    fn encode_operator(self, operator: &OperatorInfo) -> Result<Self::EncodeSequence, Self::Error> {
+        if self.depth == 0 && operator.opcode < 0x80 {
+            return Err(EncodingError::InvalidOpcode {
+                index: self.items,
+                opcode: operator.opcode,
+            });
         }
+        self.data_push(operator.opcode);
+        self.depth += 1;
+
+        Ok(self)
     }
 
-    fn encode_item<Op, Val>(self, input: &Item<Op, Val>) -> Self::Ok
+    fn encode_item<Op, Val>(self, input: &Item<Op, Val>) -> Result<Self::Ok, Self::Error>
     where
         Op: core::fmt::Debug + Encode,
         Val: core::fmt::Debug + Encode,
     {
-        input.encode(self);
+        input.encode(self)
     }
 
-    fn encode_seq(self) -> Self::EncodeSequence {
-        self
+    /// Sequences have no header nor terminator, so their length is ignored.
+    fn encode_seq(self, _length: usize) -> Result<Self::EncodeSequence, Self::Error> {
+        Ok(self)
     }
 }
 
-impl<'a> EncodeSequence for &'a mut SimpleScriptCodec {
+impl EncodeSequence for &mut SimpleScriptCodec {
     type Ok = ();
+    type Error = EncodingError;
 
-    fn encode_element<T: Sized>(&mut self, value: &T)
+    fn encode_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Encode,
+        T: Encode + Sized,
     {
-        value.encode(&mut **self)
-    }
+        value.encode(&mut **self)?;
+        if self.depth == 0 {
+            self.items += 1;
+        }
 
-    fn end(self) -> Self::Ok {
-        ()
+        Ok(())
     }
-}
 
-impl<Op, Val> Encode for Script<Op, Val>
-where
-    Op: core::fmt::Debug + Encode,
-    Val: core::fmt::Debug + Encode,
-{
-    fn encode<E>(&self, encoder: E) -> <E as Encoder>::Ok
-    where
-        E: Encoder,
-    {
-        let mut seq = encoder.encode_seq();
-        for item in self {
-            seq.encode_element(item);
-        }
-        seq.end()
+    /// Ends either an operator or the script itself, which can only end after all of its operators.
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.depth = self.depth.saturating_sub(1);
+
+        Ok(())
     }
 }
 
-impl<Op, Val> Encode for Item<Op, Val>
-where
-    Op: core::fmt::Debug + Encode,
-    Val: core::fmt::Debug + Encode,
-{
-    fn encode<E>(&self, encoder: E) -> <E as Encoder>::Ok
+impl Encode for MathOperator {
+    fn encode<E>(&self, encoder: E) -> Result<<E as Encoder>::Ok, <E as Encoder>::Error>
     where
         E: Encoder,
     {
-        match self {
-            Item::Operator(op) => op.encode(encoder),
-            Item::Value(val) => val.encode(encoder),
-        }
+        let index = match self {
+            MathOperator::Add => 0,
+            MathOperator::Equal => 1,
+            MathOperator::Mul => 2,
+            MathOperator::Not => 3,
+            MathOperator::Sub => 4,
+            MathOperator::StrictEqual => 5,
+        };
+
+        encoder.encode_operator(&MATH_OPERATORS[index])?.end()
     }
 }
 
-impl Encode for crate::op_systems::simple_math::MathOperator {
-    fn encode<E>(&self, encoder: E) -> <E as Encoder>::Ok
+impl Encode for BitwiseOperator {
+    fn encode<E>(&self, encoder: E) -> Result<<E as Encoder>::Ok, <E as Encoder>::Error>
     where
         E: Encoder,
     {
-        let discriminant = match self {
-            MathOperator::Add => 0x00,
-            MathOperator::Equal => 0x01,
-            MathOperator::Mul => 0x02,
-            MathOperator::Not => 0x03,
-            MathOperator::Sub => 0x04,
+        let index = match self {
+            BitwiseOperator::And => 0,
+            BitwiseOperator::Invert => 1,
+            BitwiseOperator::Or => 2,
+            BitwiseOperator::PopCount => 3,
+            BitwiseOperator::ShiftLeft => 4,
+            BitwiseOperator::ShiftRight => 5,
+            BitwiseOperator::Xor => 6,
         };
 
-        encoder.write_u8(discriminant + 0x80)
+        encoder.encode_operator(&BITWISE_OPERATORS[index])?.end()
     }
 }
 
-impl Encode for Value {
-    fn encode<E>(&self, encoder: E) -> <E as Encoder>::Ok
-    where
-        E: Encoder,
-    {
-        let bytes: Vec<u8> = match self {
-            Value::Boolean(val) => match val {
-                false => Vec::from([0x00]),
-                true => Vec::from([0x01]),
-            },
-            Value::Float(val) => {
-                let num_bytes = val.to_le_bytes();
-                let first_byte = 0x02;
-                [&[first_byte], &num_bytes[..]].concat()
-            }
-            Value::Integer(val) => {
-                let num_bytes = val.to_le_bytes();
-                let significant_bytes_count = significant_bytes_count(*val);
-                let first_byte = 0x03 + significant_bytes_count as u8;
+/// Values are encoded as described in the [specification](index.html#specification).
+fn value_bytes(input: &Value) -> Vec<u8> {
+    match input {
+        Value::Boolean(val) => match val {
+            false => Vec::from([0x00]),
+            true => Vec::from([0x01]),
+        },
+        Value::Float(val) => {
+            let num_bytes = val.to_le_bytes();
+            let first_byte = 0x02;
+            [&[first_byte], &num_bytes[..]].concat()
+        }
+        Value::Integer(val) => {
+            let num_bytes = val.to_le_bytes();
+            let length = signed_bytes_count(*val);
+            let first_byte = 0x02 + length as u8;
 
-                [&[first_byte], &num_bytes[..significant_bytes_count + 1]].concat()
+            [&[first_byte], &num_bytes[..length]].concat()
+        }
+        Value::String(val) => {
+            if val.is_empty() {
+                Vec::from([0x13])
+            } else {
+                let str_bytes = val.as_bytes();
+                let str_bytes_len_as_bytes = (str_bytes.len() as u64).to_le_bytes();
+                let str_bytes_len_sbc = 1 + significant_bytes_count(str_bytes.len() as i128);
+                let first_byte = 0x13 + str_bytes_len_sbc as u8;
+
+                [
+                    &[first_byte],
+                    &str_bytes_len_as_bytes[..str_bytes_len_sbc],
+                    str_bytes,
+                ]
+                .concat()
             }
-            Value::String(val) => {
-                if val.len() == 0 {
-                    Vec::from([0x13])
-                } else {
-                    let str_bytes = val.as_bytes();
-                    let str_bytes_len_as_bytes = str_bytes.len().to_le_bytes();
-                    let str_bytes_len_sbc = 1 + significant_bytes_count(str_bytes.len() as i128);
-                    let first_byte = 0x13 + str_bytes_len_sbc as u8;
-
-                    [
-                        &[first_byte],
-                        &str_bytes_len_as_bytes[..str_bytes_len_sbc],
-                        &str_bytes[..],
-                    ]
-                    .concat()
-                }
+        }
+        Value::Null => Vec::from([0x1c]),
+        Value::Decimal(val) => decimal_bytes(val),
+        Value::Bytes(val) => {
+            if val.is_empty() {
+                Vec::from([0x35])
+            } else {
+                let len_as_bytes = (val.len() as u64).to_le_bytes();
+                let len_sbc = 1 + significant_bytes_count(val.len() as i128);
+                let first_byte = 0x35 + len_sbc as u8;
+
+                [&[first_byte], &len_as_bytes[..len_sbc], &val[..]].concat()
             }
-        };
+        }
+        Value::Timestamp(val) => {
+            let num_bytes = val.to_le_bytes();
+            let length = signed_bytes_count(i128::from(*val));
+            let first_byte = 0x3d + length as u8;
 
-        encoder.write_bytes(&bytes)
+            [&[first_byte], &num_bytes[..length]].concat()
+        }
+        #[cfg(feature = "bigint")]
+        Value::BigInt(val) => bigint_bytes(val),
     }
 }
+
+/// Decimals are encoded as a first byte telling the length of the mantissa, then the scale, and
+/// finally the mantissa itself in little endian two's complement, without redundant sign bytes.
+fn decimal_bytes(input: &Decimal) -> Vec<u8> {
+    let mantissa_bytes = input.mantissa().to_le_bytes();
+    let mantissa_length = signed_bytes_count(input.mantissa());
+    let first_byte = 0x1c + mantissa_length as u8;
+
+    [
+        &[first_byte, input.scale()],
+        &mantissa_bytes[..mantissa_length],
+    ]
+    .concat()
+}
+
+/// Big integers are encoded as a first byte telling the length of the length, then the length, and
+/// finally the number itself in little endian two's complement.
+#[cfg(feature = "bigint")]
+fn bigint_bytes(input: &num_bigint::BigInt) -> Vec<u8> {
+    let int_bytes = input.to_signed_bytes_le();
+    let int_bytes_len_as_bytes = (int_bytes.len() as u64).to_le_bytes();
+    let int_bytes_len_sbc = 1 + significant_bytes_count(int_bytes.len() as i128);
+    let first_byte = 0x2c + int_bytes_len_sbc as u8;
+
+    [
+        &[first_byte],
+        &int_bytes_len_as_bytes[..int_bytes_len_sbc],
+        &int_bytes[..],
+    ]
+    .concat()
+}
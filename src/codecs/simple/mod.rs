@@ -5,29 +5,187 @@
 //! As a matter of fact, this is just as concise as CBOR, and the main difference is endianness and
 //! simpler logic for encoding and decoding.
 //!
-//! | Value                | CBOR                                   | Simple                               |
-//! |----------------------|----------------------------------------|--------------------------------------|
-//! | `false`              | `F4`                                   | `00`                                 |
-//! | `3.14`               | `FB40091EB851EB851F`                   | `021F85EB51B81E0940`                 |
-//! | `255`                | `18FF`                                 | `03FF`                               |
-//! | `999999999999999999` | `1B0DE0B6B3A763FFFF`                   | `0AFFFF63A7B3B6E00D`                 |
-//! | `i128::MIN`          | `C3507FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF` | `1200000000000000000000000000000080` |
-//! | `i128::MAX`          | `C2507FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF` | `12FFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7F` |
-//! | `""`                 | `60`                                   | `13`                                 |
-//! | `"Hello, World!"`    | `6D48656C6C6F2C20576F726C6421`         | `140D48656C6C6F2C20576F726C6421`     |
+//! | Value                              | CBOR                                   | Simple                               |
+//! |------------------------------------|----------------------------------------|--------------------------------------|
+//! | `false`                            | `F4`                                   | `00`                                 |
+//! | `3.14`                             | `FB40091EB851EB851F`                   | `021F85EB51B81E0940`                 |
+//! | `255`                              | `18FF`                                 | `04FF00`                             |
+//! | `999999999999999999`               | `1B0DE0B6B3A763FFFF`                   | `0AFFFF63A7B3B6E00D`                 |
+//! | `i128::MIN`                        | `C3507FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF` | `1200000000000000000000000000000080` |
+//! | `i128::MAX`                        | `C2507FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF` | `12FFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7F` |
+//! | `""`                               | `60`                                   | `13`                                 |
+//! | `"Hello, World!"`                  | `6D48656C6C6F2C20576F726C6421`         | `140D48656C6C6F2C20576F726C6421`     |
+//! | `null`                             | `F6`                                   | `1C`                                 |
+//! | `-12.34` (decimal)                 | `C482213904D1`                         | `1E022EFB`                           |
+//! | `2^64` (bigint)                    | `C249010000000000000000`               | `2D09000000000000000001`             |
+//! | `0xCAFE` (bytes)                   | `42CAFE`                               | `3602CAFE`                           |
+//! | `2009-01-03T18:15:05Z` (timestamp) | `C11A495FAB29`                         | `4129AB5F49`                         |
+//!
+//! Operators are encoded as a single byte with the most significant bit set, so that they can be
+//! told apart from values. Every operator system uses a different range of discriminants, so that
+//! they can be combined in a single script:
+//!
+//! | Operator system                          | Discriminants  |
+//! |------------------------------------------|----------------|
+//! | [`MathOperator`][MathOperator]           | `80` to `85`   |
+//! | [`BitwiseOperator`][BitwiseOperator]     | `90` to `96`   |
+//!
+//! # Specification
+//!
+//! This is version 1 of the format, as told by [`VERSION`][VERSION]. Any change to how existing
+//! items are encoded or decoded shall bump the version. Assigning meaning to reserved
+//! discriminants does not.
+//!
+//! A script is encoded as the concatenation of the encodings of its items, with no header, item
+//! count or terminator. Every item starts with a discriminant byte, which tells whether the item is
+//! a value (`00` to `7F`) or an operator (`80` to `FF`), and how the bytes that follow it, if any,
+//! shall be interpreted. All multi-byte numbers are little endian.
+//!
+//! | Discriminant | Item              | Followed by                                                       |
+//! |--------------|-------------------|-------------------------------------------------------------------|
+//! | `00`         | `Boolean(false)`  | Nothing.                                                          |
+//! | `01`         | `Boolean(true)`   | Nothing.                                                          |
+//! | `02`         | `Float`           | 8 bytes: IEEE 754 binary64.                                       |
+//! | `03` to `12` | `Integer`         | `d - 02` bytes: two's complement.                                 |
+//! | `13`         | `String("")`      | Nothing.                                                          |
+//! | `14` to `1B` | `String`          | `d - 13` bytes: unsigned length `n`, `n` bytes: UTF-8.            |
+//! | `1C`         | `Null`            | Nothing.                                                          |
+//! | `1D` to `2C` | `Decimal`         | 1 byte: scale, `d - 1C` bytes: two's complement mantissa.         |
+//! | `2D` to `34` | `BigInt`          | `d - 2C` bytes: unsigned length `n`, `n` bytes: two's complement. |
+//! | `35`         | `Bytes([])`       | Nothing.                                                          |
+//! | `36` to `3D` | `Bytes`           | `d - 35` bytes: unsigned length `n`, `n` bytes.                   |
+//! | `3E` to `45` | `Timestamp`       | `d - 3D` bytes: two's complement seconds.                         |
+//! | `46` to `7F` | Reserved.         |                                                                   |
+//! | `80` to `85` | `MathOperator`    | Nothing.                                                          |
+//! | `86` to `8F` | Reserved.         |                                                                   |
+//! | `90` to `96` | `BitwiseOperator` | Nothing.                                                          |
+//! | `97` to `FF` | Reserved.         |                                                                   |
+//!
+//! Where `d` stands for the discriminant itself. Two's complement numbers are sign-extended, i.e.
+//! their most significant bit tells their sign.
+//!
+//! Operators with fields, such as those derived with `#[derive(Encode)]`, are followed by the
+//! encodings of their fields, in order. Operators that are fields of other operators may take any
+//! discriminant, but encoders refuse to encode items that are operators with a discriminant below
+//! `80`, as those would be decoded as values.
+//!
+//! Encoders always produce the shortest possible encoding: numbers and lengths have no redundant
+//! leading bytes, and empty strings and bytes use their dedicated discriminants. Decoders, however,
+//! also accept longer encodings of the same item, so that the decoding of a script may be shorter
+//! when encoded again. `BigInt` values can only be decoded when the `bigint` feature is enabled.
+//!
+//! As items are self-delimiting, scripts can also be decoded item by item while their bytes are
+//! still arriving, with a [`StreamDecoder`][StreamDecoder].
 //!
 //! [CBOR]: https://www.rfc-editor.org/rfc/rfc8949
 //! [Value]: ../../../core/value/enum.Value.html
+//! [MathOperator]: ../../op_systems/simple_math/enum.MathOperator.html
+//! [BitwiseOperator]: ../../op_systems/bitwise/enum.BitwiseOperator.html
+//! [VERSION]: constant.VERSION.html
+//! [StreamDecoder]: stream/struct.StreamDecoder.html
 
 use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::codecs::dec::Decode;
+use crate::codecs::{DecodingError, OperatorInfo};
+use crate::core::ItemRef;
 
 pub mod dec;
 pub mod enc;
+pub mod stream;
+
+/// The version of the format described in the [specification](index.html#specification).
+pub const VERSION: u32 = 1;
 
+/// The variants of [`MathOperator`][MathOperator], in declaration order.
+///
+/// [MathOperator]: ../../op_systems/simple_math/enum.MathOperator.html
+const MATH_OPERATORS: [OperatorInfo; 6] = [
+    operator(0x80, "Add"),
+    operator(0x81, "Equal"),
+    operator(0x82, "Mul"),
+    operator(0x83, "Not"),
+    operator(0x84, "Sub"),
+    operator(0x85, "StrictEqual"),
+];
+
+/// The variants of [`BitwiseOperator`][BitwiseOperator], in declaration order.
+///
+/// [BitwiseOperator]: ../../op_systems/bitwise/enum.BitwiseOperator.html
+const BITWISE_OPERATORS: [OperatorInfo; 7] = [
+    operator(0x90, "And"),
+    operator(0x91, "Invert"),
+    operator(0x92, "Or"),
+    operator(0x93, "PopCount"),
+    operator(0x94, "ShiftLeft"),
+    operator(0x95, "ShiftRight"),
+    operator(0x96, "Xor"),
+];
+
+/// Describes an operator with no fields.
+const fn operator(opcode: u8, name: &'static str) -> OperatorInfo {
+    OperatorInfo {
+        opcode,
+        name,
+        fields: 0,
+    }
+}
+
+/// The limits that [`SimpleScriptCodec`][SimpleScriptCodec] enforces when decoding, so that
+/// untrusted input cannot make it use arbitrary amounts of memory or time.
+///
+/// Inputs that exceed any of these limits fail to decode with a [`DecodingError`][DecodingError].
+///
+/// [SimpleScriptCodec]: struct.SimpleScriptCodec.html
+/// [DecodingError]: ../enum.DecodingError.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum length in bytes of an encoded script.
+    pub max_script_length: usize,
+    /// The maximum number of items in a script.
+    pub max_items: usize,
+    /// The maximum length in bytes of the contents of a `String`, `Bytes` or `BigInt` value.
+    pub max_string_length: usize,
+}
+
+impl Limits {
+    /// No limits at all, other than the length of the input itself.
+    pub const UNLIMITED: Limits = Limits {
+        max_script_length: usize::MAX,
+        max_items: usize::MAX,
+        max_string_length: usize::MAX,
+    };
+}
+
+/// Scripts of up to 1 MiB and 65536 items, with strings of up to 64 KiB.
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_script_length: 1 << 20,
+            max_items: 1 << 16,
+            max_string_length: 1 << 16,
+        }
+    }
+}
+
+/// Encodes and decodes scripts in the format described in the [module documentation](index.html).
+///
+/// Decoding never panics, no matter the input, and is subject to the default [`Limits`][Limits]
+/// unless others are set with [`with_limits`][with_limits].
+///
+/// [Limits]: struct.Limits.html
+/// [with_limits]: #method.with_limits
 #[derive(Default)]
 pub struct SimpleScriptCodec {
     data: Vec<u8>,
     cursor: usize,
+    limits: Limits,
+    /// How many top-level items have been encoded so far.
+    items: usize,
+    /// How many operators are being encoded, so that their fields can be told apart from
+    /// top-level items.
+    depth: usize,
 }
 
 impl SimpleScriptCodec {
@@ -40,12 +198,199 @@ impl SimpleScriptCodec {
     }
 
     pub fn from_data(data: Vec<u8>) -> Self {
-        Self { data, cursor: 0 }
+        Self {
+            data,
+            ..Self::default()
+        }
+    }
+
+    /// Replaces the [`Limits`][Limits] enforced when decoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::codecs::dec::Decoder;
+    /// use scriptful::codecs::simple::{Limits, SimpleScriptCodec};
+    /// use scriptful::core::value::Value;
+    /// use scriptful::op_systems::simple_math::MathOperator;
+    /// use scriptful::prelude::*;
+    ///
+    /// let limits = Limits {
+    ///     max_items: 2,
+    ///     ..Limits::default()
+    /// };
+    /// let mut codec = SimpleScriptCodec::from_data(Vec::from([0x03, 0x01, 0x03, 0x02, 0x80]))
+    ///     .with_limits(limits);
+    /// let result: Result<Script<MathOperator, Value>, _> = (&mut codec).decode_script();
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    ///
+    /// [Limits]: struct.Limits.html
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+
+        self
+    }
+
+    pub fn bytes_left(&self) -> usize {
+        self.data.len() - self.cursor
+    }
+
+    /// Decodes a single item that must take up the whole of `data`, e.g. a [`Value`][Value]
+    /// received on its own rather than as part of a script.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::codecs::simple::SimpleScriptCodec;
+    /// use scriptful::codecs::DecodingError;
+    /// use scriptful::core::value::Value;
+    ///
+    /// let value: Result<Value, _> = SimpleScriptCodec::decode_exact(Vec::from([0x04, 0xff, 0x00]));
+    /// assert_eq!(value, Ok(Value::Integer(255)));
+    ///
+    /// let value: Result<Value, _> = SimpleScriptCodec::decode_exact(Vec::from([0x01, 0x01]));
+    /// assert_eq!(value, Err(DecodingError::TrailingBytes { offset: 1 }));
+    /// ```
+    ///
+    /// [Value]: ../../../core/value/enum.Value.html
+    pub fn decode_exact<T>(data: Vec<u8>) -> Result<T, DecodingError>
+    where
+        T: Decode,
+    {
+        let mut codec = SimpleScriptCodec::from_data(data);
+        let decoded = T::decode(&mut &mut codec)?;
+
+        if codec.bytes_left() > 0 {
+            return Err(DecodingError::TrailingBytes {
+                offset: codec.cursor,
+            });
+        }
+
+        Ok(decoded)
+    }
+}
+
+/// Decodes scripts in the format described in the [module documentation](index.html) straight from
+/// borrowed bytes, so that values can be decoded as [`ValueRef`s][ValueRef] pointing into them
+/// rather than as owned [`Value`s][Value], i.e. without allocating.
+///
+/// It also implements [`Decoder`][Decoder], so that anything that can be decoded by
+/// [`SimpleScriptCodec`][SimpleScriptCodec] can also be decoded from borrowed bytes, and is
+/// subject to the same [`Limits`][Limits].
+///
+/// # Examples
+///
+/// ```rust
+/// use scriptful::codecs::simple::SliceCodec;
+/// use scriptful::core::value::ValueRef;
+/// use scriptful::op_systems::simple_math::MathOperator;
+/// use scriptful::prelude::*;
+///
+/// // `"Hi" Not`.
+/// let bytes = [0x14, 0x02, 0x48, 0x69, 0x83];
+///
+/// let items: Result<Vec<_>, _> = SliceCodec::new(&bytes).items::<MathOperator>().collect();
+/// let items = items.unwrap();
+///
+/// assert_eq!(items[0], Item::Value(ValueRef::String("Hi")));
+/// assert_eq!(items[1], Item::Operator(MathOperator::Not));
+///
+/// // The string points into the encoded bytes.
+/// if let Item::Value(ValueRef::String(string)) = items[0] {
+///     assert_eq!(string.as_ptr(), bytes[2..].as_ptr());
+/// }
+/// ```
+///
+/// [ValueRef]: ../../core/value/enum.ValueRef.html
+/// [Value]: ../../core/value/enum.Value.html
+/// [Decoder]: ../dec/trait.Decoder.html
+/// [SimpleScriptCodec]: struct.SimpleScriptCodec.html
+/// [Limits]: struct.Limits.html
+#[derive(Clone, Debug)]
+pub struct SliceCodec<'a> {
+    data: &'a [u8],
+    cursor: usize,
+    limits: Limits,
+}
+
+impl<'a> SliceCodec<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            cursor: 0,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Replaces the [`Limits`][Limits] enforced when decoding.
+    ///
+    /// [Limits]: struct.Limits.html
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+
+        self
     }
 
     pub fn bytes_left(&self) -> usize {
         self.data.len() - self.cursor
     }
+
+    /// Turns this codec into an iterator over the [`ItemRef`s][ItemRef] in the rest of the bytes,
+    /// which decodes them one by one, as they are needed.
+    ///
+    /// The iterator yields an error if decoding fails or the [`Limits`][Limits] are exceeded, after
+    /// which it yields nothing else.
+    ///
+    /// [ItemRef]: ../../core/type.ItemRef.html
+    /// [Limits]: struct.Limits.html
+    pub fn items<Op>(self) -> ItemRefs<'a, Op>
+    where
+        Op: core::fmt::Debug + Decode,
+    {
+        ItemRefs {
+            codec: self,
+            items: 0,
+            failed: false,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// An iterator over the [`ItemRef`s][ItemRef] in some bytes, as returned by
+/// [`SliceCodec::items`][items].
+///
+/// [ItemRef]: ../../core/type.ItemRef.html
+/// [items]: struct.SliceCodec.html#method.items
+#[derive(Clone, Debug)]
+pub struct ItemRefs<'a, Op> {
+    codec: SliceCodec<'a>,
+    items: usize,
+    failed: bool,
+    phantom: PhantomData<fn() -> Op>,
+}
+
+impl<'a, Op> Iterator for ItemRefs<'a, Op>
+where
+    Op: core::fmt::Debug + Decode,
+{
+    type Item = Result<ItemRef<'a, Op>, DecodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.codec.bytes_left() == 0 {
+            return None;
+        }
+
+        let item = self
+            .codec
+            .check_limits(self.items)
+            .and_then(|_| self.codec.decode_item_ref());
+        self.items += 1;
+        self.failed = item.is_err();
+
+        Some(item)
+    }
 }
 
 /// Tells how many significant bytes a number takes.
@@ -57,7 +402,7 @@ fn significant_bytes_count(input: i128) -> usize {
     let mut dividend = input.saturating_abs();
     let mut counter = 0;
 
-    while dividend > 256 {
+    while dividend >= 256 {
         dividend >>= 8;
         counter += 1;
     }
@@ -65,20 +410,57 @@ fn significant_bytes_count(input: i128) -> usize {
     counter
 }
 
+/// Tells how many bytes are needed for representing a number in two's complement, so that it can be
+/// restored by sign-extending those bytes back to 16 bytes.
+fn signed_bytes_count(input: i128) -> usize {
+    let redundant_bits = if input < 0 {
+        input.leading_ones()
+    } else {
+        input.leading_zeros()
+    };
+
+    // One of the redundant bits needs to be kept as the sign bit.
+    16 - (redundant_bits as usize - 1) / 8
+}
+
+/// Restores a number from its little endian two's complement representation, as produced by
+/// [`signed_bytes_count`][signed_bytes_count].
+///
+/// [signed_bytes_count]: fn.signed_bytes_count.html
+fn sign_extend(bytes: &[u8]) -> i128 {
+    let filler = match bytes.last() {
+        Some(last) if *last >= 0x80 => 0xff,
+        _ => 0x00,
+    };
+    let mut sixteen_bytes = [filler; 16];
+    sixteen_bytes[..bytes.len()].copy_from_slice(bytes);
+
+    i128::from_le_bytes(sixteen_bytes)
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString;
     use alloc::vec::Vec;
 
-    use crate::core::value::Value;
-    use crate::encoding::codecs::simple::SimpleScriptCodec;
-    use crate::encoding::dec::Decode;
-    use crate::encoding::enc::Encode;
+    use crate::codecs::dec::{Decode, Decoder};
+    use crate::codecs::enc::{Encode, Encoder};
+    use crate::codecs::simple::{
+        sign_extend, signed_bytes_count, Limits, SimpleScriptCodec, SliceCodec,
+    };
+    use crate::codecs::DecodingError;
+    use crate::core::decimal::Decimal;
+    use crate::core::machine::StreamFailure;
+    use crate::core::value::{Value, ValueRef};
+    use crate::op_systems::simple_math::MathOperator;
+    use crate::op_systems::OperatorError;
+    use crate::prelude::*;
 
     #[test]
     fn test_boolean_false_codec() {
         let value = Value::Boolean(false);
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([0]);
 
@@ -94,7 +476,7 @@ mod tests {
     fn test_boolean_true_codec() {
         let value = Value::Boolean(true);
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([1]);
 
@@ -107,10 +489,11 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_float_codec() {
         let value = Value::Float(3.14);
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([2, 31, 133, 235, 81, 184, 30, 9, 64]);
 
@@ -126,9 +509,9 @@ mod tests {
     fn test_integer_small_codec() {
         let value = Value::Integer(255);
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
-        let expected = Vec::<u8>::from([3, 255]);
+        let expected = Vec::<u8>::from([4, 255, 0]);
 
         assert_eq!(encoded, expected);
 
@@ -138,11 +521,32 @@ mod tests {
         assert_eq!(decoded, value);
     }
 
+    #[test]
+    fn test_integer_negative_codec() {
+        for (value, expected) in [
+            (Value::Integer(-1), Vec::<u8>::from([3, 255])),
+            (Value::Integer(-128), Vec::from([3, 128])),
+            (Value::Integer(-129), Vec::from([4, 127, 255])),
+            (Value::Integer(-99999999), Vec::from([6, 1, 31, 10, 250])),
+        ] {
+            let mut codec = SimpleScriptCodec::default();
+            value.encode(&mut codec).unwrap();
+            let encoded = codec.data();
+
+            assert_eq!(encoded, expected);
+
+            codec = SimpleScriptCodec::from_data(expected);
+            let decoded = Value::decode(&mut &mut codec).unwrap();
+
+            assert_eq!(decoded, value);
+        }
+    }
+
     #[test]
     fn test_integer_big_codec() {
         let value = Value::Integer(999999999999999999);
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([10, 255, 255, 99, 167, 179, 182, 224, 13]);
 
@@ -158,7 +562,7 @@ mod tests {
     fn test_integer_max_codec() {
         let value = Value::Integer(i128::MAX);
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([
             18, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 127,
@@ -176,7 +580,7 @@ mod tests {
     fn test_integer_min_codec() {
         let value = Value::Integer(i128::MIN);
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([18, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128]);
 
@@ -192,7 +596,7 @@ mod tests {
     fn test_string_empty_codec() {
         let value = Value::String("".into());
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([19]);
 
@@ -208,7 +612,7 @@ mod tests {
     fn test_string_regular_codec() {
         let value = Value::String("Hello, World!".into());
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([
             20, 13, 72, 101, 108, 108, 111, 44, 32, 87, 111, 114, 108, 100, 33,
@@ -222,6 +626,410 @@ mod tests {
         assert_eq!(decoded, value);
     }
 
+    #[test]
+    fn test_null_codec() {
+        let value = Value::Null;
+        let mut codec = SimpleScriptCodec::default();
+        value.encode(&mut codec).unwrap();
+        let encoded = codec.data();
+        let expected = Vec::<u8>::from([28]);
+
+        assert_eq!(encoded, expected);
+
+        codec = SimpleScriptCodec::from_data(expected);
+        let decoded = Value::decode(&mut &mut codec).unwrap();
+
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_signed_bytes_count() {
+        for (input, expected) in [
+            (0, 1),
+            (1, 1),
+            (-1, 1),
+            (127, 1),
+            (128, 2),
+            (-128, 1),
+            (-129, 2),
+            (i128::MAX, 16),
+            (i128::MIN, 16),
+        ] {
+            let count = signed_bytes_count(input);
+            assert_eq!(count, expected);
+            assert_eq!(sign_extend(&input.to_le_bytes()[..count]), input);
+        }
+    }
+
+    #[test]
+    fn test_decimal_codec() {
+        let value = Value::Decimal(Decimal::new(-1234, 2).unwrap());
+        let mut codec = SimpleScriptCodec::default();
+        value.encode(&mut codec).unwrap();
+        let encoded = codec.data();
+        let expected = Vec::<u8>::from([30, 2, 46, 251]);
+
+        assert_eq!(encoded, expected);
+
+        codec = SimpleScriptCodec::from_data(expected);
+        let decoded = Value::decode(&mut &mut codec).unwrap();
+
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_decimal_extremes_codec() {
+        for value in [
+            Decimal::new(0, 0).unwrap(),
+            Decimal::new(i128::MAX, Decimal::MAX_SCALE).unwrap(),
+            Decimal::new(i128::MIN, 0).unwrap(),
+        ] {
+            let mut codec = SimpleScriptCodec::default();
+            value.encode(&mut codec).unwrap();
+            codec = SimpleScriptCodec::from_data(codec.data());
+            let decoded = Decimal::decode(&mut &mut codec).unwrap();
+
+            assert_eq!(decoded.mantissa(), value.mantissa());
+            assert_eq!(decoded.scale(), value.scale());
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_codec() {
+        let value = Value::BigInt(crate::core::value::BigInt::from(u64::MAX) + 1);
+        let mut codec = SimpleScriptCodec::default();
+        value.encode(&mut codec).unwrap();
+        let encoded = codec.data();
+        let expected = Vec::<u8>::from([45, 9, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+        assert_eq!(encoded, expected);
+
+        codec = SimpleScriptCodec::from_data(expected);
+        let decoded = Value::decode(&mut &mut codec).unwrap();
+
+        assert_eq!(decoded, value);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_huge_codec() {
+        use crate::core::value::BigInt;
+
+        for value in [
+            BigInt::from(0),
+            -BigInt::from(2).pow(4096),
+            BigInt::from(i128::MIN) * BigInt::from(i128::MAX),
+        ] {
+            let mut codec = SimpleScriptCodec::default();
+            value.encode(&mut codec).unwrap();
+            codec = SimpleScriptCodec::from_data(codec.data());
+            let decoded = BigInt::decode(&mut &mut codec).unwrap();
+
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_length_limit() {
+        let decode = |data: &[u8], limits: Limits| {
+            let mut codec = SimpleScriptCodec::from_data(Vec::from(data)).with_limits(limits);
+
+            Value::decode(&mut &mut codec)
+        };
+
+        // The length of big integers is checked before reading them, just like that of strings.
+        assert_eq!(
+            decode(
+                &[0x34, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                Limits::default()
+            ),
+            Err(DecodingError::LimitExceeded {
+                offset: 1,
+                limit: Limits::default().max_string_length
+            })
+        );
+        assert_eq!(
+            decode(
+                &[0x2d, 0x03, 0x01, 0x02, 0x03],
+                Limits {
+                    max_string_length: 2,
+                    ..Limits::UNLIMITED
+                }
+            ),
+            Err(DecodingError::LimitExceeded {
+                offset: 1,
+                limit: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_bitwise_operators_codec() {
+        use crate::codecs::{dec::Decoder, enc::Encoder};
+        use crate::core::item::Item;
+        use crate::op_systems::bitwise::BitwiseOperator::{self, *};
+
+        let script: crate::core::Script<BitwiseOperator> = Vec::from([
+            Item::Value(Value::Integer(1)),
+            Item::Value(Value::Integer(4)),
+            Item::Operator(ShiftLeft),
+            Item::Operator(Invert),
+            Item::Operator(PopCount),
+            Item::Operator(And),
+            Item::Operator(Or),
+            Item::Operator(Xor),
+            Item::Operator(ShiftRight),
+        ]);
+        let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+        let expected = Vec::<u8>::from([3, 1, 3, 4, 148, 145, 147, 144, 146, 150, 149]);
+
+        assert_eq!(encoded, expected);
+
+        let decoded = <&mut SimpleScriptCodec>::from_vec(expected).unwrap();
+
+        assert_eq!(decoded, script);
+    }
+
+    #[test]
+    fn test_bytes_codec() {
+        for (value, expected) in [
+            (Value::Bytes(Vec::new()), Vec::<u8>::from([53])),
+            (
+                Value::Bytes([0xca, 0xfe].into()),
+                Vec::from([54, 2, 0xca, 0xfe]),
+            ),
+            (
+                Value::Bytes([0; 256].into()),
+                [&[55, 0, 1][..], &[0; 256][..]].concat(),
+            ),
+        ] {
+            let mut codec = SimpleScriptCodec::default();
+            value.encode(&mut codec).unwrap();
+            let encoded = codec.data();
+
+            assert_eq!(encoded, expected);
+
+            codec = SimpleScriptCodec::from_data(expected);
+            let decoded = Value::decode(&mut &mut codec).unwrap();
+
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn test_timestamp_codec() {
+        for (value, expected) in [
+            (Value::Timestamp(0), Vec::<u8>::from([0x3e, 0x00])),
+            (Value::Timestamp(-1), Vec::from([0x3e, 0xff])),
+            (
+                Value::Timestamp(1_231_006_505),
+                Vec::from([0x41, 0x29, 0xab, 0x5f, 0x49]),
+            ),
+            (
+                Value::Timestamp(i64::MIN),
+                Vec::from([0x45, 0, 0, 0, 0, 0, 0, 0, 0x80]),
+            ),
+            (
+                Value::Timestamp(i64::MAX),
+                Vec::from([0x45, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]),
+            ),
+        ] {
+            let mut codec = SimpleScriptCodec::default();
+            value.encode(&mut codec).unwrap();
+            let encoded = codec.data();
+
+            assert_eq!(encoded, expected);
+
+            codec = SimpleScriptCodec::from_data(expected);
+            let decoded = Value::decode(&mut &mut codec).unwrap();
+
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn test_non_minimal_decoding() {
+        for (bytes, expected) in [
+            (Vec::<u8>::from([4, 255, 255]), Value::Integer(-1)),
+            (Vec::from([0x14, 0]), Value::String("".into())),
+            (
+                Vec::from([0x37, 2, 0, 0xca, 0xfe]),
+                Value::Bytes([0xca, 0xfe].into()),
+            ),
+        ] {
+            let mut codec = SimpleScriptCodec::from_data(bytes);
+            let decoded = Value::decode(&mut &mut codec).unwrap();
+
+            assert_eq!(decoded, expected);
+        }
+    }
+
+    #[test]
+    fn test_truncated_script() {
+        let decoded = <&mut SimpleScriptCodec>::from_vec::<MathOperator, Value>(Vec::from([
+            3, 1, 0x80, 4, 255,
+        ]));
+
+        assert!(decoded.is_err());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        for (bytes, expected) in [
+            (
+                Vec::<u8>::from([0x03, 0x01, 0x7f]),
+                DecodingError::InvalidDiscriminant {
+                    offset: 2,
+                    byte: 0x7f,
+                },
+            ),
+            (
+                Vec::from([0x86]),
+                DecodingError::InvalidDiscriminant {
+                    offset: 0,
+                    byte: 0x86,
+                },
+            ),
+            (
+                Vec::from([0x14, 0x02, 0xff, 0xfe]),
+                DecodingError::InvalidUtf8 { offset: 2 },
+            ),
+            (
+                Vec::from([0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+                DecodingError::LimitExceeded {
+                    offset: 1,
+                    limit: 1 << 16,
+                },
+            ),
+            (
+                Vec::from([0x02, 0x00]),
+                DecodingError::UnexpectedEof {
+                    offset: 1,
+                    needed: 8,
+                },
+            ),
+            (
+                Vec::from([0x1d, 0xff, 0x01]),
+                DecodingError::OutOfRange { offset: 0 },
+            ),
+        ] {
+            let decoded = <&mut SimpleScriptCodec>::from_vec::<MathOperator, Value>(bytes);
+
+            assert_eq!(decoded, Err(expected));
+        }
+    }
+
+    #[test]
+    fn test_wrong_discriminants() {
+        let mut codec = SimpleScriptCodec::from_data(Vec::from([0x00]));
+        assert_eq!(
+            (&mut codec).decode_string(),
+            Err(DecodingError::InvalidDiscriminant {
+                offset: 0,
+                byte: 0x00
+            })
+        );
+
+        let mut codec = SimpleScriptCodec::from_data(Vec::from([0x13]));
+        assert!((&mut codec).decode_i128().is_err());
+
+        let mut codec = SimpleScriptCodec::from_data(Vec::from([0x03]));
+        assert!((&mut codec).decode_f64().is_err());
+
+        let mut codec = SimpleScriptCodec::from_data(Vec::from([0x14]));
+        assert!((&mut codec).decode_bytes().is_err());
+
+        let mut codec = SimpleScriptCodec::from_data(Vec::from([0x00]));
+        assert!(MathOperator::decode(&mut &mut codec).is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        let script = Vec::<u8>::from([0x14, 0x03, 0x61, 0x62, 0x63, 0x80, 0x80]);
+        let decode = |limits: Limits| {
+            let mut codec = SimpleScriptCodec::from_data(script.clone()).with_limits(limits);
+
+            <&mut SimpleScriptCodec as Decoder>::decode_script::<MathOperator, Value>(
+                &mut &mut codec,
+            )
+            .map(|script| script.len())
+        };
+
+        assert_eq!(decode(Limits::default()), Ok(3));
+        assert_eq!(decode(Limits::UNLIMITED), Ok(3));
+        assert_eq!(
+            decode(Limits {
+                max_script_length: 6,
+                ..Limits::UNLIMITED
+            }),
+            Err(DecodingError::LimitExceeded {
+                offset: 0,
+                limit: 6
+            })
+        );
+        assert_eq!(
+            decode(Limits {
+                max_items: 2,
+                ..Limits::UNLIMITED
+            }),
+            Err(DecodingError::LimitExceeded {
+                offset: 6,
+                limit: 2
+            })
+        );
+        assert_eq!(
+            decode(Limits {
+                max_string_length: 2,
+                ..Limits::UNLIMITED
+            }),
+            Err(DecodingError::LimitExceeded {
+                offset: 1,
+                limit: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_exact() {
+        assert_eq!(
+            SimpleScriptCodec::decode_exact::<Value>(Vec::from([0x13])),
+            Ok(Value::String("".into()))
+        );
+        assert_eq!(
+            SimpleScriptCodec::decode_exact::<MathOperator>(Vec::from([0x80, 0x80])),
+            Err(DecodingError::TrailingBytes { offset: 1 })
+        );
+        assert_eq!(
+            SimpleScriptCodec::decode_exact::<Value>(Vec::new()),
+            Err(DecodingError::UnexpectedEof {
+                offset: 0,
+                needed: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            DecodingError::InvalidDiscriminant {
+                offset: 3,
+                byte: 0x7f
+            }
+            .to_string(),
+            "Invalid discriminant 0x7f at offset 3"
+        );
+        assert_eq!(
+            DecodingError::UnexpectedEof {
+                offset: 1,
+                needed: 8
+            }
+            .to_string(),
+            "Unexpected end of input at offset 1 when reading 8 bytes"
+        );
+    }
+
     #[test]
     fn test_string_massive_codec() {
         let value = Value::String(
@@ -242,7 +1050,7 @@ dél no se salga un punto de la verdad."#
                 .into(),
         );
         let mut codec = SimpleScriptCodec::default();
-        value.encode(&mut codec);
+        value.encode(&mut codec).unwrap();
         let encoded = codec.data();
         let expected = Vec::<u8>::from([
             21, 198, 4, 10, 69, 110, 32, 117, 110, 32, 108, 117, 103, 97, 114, 32, 100, 101, 32,
@@ -320,4 +1128,162 @@ dél no se salga un punto de la verdad."#
 
         assert_eq!(decoded, value);
     }
+
+    #[test]
+    fn test_slice_codec() {
+        let bytes = Vec::from([
+            0x14, 0x02, 0x48, 0x69, 0x36, 0x02, 0xca, 0xfe, 0x1c, 0x83, 0x14, 0x01, 0xff,
+        ]);
+        let mut items = SliceCodec::new(&bytes).items::<MathOperator>();
+
+        assert_eq!(items.next(), Some(Ok(Item::Value(ValueRef::String("Hi")))));
+        assert_eq!(
+            items.next(),
+            Some(Ok(Item::Value(ValueRef::Bytes(&[0xca, 0xfe]))))
+        );
+        assert_eq!(items.next(), Some(Ok(Item::Value(ValueRef::Null))));
+        assert_eq!(items.next(), Some(Ok(Item::Operator(MathOperator::Not))));
+        assert_eq!(
+            items.next(),
+            Some(Err(DecodingError::InvalidUtf8 { offset: 12 }))
+        );
+        assert_eq!(items.next(), None);
+
+        // The very same limits as `SimpleScriptCodec` apply.
+        let limits = Limits {
+            max_items: 3,
+            ..Limits::default()
+        };
+        let items = SliceCodec::new(&bytes[..10])
+            .with_limits(limits)
+            .items::<MathOperator>()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items.last(),
+            Some(&Err(DecodingError::LimitExceeded {
+                offset: 9,
+                limit: 3
+            }))
+        );
+
+        let limits = Limits {
+            max_script_length: 9,
+            ..Limits::default()
+        };
+        let items = SliceCodec::new(&bytes[..10])
+            .with_limits(limits)
+            .items::<MathOperator>()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [Err(DecodingError::LimitExceeded {
+                offset: 0,
+                limit: 9
+            })]
+        );
+
+        // It can also decode owned items.
+        let mut codec = SliceCodec::new(&bytes[..10]);
+        let script: Script<MathOperator> = (&mut codec).decode_script().unwrap();
+        assert_eq!(script[0], Item::Value(Value::String("Hi".into())));
+        assert_eq!(codec.bytes_left(), 0);
+    }
+
+    /// Verifies that the topmost two values are equal, without ever allocating.
+    fn verify_equal_op_sys(
+        stack: &mut Stack<ValueRef>,
+        operator: &MathOperator,
+        _if_stack: &mut ConditionStack,
+    ) -> Result<(), OperatorError> {
+        match operator {
+            MathOperator::Equal => {
+                let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+                let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+                stack.push(ValueRef::Boolean(a == b));
+
+                Ok(())
+            }
+            _ => Err(OperatorError::from_str("Unsupported operator")),
+        }
+    }
+
+    #[test]
+    fn test_slice_codec_machine() {
+        let script: Script<MathOperator> = Vec::from([
+            Item::Value(Value::String("Hello".into())),
+            Item::Value(Value::String("Hello".into())),
+            Item::Operator(MathOperator::Equal),
+            Item::Value(Value::Bytes(Vec::from([0xca, 0xfe]))),
+            Item::Value(Value::Bytes(Vec::from([0xca, 0xfe]))),
+            Item::Operator(MathOperator::Equal),
+            Item::Operator(MathOperator::Equal),
+        ]);
+        let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+
+        let mut machine = Machine::new(&verify_equal_op_sys);
+        let result = machine.run_stream(SliceCodec::new(&encoded).items());
+        assert_eq!(result, Ok(Some(&ValueRef::Boolean(true))));
+
+        let mut machine = Machine::new(&verify_equal_op_sys);
+        let result = machine.run_stream(SliceCodec::new(&encoded[..encoded.len() - 3]).items());
+        assert_eq!(
+            result,
+            Err(StreamFailure::Decoding {
+                index: 4,
+                error: DecodingError::UnexpectedEof {
+                    offset: encoded.len() - 4,
+                    needed: 2
+                }
+            })
+        );
+        assert_eq!(machine.stack_length(), 2);
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use proptest::prelude::*;
+
+    use crate::codecs::dec::Decoder;
+    use crate::codecs::enc::Encoder;
+    use crate::codecs::simple::{SimpleScriptCodec, SliceCodec};
+    use crate::core::value::Value;
+    use crate::op_systems::bitwise::BitwiseOperator;
+    use crate::op_systems::simple_math::MathOperator;
+    use crate::prelude::*;
+    use crate::test_utils::{canonical, script, value};
+
+    proptest! {
+        #[test]
+        fn test_script_round_trip(script in script(value())) {
+            let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+            let decoded: Script<MathOperator> =
+                <&mut SimpleScriptCodec>::from_vec(encoded.clone()).unwrap();
+
+            prop_assert_eq!(<&mut SimpleScriptCodec>::to_vec(&decoded).unwrap(), encoded);
+            prop_assert_eq!(canonical(decoded), canonical(script));
+        }
+
+        #[test]
+        fn test_arbitrary_bytes_never_panic(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+            let _ = <&mut SimpleScriptCodec>::from_vec::<MathOperator, Value>(bytes.clone());
+            let _ = <&mut SimpleScriptCodec>::from_vec::<BitwiseOperator, Value>(bytes);
+        }
+
+        #[test]
+        fn test_borrowed_decoding_matches_owned(script in script(value())) {
+            let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+            let decoded = SliceCodec::new(&encoded)
+                .items::<MathOperator>()
+                .map(|item| {
+                    item.map(|item| match item {
+                        Item::Operator(operator) => Item::Operator(operator),
+                        Item::Value(value) => Item::Value(Value::from(value)),
+                    })
+                })
+                .collect::<Result<Script<MathOperator>, _>>();
+
+            prop_assert_eq!(decoded.map(canonical), Ok(canonical(script)));
+        }
+    }
 }
@@ -0,0 +1,643 @@
+//! Decoding of scripts in the [simple format](../index.html) while their bytes are still arriving,
+//! e.g. over a network connection or from a flash chip, without ever holding the whole script in
+//! memory.
+//!
+//! A [`StreamDecoder`][StreamDecoder] pulls bytes from any [`Read`][Read] source as they are
+//! needed, and yields the decoded [`Item`s][Item] one by one, so that a [`Machine`][Machine] can
+//! execute them right away through [`Machine::run_stream`][run_stream].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scriptful::codecs::simple::stream::StreamDecoder;
+//! use scriptful::core::value::Value::*;
+//! use scriptful::op_systems::simple_math::*;
+//! use scriptful::prelude::*;
+//!
+//! // `1 2 Add`, as it could be read from a socket.
+//! let bytes: &[u8] = &[0x03, 0x01, 0x03, 0x02, 0x80];
+//!
+//! let mut machine = Machine::new(&simple_math_op_sys);
+//! let result = machine.run_stream(StreamDecoder::<_, MathOperator>::new(bytes));
+//!
+//! assert_eq!(result, Ok(Some(&Integer(3))));
+//! ```
+//!
+//! [StreamDecoder]: struct.StreamDecoder.html
+//! [Read]: trait.Read.html
+//! [Item]: ../../../core/item/enum.Item.html
+//! [Machine]: ../../../core/machine/struct.Machine.html
+//! [run_stream]: ../../../core/machine/struct.Machine.html#method.run_stream
+
+use core::marker::PhantomData;
+
+use crate::{
+    codecs::{
+        dec::{Decode, Decoder},
+        simple::{Limits, SimpleScriptCodec},
+        DecodingError,
+    },
+    core::value::Value,
+    prelude::*,
+};
+
+/// How many bytes are requested from the source at once, at most.
+const CHUNK_LENGTH: usize = 512;
+
+/// A source of bytes, modeled after `embedded-io`'s `Read`, so that it can be implemented on
+/// targets without `std`.
+pub trait Read {
+    /// The error raised by the source itself.
+    type Error;
+
+    /// Reads some bytes into `buffer`, and returns how many were read.
+    ///
+    /// Returning `0` for a non-empty `buffer` means that the source has reached its end. Sources
+    /// that are still waiting for more bytes shall rather block until at least one is available.
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// Byte slices are read from the start, and shrink as they are read.
+impl Read for &[u8] {
+    type Error = core::convert::Infallible;
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let length = buffer.len().min(self.len());
+        let (read, rest) = self.split_at(length);
+        buffer[..length].copy_from_slice(read);
+        *self = rest;
+
+        Ok(length)
+    }
+}
+
+impl<R> Read for &mut R
+where
+    R: Read + ?Sized,
+{
+    type Error = R::Error;
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        (**self).read(buffer)
+    }
+}
+
+/// The error yielded by a [`StreamDecoder`][StreamDecoder], which may come either from the source
+/// or from the decoding of its bytes.
+///
+/// [StreamDecoder]: struct.StreamDecoder.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamError<E> {
+    /// The source failed to provide more bytes.
+    Read(E),
+    /// The bytes provided by the source could not be decoded. Offsets are counted from the start
+    /// of the stream.
+    Decoding(DecodingError),
+}
+
+impl<E> core::fmt::Display for StreamError<E>
+where
+    E: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            StreamError::Read(error) => write!(f, "Failed to read the stream: {}", error),
+            StreamError::Decoding(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Decodes the [`Item`s][Item] of a script lazily, as an iterator, while reading its bytes from a
+/// [`Read`][Read] source.
+///
+/// Only the bytes of the item being decoded are buffered, plus at most one chunk of the ones after
+/// it, and items are handed over as soon as they are decoded. As the encoding of an item can only
+/// be as long as allowed by the [`Limits`][Limits], so is the memory used by the decoder. The
+/// `max_script_length` and `max_items` limits apply to the stream as a whole.
+///
+/// The iterator yields the very same items as [`Decoder::from_vec`][from_vec] would for the whole
+/// script, followed by an error if decoding fails, after which it yields nothing else. A stream
+/// ending in the middle of an item fails with
+/// [`DecodingError::UnexpectedEof`][UnexpectedEof].
+///
+/// # Examples
+///
+/// ```rust
+/// use scriptful::codecs::simple::stream::StreamDecoder;
+/// use scriptful::codecs::simple::Limits;
+/// use scriptful::core::value::Value::*;
+/// use scriptful::op_systems::simple_math::MathOperator;
+/// use scriptful::prelude::*;
+///
+/// let bytes: &[u8] = &[0x03, 0x01, 0x80, 0x14, 0x05, 0x68];
+/// let mut decoder = StreamDecoder::<_, MathOperator>::new(bytes);
+///
+/// assert_eq!(decoder.next(), Some(Ok(Item::Value(Integer(1)))));
+/// assert_eq!(decoder.next(), Some(Ok(Item::Operator(MathOperator::Add))));
+/// assert_eq!(decoder.position(), 3);
+/// assert!(decoder.next().unwrap().is_err());
+/// assert_eq!(decoder.next(), None);
+///
+/// // Limits are enforced as the stream goes.
+/// let bytes: &[u8] = &[0x03, 0x01, 0x03, 0x02, 0x80];
+/// let limits = Limits {
+///     max_items: 2,
+///     ..Limits::default()
+/// };
+/// let items: Vec<_> = StreamDecoder::<_, MathOperator>::new(bytes)
+///     .with_limits(limits)
+///     .collect();
+///
+/// assert_eq!(items.len(), 3);
+/// assert!(items[2].is_err());
+/// ```
+///
+/// [Item]: ../../../core/item/enum.Item.html
+/// [Read]: trait.Read.html
+/// [Limits]: ../struct.Limits.html
+/// [from_vec]: ../../dec/trait.Decoder.html#tymethod.from_vec
+/// [UnexpectedEof]: ../../enum.DecodingError.html#variant.UnexpectedEof
+pub struct StreamDecoder<R, Op, Val = Value> {
+    reader: R,
+    /// Holds the bytes read but not yet decoded, starting at the next item.
+    codec: SimpleScriptCodec,
+    /// The offset in the stream of the first byte held by `codec`.
+    offset: usize,
+    items: usize,
+    finished: bool,
+    failed: bool,
+    phantom: PhantomData<fn() -> (Op, Val)>,
+}
+
+impl<R, Op, Val> StreamDecoder<R, Op, Val>
+where
+    R: Read,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            codec: SimpleScriptCodec::default(),
+            offset: 0,
+            items: 0,
+            finished: false,
+            failed: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Replaces the [`Limits`][Limits] enforced when decoding.
+    ///
+    /// [Limits]: ../struct.Limits.html
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.codec.limits = limits;
+
+        self
+    }
+
+    /// The offset in the stream of the next item to be decoded.
+    pub fn position(&self) -> usize {
+        self.offset
+    }
+
+    /// Gives the source back, e.g. to read whatever comes after the script. Note that any bytes
+    /// already read from it but not yet decoded are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next chunk of bytes from the source, without ever going past the maximum length of
+    /// a script by more than one byte, so that exceeding it can be told apart from reaching it.
+    fn fill(&mut self) -> Result<(), R::Error> {
+        let data = &mut self.codec.data;
+        let read = self.offset + data.len();
+        let start = data.len();
+        let length = CHUNK_LENGTH
+            .min(self.codec.limits.max_script_length.saturating_sub(read))
+            .max(1);
+
+        data.resize(start + length, 0);
+        let length = match self.reader.read(&mut data[start..]) {
+            Ok(read) => read.min(length),
+            Err(error) => {
+                data.truncate(start);
+                return Err(error);
+            }
+        };
+        data.truncate(start + length);
+        self.finished = length == 0;
+
+        Ok(())
+    }
+
+    /// Tries to decode an item from the bytes read so far, reading more of them until it succeeds,
+    /// it fails for any reason other than running out of bytes, or the source reaches its end.
+    fn decode_next(&mut self) -> Result<Option<Item<Op, Val>>, StreamError<R::Error>>
+    where
+        Op: core::fmt::Debug + Decode,
+        Val: core::fmt::Debug + Decode,
+    {
+        let limits = self.codec.limits;
+
+        loop {
+            if self.offset + self.codec.data.len() > limits.max_script_length {
+                return Err(StreamError::Decoding(DecodingError::LimitExceeded {
+                    offset: self.offset,
+                    limit: limits.max_script_length,
+                }));
+            }
+
+            if !self.codec.data.is_empty() {
+                if self.items == limits.max_items {
+                    return Err(StreamError::Decoding(DecodingError::LimitExceeded {
+                        offset: self.offset,
+                        limit: limits.max_items,
+                    }));
+                }
+
+                self.codec.cursor = 0;
+                match (&mut self.codec).decode_item() {
+                    Ok(item) => {
+                        let length = self.codec.cursor;
+                        self.codec.data.drain(..length);
+                        self.offset += length;
+                        self.items += 1;
+
+                        return Ok(Some(item));
+                    }
+                    Err(DecodingError::UnexpectedEof { .. }) if !self.finished => {}
+                    Err(error) => {
+                        return Err(StreamError::Decoding(offset_by(error, self.offset)));
+                    }
+                }
+            } else if self.finished {
+                return Ok(None);
+            }
+
+            self.fill().map_err(StreamError::Read)?;
+        }
+    }
+}
+
+impl<R, Op, Val> Iterator for StreamDecoder<R, Op, Val>
+where
+    R: Read,
+    Op: core::fmt::Debug + Decode,
+    Val: core::fmt::Debug + Decode,
+{
+    type Item = Result<Item<Op, Val>, StreamError<R::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let next = self.decode_next();
+        self.failed = next.is_err();
+
+        next.transpose()
+    }
+}
+
+/// Makes the offset of a decoding error relative to the start of the stream rather than to the
+/// start of the buffered bytes.
+fn offset_by(error: DecodingError, by: usize) -> DecodingError {
+    match error {
+        DecodingError::UnexpectedEof { offset, needed } => DecodingError::UnexpectedEof {
+            offset: offset + by,
+            needed,
+        },
+        DecodingError::InvalidDiscriminant { offset, byte } => DecodingError::InvalidDiscriminant {
+            offset: offset + by,
+            byte,
+        },
+        DecodingError::UnexpectedType {
+            offset,
+            expected,
+            found,
+        } => DecodingError::UnexpectedType {
+            offset: offset + by,
+            expected,
+            found,
+        },
+        DecodingError::InvalidToken { offset } => DecodingError::InvalidToken {
+            offset: offset + by,
+        },
+        DecodingError::UnknownOperator { offset } => DecodingError::UnknownOperator {
+            offset: offset + by,
+        },
+        DecodingError::InvalidUtf8 { offset } => DecodingError::InvalidUtf8 {
+            offset: offset + by,
+        },
+        DecodingError::LengthOverflow { offset } => DecodingError::LengthOverflow {
+            offset: offset + by,
+        },
+        DecodingError::LimitExceeded { offset, limit } => DecodingError::LimitExceeded {
+            offset: offset + by,
+            limit,
+        },
+        DecodingError::OutOfRange { offset } => DecodingError::OutOfRange {
+            offset: offset + by,
+        },
+        DecodingError::TrailingBytes { offset } => DecodingError::TrailingBytes {
+            offset: offset + by,
+        },
+        DecodingError::Other(message) => DecodingError::Other(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::codecs::enc::Encoder;
+    use crate::codecs::simple::stream::{Read, StreamDecoder, StreamError, CHUNK_LENGTH};
+    use crate::codecs::simple::{Limits, SimpleScriptCodec};
+    use crate::codecs::DecodingError;
+    use crate::core::machine::{ScriptFailure, StreamFailure};
+    use crate::core::value::Value;
+    use crate::op_systems::simple_math::{simple_math_op_sys, MathOperator};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::*;
+
+    /// Hands over a single byte at a time, and then fails if told to.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        fail: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        type Error = &'static str;
+
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+            if self.bytes.is_empty() && self.fail {
+                return Err("Connection lost");
+            }
+
+            let length = self.bytes.len().min(buffer.len()).min(1);
+            buffer[..length].copy_from_slice(&self.bytes[..length]);
+            self.bytes = &self.bytes[length..];
+
+            Ok(length)
+        }
+    }
+
+    /// Never ends, e.g. a malicious peer.
+    struct Endless;
+
+    impl Read for Endless {
+        type Error = ();
+
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+            // `true`, over and over again.
+            buffer.fill(0x01);
+
+            Ok(buffer.len())
+        }
+    }
+
+    fn script() -> Script<MathOperator> {
+        Vec::from([
+            Item::Value(Value::Integer(-1)),
+            Item::Value(Value::String("a".repeat(CHUNK_LENGTH * 3))),
+            Item::Operator(MathOperator::Not),
+            Item::Value(Value::Bytes(Vec::from([0xca, 0xfe]))),
+            Item::Value(Value::Float(1.5)),
+            Item::Operator(MathOperator::Add),
+        ])
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        let script = script();
+        let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+
+        let decoded: Result<Script<MathOperator>, _> =
+            StreamDecoder::new(encoded.as_slice()).collect();
+        assert_eq!(decoded, Ok(script.clone()));
+
+        let trickle = Trickle {
+            bytes: &encoded,
+            fail: false,
+        };
+        let decoded: Result<Script<MathOperator>, _> = StreamDecoder::new(trickle).collect();
+        assert_eq!(decoded, Ok(script));
+
+        let mut decoder = StreamDecoder::<_, MathOperator>::new(&[][..]);
+        assert_eq!(decoder.next(), None);
+        assert_eq!(decoder.position(), 0);
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let encoded = <&mut SimpleScriptCodec>::to_vec(&script()).unwrap();
+        let length = encoded.len();
+
+        // Offsets are counted from the start of the stream.
+        let mut truncated = StreamDecoder::<_, MathOperator>::new(&encoded[..length - 2]);
+        assert_eq!(
+            truncated.by_ref().last(),
+            Some(Err(StreamError::Decoding(DecodingError::UnexpectedEof {
+                offset: length - 9,
+                needed: 8
+            })))
+        );
+        assert_eq!(truncated.next(), None);
+
+        let mut corrupted = encoded.clone();
+        corrupted[length - 1] = 0x7f;
+        let decoded: Result<Script<MathOperator>, _> =
+            StreamDecoder::new(corrupted.as_slice()).collect();
+        assert_eq!(
+            decoded,
+            Err(StreamError::Decoding(DecodingError::InvalidDiscriminant {
+                offset: length - 1,
+                byte: 0x7f
+            }))
+        );
+
+        // Errors from the source itself are handed over as they are, and nothing follows them.
+        let mut decoder = StreamDecoder::<_, MathOperator>::new(Trickle {
+            bytes: &encoded[..3],
+            fail: true,
+        });
+        assert_eq!(decoder.next(), Some(Ok(Item::Value(Value::Integer(-1)))));
+        assert_eq!(
+            decoder.next(),
+            Some(Err(StreamError::Read("Connection lost")))
+        );
+        assert_eq!(decoder.next(), None);
+    }
+
+    #[test]
+    fn test_stream_limits() {
+        let encoded = <&mut SimpleScriptCodec>::to_vec(&script()).unwrap();
+        let decode = |limits| {
+            StreamDecoder::<_, MathOperator>::new(encoded.as_slice())
+                .with_limits(limits)
+                .collect::<Result<Script<MathOperator>, _>>()
+        };
+
+        assert!(decode(Limits {
+            max_script_length: encoded.len(),
+            max_items: 6,
+            max_string_length: CHUNK_LENGTH * 3,
+        })
+        .is_ok());
+        assert_eq!(
+            decode(Limits {
+                max_script_length: encoded.len() - 1,
+                ..Limits::UNLIMITED
+            }),
+            Err(StreamError::Decoding(DecodingError::LimitExceeded {
+                offset: encoded.len() - 1,
+                limit: encoded.len() - 1
+            }))
+        );
+        assert_eq!(
+            decode(Limits {
+                max_items: 5,
+                ..Limits::UNLIMITED
+            }),
+            Err(StreamError::Decoding(DecodingError::LimitExceeded {
+                offset: encoded.len() - 1,
+                limit: 5
+            }))
+        );
+        // The string is rejected before its contents are read.
+        assert_eq!(
+            decode(Limits {
+                max_string_length: CHUNK_LENGTH,
+                ..Limits::UNLIMITED
+            }),
+            Err(StreamError::Decoding(DecodingError::LimitExceeded {
+                offset: 3,
+                limit: CHUNK_LENGTH
+            }))
+        );
+    }
+
+    #[test]
+    fn test_stream_memory_is_bounded() {
+        let mut decoder =
+            StreamDecoder::<_, MathOperator>::new(Endless).with_limits(Limits::UNLIMITED);
+
+        for _ in 0..CHUNK_LENGTH * 10 {
+            assert_eq!(decoder.next(), Some(Ok(Item::Value(Value::Boolean(true)))));
+            assert!(decoder.codec.data.len() < CHUNK_LENGTH);
+        }
+        assert_eq!(decoder.position(), CHUNK_LENGTH * 10);
+
+        // The default limits stop it eventually.
+        let items = StreamDecoder::<_, MathOperator>::new(Endless).count();
+        assert_eq!(items, Limits::default().max_items + 1);
+    }
+
+    #[test]
+    fn test_run_stream() {
+        let script: Script<MathOperator> = Vec::from([
+            Item::Value(Value::Integer(1)),
+            Item::Value(Value::Integer(2)),
+            Item::Operator(MathOperator::Add),
+            Item::Operator(MathOperator::Add),
+        ]);
+        let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+
+        let mut machine = Machine::new(&simple_math_op_sys);
+        let result = machine.run_stream(StreamDecoder::new(Trickle {
+            bytes: &encoded[..5],
+            fail: false,
+        }));
+        assert_eq!(result, Ok(Some(&Value::Integer(3))));
+
+        let mut machine = Machine::new(&simple_math_op_sys);
+        let result = machine.run_stream(StreamDecoder::new(encoded.as_slice()));
+        assert_eq!(
+            result,
+            Err(StreamFailure::Script(ScriptFailure {
+                index: 3,
+                error: OperatorError::StackUnderflow
+            }))
+        );
+        assert_eq!(
+            String::from("Script failed at item 3: Stack underflow"),
+            alloc::format!("{}", result.unwrap_err())
+        );
+
+        let mut machine = Machine::new(&simple_math_op_sys);
+        let result = machine.run_stream(StreamDecoder::new(&encoded[..1]));
+        assert_eq!(
+            result,
+            Err(StreamFailure::Decoding {
+                index: 0,
+                error: StreamError::Decoding(DecodingError::UnexpectedEof {
+                    offset: 1,
+                    needed: 1
+                })
+            })
+        );
+        assert_eq!(
+            String::from(
+                "Failed to obtain item 0: Unexpected end of input at offset 1 when reading 1 bytes"
+            ),
+            alloc::format!("{}", result.unwrap_err())
+        );
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use alloc::vec::Vec;
+
+    use proptest::prelude::*;
+
+    use crate::codecs::dec::Decoder;
+    use crate::codecs::simple::stream::{Read, StreamDecoder, StreamError};
+    use crate::codecs::simple::SimpleScriptCodec;
+    use crate::core::value::Value;
+    use crate::op_systems::simple_math::MathOperator;
+    use crate::prelude::*;
+    use crate::test_utils::canonical;
+
+    /// Hands over bytes in chunks of the given lengths, one after the other, in a loop.
+    struct Chunked<'a> {
+        bytes: &'a [u8],
+        lengths: Vec<usize>,
+        reads: usize,
+    }
+
+    impl Read for Chunked<'_> {
+        type Error = ();
+
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+            let length = self.lengths[self.reads % self.lengths.len()].min(buffer.len());
+            self.reads += 1;
+
+            (&mut self.bytes)
+                .read(&mut buffer[..length])
+                .map_err(|_| ())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_stream_matches_from_vec(
+            bytes in prop::collection::vec(any::<u8>(), 0..256),
+            lengths in prop::collection::vec(1usize..64, 1..8),
+        ) {
+            let expected =
+                <&mut SimpleScriptCodec>::from_vec::<MathOperator, Value>(bytes.clone())
+                    .map(canonical);
+            let reader = Chunked { bytes: &bytes, lengths, reads: 0 };
+            let decoded = StreamDecoder::new(reader)
+                .collect::<Result<Script<MathOperator>, _>>()
+                .map(canonical)
+                .map_err(|error| match error {
+                    StreamError::Decoding(error) => error,
+                    StreamError::Read(()) => unreachable!(),
+                });
+
+            prop_assert_eq!(decoded, expected);
+        }
+    }
+}
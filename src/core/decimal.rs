@@ -0,0 +1,474 @@
+//! A fixed-point decimal number type for exact arithmetic.
+//!
+//! This module provides the [`Decimal`][Decimal] struct, which can either be used through the
+//! [`Decimal`][Value::Decimal] variant of [`Value`][Value], or on its own as a standalone type
+//! system, i.e. `Stack<Decimal>`.
+//!
+//! [Decimal]: struct.Decimal.html
+//! [Value::Decimal]: ../value/enum.Value.html#variant.Decimal
+//! [Value]: ../value/enum.Value.html
+
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+/// A decimal number represented as an integer `mantissa` and a `scale`, so that its actual value is
+/// `mantissa × 10^-scale`.
+///
+/// Addition, subtraction and multiplication are exact, and panic upon overflow, just like `i128`
+/// does. The `checked_*` variants return `None` instead. Division is never exact in general, so it
+/// is only available through [`checked_div`][checked_div], which takes the scale of the result and
+/// a [`Rounding`][Rounding] mode.
+///
+/// Equality, ordering and hashing are defined on the numeric value, so that `1.0` and `1.00` are
+/// considered to be equal.
+///
+/// # Examples
+///
+/// ```rust
+/// use scriptful::core::decimal::{Decimal, Rounding};
+///
+/// let price = Decimal::new(1999, 2).unwrap();
+/// let quantity = Decimal::new(3, 0).unwrap();
+///
+/// assert_eq!(price * quantity, Decimal::new(5997, 2).unwrap());
+///
+/// let third = price.checked_div(quantity, 2, Rounding::HalfEven).unwrap();
+/// assert_eq!(third, Decimal::new(666, 2).unwrap());
+/// ```
+///
+/// [checked_div]: #method.checked_div
+/// [Rounding]: enum.Rounding.html
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "use_serde", serde(try_from = "DecimalParts"))]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u8,
+}
+
+/// The serialized form of a [`Decimal`][Decimal], which is deserialized through
+/// [`Decimal::new`][new] so that scales greater than [`MAX_SCALE`][MAX_SCALE] are rejected.
+///
+/// [Decimal]: struct.Decimal.html
+/// [new]: struct.Decimal.html#method.new
+/// [MAX_SCALE]: struct.Decimal.html#associatedconstant.MAX_SCALE
+#[cfg(feature = "use_serde")]
+#[derive(serde::Deserialize)]
+struct DecimalParts {
+    mantissa: i128,
+    scale: u8,
+}
+
+#[cfg(feature = "use_serde")]
+impl TryFrom<DecimalParts> for Decimal {
+    type Error = &'static str;
+
+    fn try_from(parts: DecimalParts) -> Result<Self, Self::Error> {
+        Self::new(parts.mantissa, parts.scale).ok_or("Decimal scale is too big")
+    }
+}
+
+/// The different ways in which a [`Decimal`][Decimal] can be rounded when some of its digits need
+/// to be discarded.
+///
+/// [Decimal]: struct.Decimal.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Rounding {
+    /// Round towards zero, i.e. truncate.
+    Down,
+    /// Round away from zero.
+    Up,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceiling,
+    /// Round to the nearest neighbor, or away from zero if both neighbors are equidistant.
+    HalfUp,
+    /// Round to the nearest neighbor, or towards zero if both neighbors are equidistant.
+    HalfDown,
+    /// Round to the nearest neighbor, or to the even one if both neighbors are equidistant. This is
+    /// also known as "banker's rounding".
+    HalfEven,
+}
+
+impl Decimal {
+    /// The maximum supported scale, i.e. the maximum amount of fractional digits.
+    pub const MAX_SCALE: u8 = 38;
+
+    /// Creates a new `Decimal` from its mantissa and scale, or `None` if the scale is greater than
+    /// [`MAX_SCALE`][MAX_SCALE].
+    ///
+    /// [MAX_SCALE]: #associatedconstant.MAX_SCALE
+    pub fn new(mantissa: i128, scale: u8) -> Option<Self> {
+        if scale > Self::MAX_SCALE {
+            None
+        } else {
+            Some(Self { mantissa, scale })
+        }
+    }
+
+    /// The unscaled integer value, i.e. the `mantissa` in `mantissa × 10^-scale`.
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// The amount of fractional digits.
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Exact addition, or `None` upon overflow.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let (a, b, scale) = Self::align(self, rhs)?;
+
+        a.checked_add(b).map(|mantissa| Self { mantissa, scale })
+    }
+
+    /// Exact subtraction, or `None` upon overflow.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        let (a, b, scale) = Self::align(self, rhs)?;
+
+        a.checked_sub(b).map(|mantissa| Self { mantissa, scale })
+    }
+
+    /// Exact negation, or `None` upon overflow.
+    pub fn checked_neg(self) -> Option<Self> {
+        self.mantissa.checked_neg().map(|mantissa| Self {
+            mantissa,
+            scale: self.scale,
+        })
+    }
+
+    /// Exact multiplication, or `None` upon overflow or if the resulting scale would be greater
+    /// than [`MAX_SCALE`][MAX_SCALE].
+    ///
+    /// [MAX_SCALE]: #associatedconstant.MAX_SCALE
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let mantissa = self.mantissa.checked_mul(rhs.mantissa)?;
+
+        Self::new(mantissa, self.scale + rhs.scale)
+    }
+
+    /// Division into a result with the given `scale`, rounding the discarded digits as told by
+    /// `rounding`.
+    ///
+    /// Returns `None` upon division by zero, overflow, or if `scale` is greater than
+    /// [`MAX_SCALE`][MAX_SCALE].
+    ///
+    /// [MAX_SCALE]: #associatedconstant.MAX_SCALE
+    pub fn checked_div(self, rhs: Self, scale: u8, rounding: Rounding) -> Option<Self> {
+        if scale > Self::MAX_SCALE || rhs.mantissa == 0 {
+            return None;
+        }
+
+        // The result mantissa is `a.mantissa × 10^(scale + b.scale - a.scale) / b.mantissa`.
+        let exponent = i32::from(scale) + i32::from(rhs.scale) - i32::from(self.scale);
+        let (numerator, denominator) = if exponent >= 0 {
+            (
+                self.mantissa.checked_mul(pow10(exponent as u32)?)?,
+                rhs.mantissa,
+            )
+        } else {
+            (
+                self.mantissa,
+                rhs.mantissa.checked_mul(pow10(exponent.unsigned_abs())?)?,
+            )
+        };
+
+        div_rounded(numerator, denominator, rounding).map(|mantissa| Self { mantissa, scale })
+    }
+
+    /// Changes the scale of a `Decimal`, rounding the discarded digits as told by `rounding` if the
+    /// new scale is smaller than the current one.
+    ///
+    /// Returns `None` upon overflow, or if `scale` is greater than [`MAX_SCALE`][MAX_SCALE].
+    ///
+    /// [MAX_SCALE]: #associatedconstant.MAX_SCALE
+    pub fn rescale(self, scale: u8, rounding: Rounding) -> Option<Self> {
+        if scale > Self::MAX_SCALE {
+            return None;
+        }
+
+        let mantissa = if scale >= self.scale {
+            self.mantissa
+                .checked_mul(pow10(u32::from(scale - self.scale))?)?
+        } else {
+            div_rounded(
+                self.mantissa,
+                pow10(u32::from(self.scale - scale))?,
+                rounding,
+            )?
+        };
+
+        Some(Self { mantissa, scale })
+    }
+
+    /// Converts this `Decimal` into the closest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10u128.pow(u32::from(self.scale)) as f64
+    }
+
+    /// Brings two decimals to the same scale, so that their mantissas can be operated directly.
+    fn align(a: Self, b: Self) -> Option<(i128, i128, u8)> {
+        match a.scale.cmp(&b.scale) {
+            Ordering::Equal => Some((a.mantissa, b.mantissa, a.scale)),
+            Ordering::Less => Some((
+                a.mantissa
+                    .checked_mul(pow10(u32::from(b.scale - a.scale))?)?,
+                b.mantissa,
+                b.scale,
+            )),
+            Ordering::Greater => Some((
+                a.mantissa,
+                b.mantissa
+                    .checked_mul(pow10(u32::from(a.scale - b.scale))?)?,
+                a.scale,
+            )),
+        }
+    }
+
+    /// Removes any trailing zeros from the fractional part, so that numerically equal decimals
+    /// have the same representation.
+    fn normalize(self) -> Self {
+        let mut normalized = self;
+        while normalized.scale > 0 && normalized.mantissa % 10 == 0 {
+            normalized.mantissa /= 10;
+            normalized.scale -= 1;
+        }
+
+        normalized
+    }
+}
+
+/// Computes `10^exponent`, or `None` if it does not fit into an `i128`.
+fn pow10(exponent: u32) -> Option<i128> {
+    10i128.checked_pow(exponent)
+}
+
+/// Integer division that rounds the remainder as told by `rounding`.
+fn div_rounded(numerator: i128, denominator: i128, rounding: Rounding) -> Option<i128> {
+    let quotient = numerator.checked_div(denominator)?;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return Some(quotient);
+    }
+
+    let negative = (numerator < 0) != (denominator < 0);
+    let remainder = remainder.unsigned_abs();
+    let half = denominator.unsigned_abs() - remainder;
+    let away_from_zero = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => true,
+        Rounding::Floor => negative,
+        Rounding::Ceiling => !negative,
+        Rounding::HalfUp => remainder >= half,
+        Rounding::HalfDown => remainder > half,
+        Rounding::HalfEven => remainder > half || (remainder == half && quotient % 2 != 0),
+    };
+
+    if !away_from_zero {
+        Some(quotient)
+    } else if negative {
+        quotient.checked_sub(1)
+    } else {
+        quotient.checked_add(1)
+    }
+}
+
+impl From<i128> for Decimal {
+    fn from(input: i128) -> Self {
+        Self {
+            mantissa: input,
+            scale: 0,
+        }
+    }
+}
+
+impl core::ops::Add for Decimal {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl core::ops::Sub for Decimal {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl core::ops::Mul for Decimal {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("attempt to multiply with overflow")
+    }
+}
+
+impl core::ops::Neg for Decimal {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("attempt to negate with overflow")
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match Self::align(*self, *other) {
+            Some((a, b, _)) => a.cmp(&b),
+            // Aligning can only overflow when scaling up the number with the smallest scale, which
+            // means that its absolute value is greater than that of the other.
+            None if self.scale < other.scale => self.mantissa.cmp(&0),
+            None => 0.cmp(&other.mantissa),
+        }
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalize();
+        normalized.mantissa.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+impl core::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let magnitude = self.mantissa.unsigned_abs();
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, magnitude);
+        }
+
+        let divisor = 10u128.pow(u32::from(self.scale));
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            magnitude / divisor,
+            magnitude % divisor,
+            width = usize::from(self.scale)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::core::decimal::{Decimal, Rounding::*};
+
+    fn dec(mantissa: i128, scale: u8) -> Decimal {
+        Decimal::new(mantissa, scale).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(Decimal::new(1, Decimal::MAX_SCALE).is_some());
+        assert!(Decimal::new(1, Decimal::MAX_SCALE + 1).is_none());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(dec(11, 1) + dec(22, 1), dec(33, 1));
+        assert_eq!(dec(1, 1) + dec(2, 1), dec(3, 1));
+        assert_eq!(dec(1, 1) + dec(2, 2), dec(12, 2));
+        assert_eq!(dec(11, 1) - dec(22, 1), dec(-11, 1));
+        assert_eq!(dec(11, 1) * dec(22, 1), dec(242, 2));
+        assert_eq!(-dec(11, 1), dec(-11, 1));
+        assert_eq!(dec(i128::MAX, 0).checked_add(dec(1, 0)), None);
+        assert_eq!(dec(i128::MAX, 0).checked_add(dec(1, 1)), None);
+        assert_eq!(dec(1, 20).checked_mul(dec(1, 20)), None);
+    }
+
+    #[test]
+    fn test_division() {
+        let one = dec(1, 0);
+        let three = dec(3, 0);
+        let two_thirds = dec(2, 0).checked_div(three, 4, Down).unwrap();
+
+        assert_eq!(one.checked_div(three, 4, Down), Some(dec(3333, 4)));
+        assert_eq!(one.checked_div(three, 4, Up), Some(dec(3334, 4)));
+        assert_eq!(two_thirds, dec(6666, 4));
+        assert_eq!(dec(2, 0).checked_div(three, 4, HalfUp), Some(dec(6667, 4)));
+        assert_eq!(dec(-2, 0).checked_div(three, 2, Floor), Some(dec(-67, 2)));
+        assert_eq!(dec(-2, 0).checked_div(three, 2, Ceiling), Some(dec(-66, 2)));
+        assert_eq!(dec(1, 2).checked_div(dec(4, 0), 2, HalfUp), Some(dec(0, 2)));
+        assert_eq!(dec(15, 1).checked_div(one, 0, HalfEven), Some(dec(2, 0)));
+        assert_eq!(dec(25, 1).checked_div(one, 0, HalfEven), Some(dec(2, 0)));
+        assert_eq!(dec(25, 1).checked_div(one, 0, HalfUp), Some(dec(3, 0)));
+        assert_eq!(dec(25, 1).checked_div(one, 0, HalfDown), Some(dec(2, 0)));
+        assert_eq!(dec(-25, 1).checked_div(one, 0, HalfUp), Some(dec(-3, 0)));
+        assert_eq!(
+            dec(12345, 2).checked_div(dec(5, 1), 1, Down),
+            Some(dec(2469, 1))
+        );
+        assert_eq!(one.checked_div(dec(0, 3), 2, Down), None);
+    }
+
+    #[test]
+    fn test_rescale() {
+        assert_eq!(dec(12345, 3).rescale(1, HalfEven), Some(dec(123, 1)));
+        assert_eq!(dec(12355, 3).rescale(2, HalfEven), Some(dec(1236, 2)));
+        assert_eq!(
+            dec(12345, 3).rescale(5, HalfEven).unwrap().mantissa(),
+            1234500
+        );
+        assert_eq!(
+            dec(1, 0).rescale(Decimal::MAX_SCALE, Down).unwrap().scale(),
+            38
+        );
+        assert_eq!(dec(2, 0).rescale(Decimal::MAX_SCALE, Down), None);
+    }
+
+    #[test]
+    fn test_comparison() {
+        assert_eq!(dec(1, 0), dec(100, 2));
+        assert_ne!(dec(1, 0), dec(101, 2));
+        assert!(dec(1, 1) < dec(2, 1));
+        assert!(dec(-1, 0) < dec(1, 38));
+        assert!(dec(i128::MAX, 0) > dec(1, 38));
+        assert!(dec(i128::MIN, 0) < dec(-1, 38));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(dec(12345, 2).to_string(), "123.45");
+        assert_eq!(dec(-5, 3).to_string(), "-0.005");
+        assert_eq!(dec(7, 0).to_string(), "7");
+        assert_eq!(
+            dec(i128::MIN, 0).to_string(),
+            "-170141183460469231731687303715884105728"
+        );
+    }
+
+    #[cfg(feature = "use_serde")]
+    #[test]
+    fn test_deserialize() {
+        let decimal: Decimal = serde_json::from_str(r#"{"mantissa":12345,"scale":2}"#).unwrap();
+        assert_eq!(decimal, dec(12345, 2));
+
+        assert!(serde_json::from_str::<Decimal>(r#"{"mantissa":1,"scale":50}"#).is_err());
+    }
+}
@@ -25,7 +25,7 @@ where
     Operator(Op),
     /// A value, either a variant of an user-defined [`enum`][enum] representing a type system, or
     /// an instance of any of the variants of [`Value`][Value], i.e. [`Boolean`][Boolean],
-    /// [`Float`][Float], [`Integer`][Integer] or [`String`][String].
+    /// [`Float`][Float], [`Integer`][Integer], [`String`][String] or [`Null`][Null].
     ///
     /// [enum]: https://doc.rust-lang.org/std/keyword.enum.html
     /// [Value]: ../value/enum.Value.html
@@ -33,6 +33,7 @@ where
     /// [Float]: ../value/enum.Value.html#variant.Float
     /// [Integer]: ../value/enum.Value.html#variant.Integer
     /// [String]: ../value/enum.Value.html#variant.String
+    /// [Null]: ../value/enum.Value.html#variant.Null
     Value(Val),
 }
 
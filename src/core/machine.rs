@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::core::ScriptRef;
 use crate::prelude::*;
 use core::marker::PhantomData;
@@ -63,10 +65,10 @@ where
     /// [`run_script`][run_script] method, which instead of single [`Item`s][Item] takes a
     /// [`Script`][Script], i.e. an array of [`Item`s][Item].
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Operating on a `Machine` that has an empty [`Stack`][Stack] can cause a panic if the
-    /// [`Item`][Item] is an operator that tries to pop from it.
+    /// Returns the error of the operator system if it fails on the [`Item`][Item], e.g. because it
+    /// is an operator that tries to pop from an empty [`Stack`][Stack].
     ///
     /// # Examples
     ///
@@ -82,14 +84,14 @@ where
     /// // Operating a `Value::Integer(1)` should simply push it into the stack.
     /// let result = machine.operate(&Item::Value(Integer(1)));
     /// // Make sure the value gets pushed.
-    /// assert_eq!(result, Some(&Integer(1)));
+    /// assert_eq!(result, Ok(Some(&Integer(1))));
     /// // The length of the stack should be 1.
     /// assert_eq!(machine.stack_length(), 1);
     ///
     /// // Operating a `Value::Integer(2)` should simply push it into the stack.
     /// let result = machine.operate(&Item::Value(Integer(2)));
     /// // Make sure the value gets pushed.
-    /// assert_eq!(result, Some(&Integer(2)));
+    /// assert_eq!(result, Ok(Some(&Integer(2))));
     /// // The length of the stack should be 2.
     /// assert_eq!(machine.stack_length(), 2);
     ///
@@ -97,7 +99,7 @@ where
     /// // together, and push the result back into the stack.
     /// let result = machine.operate(&Item::Operator(MathOperator::Add));
     /// // Make sure the result is 3.
-    /// assert_eq!(result, Some(&Integer(3)));
+    /// assert_eq!(result, Ok(Some(&Integer(3))));
     /// // The final length of the stack should be 1 again.
     /// assert_eq!(machine.stack_length(), 1);
     /// ```
@@ -124,10 +126,17 @@ where
 
     /// Evaluates a [`Script`][Script] in the context of a `Machine`.
     ///
-    /// # Panics
+    /// Evaluation stops as soon as the operator system fails on any of the [`Item`s][Item], in
+    /// which case the error is wrapped into a [`ScriptFailure`][ScriptFailure] telling the position
+    /// of the failing [`Item`][Item] in the [`Script`][Script]. The [`Stack`][Stack] is left as the
+    /// failing operator left it, e.g. without any operands it had already popped.
+    ///
+    /// # Errors
     ///
-    /// Operating on a `Machine` that has an empty [`Stack`][Stack] can cause a panic if any of the
-    /// [`Item`s][Item] in the [`Script`][Script] is an operator that tries to pop from it.
+    /// Returns a [`ScriptFailure`][ScriptFailure] if the operator system fails on any of the
+    /// [`Item`s][Item] in the [`Script`][Script], e.g. because it is an operator that tries to pop
+    /// from an empty [`Stack`][Stack]. Its `index` tells the position of that [`Item`][Item], and
+    /// its `error` is the one returned by the operator system.
     ///
     /// # Examples
     ///
@@ -148,7 +157,7 @@ where
     /// ]));
     ///
     /// // The result should unsurprisingly be 3.
-    /// assert_eq!(result, Some(&Integer(3)));
+    /// assert_eq!(result, Ok(Some(&Integer(3))));
     /// // The final length of the stack should be 1.
     /// assert_eq!(machine.stack_length(), 1);
     /// ```
@@ -156,14 +165,186 @@ where
     /// [Script]: ../type.Script.html
     /// [Stack]: ../stack/struct.Stack.html
     /// [Item]: ../item/enum.Item.html
-    pub fn run_script(&mut self, script: ScriptRef<Op, Val>) -> Result<Option<&Val>, E> {
-        for item in script {
-            self.operate(item)?;
+    /// [ScriptFailure]: struct.ScriptFailure.html
+    pub fn run_script(
+        &mut self,
+        script: ScriptRef<Op, Val>,
+    ) -> Result<Option<&Val>, ScriptFailure<E>> {
+        for (index, item) in script.iter().enumerate() {
+            self.operate(item)
+                .map_err(|error| ScriptFailure { index, error })?;
+        }
+
+        Ok(self.stack.topmost())
+    }
+
+    /// Evaluates [`Item`s][Item] one by one as they come, e.g. while a [`Script`][Script] is still
+    /// being decoded, without ever holding all of them in memory.
+    ///
+    /// `items` can yield errors, e.g. [`StreamDecoder`][StreamDecoder] does so when its bytes
+    /// cannot be decoded. Evaluation stops as soon as any of the [`Item`s][Item] is an error or
+    /// the operator system fails on it, telling its position in a [`StreamFailure`][StreamFailure].
+    /// Note that any previous [`Item`s][Item] were already evaluated by then, and that the
+    /// [`Stack`][Stack] is left as the failing operator left it, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StreamFailure::Decoding`][StreamFailure] if any of the [`Item`s][Item] is an error,
+    /// or [`StreamFailure::Script`][StreamFailure] wrapping a [`ScriptFailure`][ScriptFailure] if
+    /// the operator system fails on it, e.g. because it is an operator that tries to pop from an
+    /// empty [`Stack`][Stack].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::prelude::*;
+    /// use scriptful::core::machine::StreamFailure;
+    /// use scriptful::core::value::Value::*;
+    /// use scriptful::op_systems::simple_math::*;
+    ///
+    /// let mut machine = Machine::new(&simple_math_op_sys);
+    ///
+    /// let items = [
+    ///     Ok(Item::Value(Integer(1))),
+    ///     Ok(Item::Value(Integer(2))),
+    ///     Ok(Item::Operator(MathOperator::Add)),
+    ///     Err("Connection lost"),
+    /// ];
+    /// let result = machine.run_stream(items);
+    ///
+    /// assert_eq!(
+    ///     result,
+    ///     Err(StreamFailure::Decoding {
+    ///         index: 3,
+    ///         error: "Connection lost"
+    ///     })
+    /// );
+    /// // The items before the error were evaluated nevertheless.
+    /// assert_eq!(machine.topmost(), Some(&Integer(3)));
+    /// ```
+    ///
+    /// [Item]: ../item/enum.Item.html
+    /// [Script]: ../type.Script.html
+    /// [Stack]: ../stack/struct.Stack.html
+    /// [StreamDecoder]: ../../codecs/simple/stream/struct.StreamDecoder.html
+    /// [ScriptFailure]: struct.ScriptFailure.html
+    /// [StreamFailure]: enum.StreamFailure.html
+    pub fn run_stream<I, D>(&mut self, items: I) -> Result<Option<&Val>, StreamFailure<D, E>>
+    where
+        I: IntoIterator<Item = Result<Item<Op, Val>, D>>,
+    {
+        for (index, item) in items.into_iter().enumerate() {
+            let item = item.map_err(|error| StreamFailure::Decoding { index, error })?;
+            self.operate(&item)
+                .map_err(|error| StreamFailure::Script(ScriptFailure { index, error }))?;
         }
 
         Ok(self.stack.topmost())
     }
 
+    /// Evaluates a [`Script`][Script] in the context of a `Machine`, just like
+    /// [`run_script`][run_script] does, and then tells whether it succeeded by means of an
+    /// [`Outcome`][Outcome]. Note that, as with [`run_script`][run_script], any values already
+    /// in the [`Stack`][Stack] and any open conditionals are taken into account.
+    ///
+    /// The `is_truthy` function decides whether the topmost value in the [`Stack`][Stack] counts as
+    /// `true`, e.g. [`Value::is_truthy`][Value::is_truthy]. It is a function argument rather than a
+    /// trait bound so that the same type of values can be evaluated with different rules.
+    ///
+    /// As with [`run_script`][run_script], evaluation stops as soon as the operator system fails
+    /// on any of the [`Item`s][Item], in which case a [`ScriptFailure`][ScriptFailure] is returned
+    /// instead of an [`Outcome`][Outcome].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::prelude::*;
+    /// use scriptful::core::value::Value::{self, *};
+    /// use scriptful::op_systems::flow_control::*;
+    ///
+    /// let mut machine = Machine::new(&flow_control_op_sys);
+    ///
+    /// let outcome = machine
+    ///     .evaluate(
+    ///         &[
+    ///             Item::Value(Integer(0)),
+    ///             Item::Operator(FlowOperator::If),
+    ///             Item::Value(Null),
+    ///             Item::Operator(FlowOperator::Else),
+    ///             Item::Value(String("ok".into())),
+    ///             Item::Operator(FlowOperator::EndIf),
+    ///         ],
+    ///         Value::is_truthy,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// assert!(outcome.is_success());
+    /// assert!(outcome.clean_stack);
+    /// assert_eq!(outcome.top(), Some(&String("ok".into())));
+    /// // `Null` was skipped, as it sits in the branch that was not taken.
+    /// assert_eq!(outcome.items_executed, 5);
+    /// ```
+    ///
+    /// [Script]: ../type.Script.html
+    /// [Stack]: ../stack/struct.Stack.html
+    /// [Item]: ../item/enum.Item.html
+    /// [run_script]: #method.run_script
+    /// [Outcome]: struct.Outcome.html
+    /// [ScriptFailure]: struct.ScriptFailure.html
+    /// [Value::is_truthy]: ../value/enum.Value.html#method.is_truthy
+    pub fn evaluate<T>(
+        &mut self,
+        script: ScriptRef<Op, Val>,
+        is_truthy: T,
+    ) -> Result<Outcome<Val>, ScriptFailure<E>>
+    where
+        T: Fn(&Val) -> bool,
+    {
+        let mut items_executed = 0;
+
+        for (index, item) in script.iter().enumerate() {
+            let active = self.if_stack.all_true();
+            self.operate(item)
+                .map_err(|error| ScriptFailure { index, error })?;
+            // Operators that enter or leave a branch that is not taken still count as executed.
+            if active || self.if_stack.all_true() {
+                items_executed += 1;
+            }
+        }
+
+        let stack = Vec::from(self.stack.as_slice());
+
+        Ok(Outcome {
+            truthy: stack.last().map(is_truthy).unwrap_or(false),
+            clean_stack: stack.len() == 1,
+            balanced: self.if_stack.is_empty(),
+            items_executed,
+            stack,
+        })
+    }
+
+    /// Returns a reference to the topmost [`Value`][Value] in the [`Stack`][Stack], if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::prelude::*;
+    /// use scriptful::core::value::Value::*;
+    /// use scriptful::op_systems::simple_math::*;
+    ///
+    /// let mut machine = Machine::new(&simple_math_op_sys);
+    /// assert_eq!(machine.topmost(), None);
+    ///
+    /// machine.operate(&Item::Value(Integer(1)));
+    /// assert_eq!(machine.topmost(), Some(&Integer(1)));
+    /// ```
+    ///
+    /// [Value]: ../value/enum.Value.html
+    /// [Stack]: ../stack/struct.Stack.html
+    pub fn topmost(&self) -> Option<&Val> {
+        self.stack.topmost()
+    }
+
     /// Returns the number of [`Value`s][Value] currently in the [`Stack`][Stack].
     ///
     /// # Examples
@@ -196,6 +377,187 @@ where
     }
 }
 
+/// The result of successfully [`evaluate`-ing][evaluate] a [`Script`][Script], telling whether it
+/// should be considered a success.
+///
+/// [`is_success`][is_success] and [`is_clean_success`][is_clean_success] follow the same criteria
+/// as `VerifyScript` in Bitcoin Core, respectively without and with the `CLEANSTACK` flag.
+///
+/// # Examples
+///
+/// ```rust
+/// use scriptful::prelude::*;
+/// use scriptful::core::value::Value::{self, *};
+/// use scriptful::op_systems::flow_control::*;
+///
+/// let mut machine = Machine::new(&flow_control_op_sys);
+///
+/// // An empty stack is never a success.
+/// let outcome = machine.evaluate(&[], Value::is_truthy).unwrap();
+/// assert!(!outcome.truthy);
+/// assert!(!outcome.is_success());
+///
+/// // Leaving more than one value in the stack is only a success without the clean stack rule.
+/// let outcome = machine
+///     .evaluate(&[Item::Value(Integer(1)), Item::Value(Integer(2))], Value::is_truthy)
+///     .unwrap();
+/// assert!(outcome.is_success());
+/// assert!(!outcome.is_clean_success());
+/// assert_eq!(outcome.stack, [Integer(1), Integer(2)]);
+///
+/// // Leaving a conditional open is never a success, even with a truthy topmost value.
+/// let outcome = machine
+///     .evaluate(&[Item::Value(Integer(1)), Item::Operator(FlowOperator::If)], Value::is_truthy)
+///     .unwrap();
+/// assert!(outcome.truthy);
+/// assert!(!outcome.balanced);
+/// assert!(!outcome.is_success());
+/// ```
+///
+/// [evaluate]: struct.Machine.html#method.evaluate
+/// [Script]: ../type.Script.html
+/// [is_success]: #method.is_success
+/// [is_clean_success]: #method.is_clean_success
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome<Val> {
+    /// The values left in the [`Stack`][Stack], from the bottom to the topmost one.
+    ///
+    /// [Stack]: ../stack/struct.Stack.html
+    pub stack: Vec<Val>,
+    /// Whether the topmost value is `true` according to the rule used for the evaluation. Always
+    /// `false` if the [`Stack`][Stack] is empty.
+    ///
+    /// [Stack]: ../stack/struct.Stack.html
+    pub truthy: bool,
+    /// Whether exactly one value was left in the [`Stack`][Stack].
+    ///
+    /// [Stack]: ../stack/struct.Stack.html
+    pub clean_stack: bool,
+    /// Whether all conditionals were closed, i.e. the [`ConditionStack`][ConditionStack] was left
+    /// empty.
+    ///
+    /// [ConditionStack]: ../condition_stack/struct.ConditionStack.html
+    pub balanced: bool,
+    /// The number of [`Item`s][Item] that were executed, not counting those skipped because they
+    /// were inside a branch of a conditional that was not taken.
+    ///
+    /// [Item]: ../item/enum.Item.html
+    pub items_executed: usize,
+}
+
+impl<Val> Outcome<Val> {
+    /// Returns a reference to the topmost value left in the [`Stack`][Stack], if any.
+    ///
+    /// [Stack]: ../stack/struct.Stack.html
+    pub fn top(&self) -> Option<&Val> {
+        self.stack.last()
+    }
+
+    /// Tells whether the evaluation succeeded, i.e. all conditionals were closed and the topmost
+    /// value is truthy.
+    pub fn is_success(&self) -> bool {
+        self.balanced && self.truthy
+    }
+
+    /// Same as [`is_success`][is_success], but also requires exactly one value to be left in the
+    /// [`Stack`][Stack].
+    ///
+    /// [is_success]: #method.is_success
+    /// [Stack]: ../stack/struct.Stack.html
+    pub fn is_clean_success(&self) -> bool {
+        self.is_success() && self.clean_stack
+    }
+}
+
+/// The error returned by [`Machine::run_script`][run_script] when the operator system fails on
+/// any of the [`Item`s][Item] in a [`Script`][Script].
+///
+/// # Examples
+///
+/// ```rust
+/// use scriptful::prelude::*;
+/// use scriptful::core::machine::ScriptFailure;
+/// use scriptful::core::value::Value::*;
+/// use scriptful::op_systems::logic::*;
+/// use scriptful::op_systems::OperatorError;
+///
+/// let mut machine = Machine::new(&logic_op_sys);
+///
+/// let result = machine.run_script(&[
+///     Item::Value(Boolean(false)),
+///     Item::Value(String("Amount exceeds the daily limit".into())),
+///     Item::Operator(LogicOperator::Assert),
+/// ]);
+///
+/// let failure = result.unwrap_err();
+/// assert_eq!(failure.index, 2);
+/// assert_eq!(failure.error.message(), Some(&String("Amount exceeds the daily limit".into())));
+/// ```
+///
+/// [run_script]: struct.Machine.html#method.run_script
+/// [Item]: ../item/enum.Item.html
+/// [Script]: ../type.Script.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptFailure<E> {
+    /// The position in the [`Script`][Script] of the [`Item`][Item] that failed.
+    ///
+    /// [Item]: ../item/enum.Item.html
+    /// [Script]: ../type.Script.html
+    pub index: usize,
+    /// The error returned by the operator system.
+    pub error: E,
+}
+
+impl<E> core::fmt::Display for ScriptFailure<E>
+where
+    E: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Script failed at item {}: {}", self.index, self.error)
+    }
+}
+
+/// The error returned by [`Machine::run_stream`][run_stream] when any of the [`Item`s][Item] could
+/// not be obtained, or the operator system fails on it.
+///
+/// [run_stream]: struct.Machine.html#method.run_stream
+/// [Item]: ../item/enum.Item.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamFailure<D, E> {
+    /// The [`Item`][Item] at `index` could not be obtained, e.g. decoded, because of `error`.
+    ///
+    /// [Item]: ../item/enum.Item.html
+    Decoding {
+        /// The position in the sequence of [`Item`s][Item] of the one that could not be obtained.
+        ///
+        /// [Item]: ../item/enum.Item.html
+        index: usize,
+        /// The error yielded instead of the [`Item`][Item].
+        ///
+        /// [Item]: ../item/enum.Item.html
+        error: D,
+    },
+    /// The operator system failed on an [`Item`][Item].
+    ///
+    /// [Item]: ../item/enum.Item.html
+    Script(ScriptFailure<E>),
+}
+
+impl<D, E> core::fmt::Display for StreamFailure<D, E>
+where
+    D: core::fmt::Display,
+    E: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            StreamFailure::Decoding { index, error } => {
+                write!(f, "Failed to obtain item {}: {}", index, error)
+            }
+            StreamFailure::Script(failure) => failure.fmt(f),
+        }
+    }
+}
+
 /// Debugging of `Machine` only shows the internal [`Stack`][Stack], but not the operator system.
 ///
 /// The explanation for this is straightforward: how do you print a dynamic reference to a function?
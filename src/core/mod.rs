@@ -3,6 +3,7 @@ use alloc::vec::Vec;
 use crate::core::value::Value;
 
 pub mod condition_stack;
+pub mod decimal;
 pub mod item;
 pub mod machine;
 pub mod stack;
@@ -13,6 +14,11 @@ pub mod value;
 /// [Item]: item
 pub type Script<Op, Val = Value> = Vec<item::Item<Op, Val>>;
 
+/// An [`Item`][Item] whose values borrow their payloads, e.g. from the bytes they were decoded from.
+///
+/// [Item]: item/enum.Item.html
+pub type ItemRef<'a, Op> = item::Item<Op, value::ValueRef<'a>>;
+
 /// Convenient type alias for `&Script<Op, Val>`.
 pub type ScriptRef<'a, Op, Val = Value> = &'a [item::Item<Op, Val>];
 
@@ -58,6 +58,93 @@ where
         self.main.len()
     }
 
+    /// Returns the number of values in the `alt` sub-stack.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::prelude::*;
+    /// use scriptful::core::value::Value::*;
+    ///
+    /// let mut stack = Stack::default();
+    /// stack.push(Integer(i128::default()));
+    /// stack.pop_into_alt();
+    ///
+    /// assert_eq!(stack.length(), 0);
+    /// assert_eq!(stack.alt_length(), 1);
+    /// ```
+    pub fn alt_length(&self) -> usize {
+        self.alt.len()
+    }
+
+    /// Returns all the values in the `main` sub-stack, from the bottom to the topmost one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::prelude::*;
+    /// use scriptful::core::value::Value::*;
+    ///
+    /// let mut stack = Stack::default();
+    /// stack.push(Integer(1));
+    /// stack.push(Integer(2));
+    ///
+    /// assert_eq!(stack.as_slice(), &[Integer(1), Integer(2)]);
+    /// ```
+    pub fn as_slice(&self) -> &[Val] {
+        &self.main
+    }
+
+    /// Returns a reference to the value that sits `depth` positions below the topmost value in the
+    /// `main` sub-stack, i.e. `peek(0)` is equivalent to [`topmost`][topmost].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::prelude::*;
+    /// use scriptful::core::value::Value::*;
+    ///
+    /// let mut stack = Stack::default();
+    /// stack.push(Integer(1));
+    /// stack.push(Integer(2));
+    ///
+    /// assert_eq!(stack.peek(0), Some(&Integer(2)));
+    /// assert_eq!(stack.peek(1), Some(&Integer(1)));
+    /// assert_eq!(stack.peek(2), None);
+    /// ```
+    ///
+    /// [topmost]: #method.topmost
+    pub fn peek(&self, depth: usize) -> Option<&Val> {
+        let index = self.main.len().checked_sub(depth)?.checked_sub(1)?;
+
+        self.main.get(index)
+    }
+
+    /// Removes the value that sits `depth` positions below the topmost value in the `main`
+    /// sub-stack and returns it, i.e. `remove(0)` is equivalent to [`pop`][pop].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::prelude::*;
+    /// use scriptful::core::value::Value::*;
+    ///
+    /// let mut stack = Stack::default();
+    /// stack.push(Integer(1));
+    /// stack.push(Integer(2));
+    ///
+    /// assert_eq!(stack.remove(1), Some(Integer(1)));
+    /// assert_eq!(stack.topmost(), Some(&Integer(2)));
+    /// assert_eq!(stack.length(), 1);
+    /// ```
+    ///
+    /// [pop]: #method.pop
+    pub fn remove(&mut self, depth: usize) -> Option<Val> {
+        let index = self.main.len().checked_sub(depth)?.checked_sub(1)?;
+
+        Some(self.main.remove(index))
+    }
+
     /// Removes the topmost value in the `main` sub-stack and returns it.
     ///
     /// # Examples
@@ -71,7 +158,7 @@ where
     /// stack.push(value.clone());
     /// let popped = stack.pop();
     ///
-    /// assert_eq!(value, popped);
+    /// assert_eq!(popped, Some(value));
     /// ```
     pub fn pop(&mut self) -> Option<Val> {
         self.main.pop()
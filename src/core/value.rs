@@ -1,7 +1,15 @@
-use alloc::string::String;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 #[cfg(feature = "use_serde")]
 use serde;
 
+use crate::core::decimal;
+use crate::op_systems::OperatorError;
+#[cfg(feature = "bigint")]
+pub use num_bigint::BigInt;
+#[cfg(feature = "bigint")]
+use num_traits::ToPrimitive;
+
 /// An algebraic data type that can be used to represent many types of values that can be present in
 /// a [`Stack`][Stack].
 ///
@@ -9,21 +17,28 @@ use serde;
 /// provided in expectation that some users will prefer not to define their own type systems but
 /// rather use a stock solution.
 ///
-/// `Value` has four variants that should cover most use cases, namely: [`Boolean`][Boolean],
-/// [`Float`][Float], [`Integer`][Integer] or [`String`][String].
+/// `Value` has eight variants that should cover most use cases, namely: [`Boolean`][Boolean],
+/// [`Decimal`][Decimal], [`Float`][Float], [`Integer`][Integer], [`String`][String],
+/// [`Null`][Null], [`Bytes`][Bytes] or [`Timestamp`][Timestamp]. An additional [`BigInt`][BigInt] variant for arbitrary-precision integers is
+/// available behind the `bigint` feature.
 ///
 /// The point of `Value` is being used inside [`Script`s][Script] (wrapped in an [`Item`][Item]) or
 /// to be pushed into a [`Stack`][Stack].
 ///
-/// `Value` does not implement any methods other than implementations of some traits from the
-/// [`core`][core] crate.
+/// Besides implementations of many traits from the [`core`][core] crate, `Value` provides a few
+/// methods for telling its type, its truthiness, and converting it into other variants.
 ///
 /// [Stack]: ../stack/struct.Stack.html
 /// [enum]: https://doc.rust-lang.org/std/keyword.enum.html
 /// [Boolean]: #variant.Boolean
+/// [Decimal]: #variant.Decimal
 /// [Float]: #variant.Float
 /// [Integer]: #variant.Integer
 /// [String]: #variant.String
+/// [Null]: #variant.Null
+/// [Bytes]: #variant.Bytes
+/// [Timestamp]: #variant.Timestamp
+/// [BigInt]: #variant.BigInt
 /// [Script]: ../type.Script.html
 /// [Item]: ../item/enum.Item.html
 /// [known limitations]: ../../#known-limitations
@@ -33,67 +48,603 @@ use serde;
 pub enum Value {
     /// A binary value: either `true` or `false`.
     Boolean(bool),
+    /// A signed fixed-point decimal value, for exact arithmetic.
+    ///
+    /// Operating a `Decimal` with an `Integer` yields a `Decimal`, while operating it with a
+    /// `Float` yields a `Float`.
+    Decimal(decimal::Decimal),
     /// A signed floating point value.
     Float(f64),
     /// A signed integer value.
     Integer(i128),
     /// A string of characters.
     String(String),
+    /// The absence of a value.
+    ///
+    /// `Null` is contagious: adding, multiplying, subtracting or negating it always yields `Null`.
+    Null,
+    /// An arbitrary sequence of bytes, such as a hash digest or a public key.
+    Bytes(Vec<u8>),
+    /// A point in time, as the number of seconds since the Unix epoch (`1970-01-01T00:00:00Z`),
+    /// ignoring leap seconds.
+    Timestamp(i64),
+    /// A signed integer value of arbitrary precision, which never overflows.
+    ///
+    /// Operating a `BigInt` with an `Integer` yields a `BigInt`, while operating it with a `Float`
+    /// yields a `Float`.
+    #[cfg(feature = "bigint")]
+    BigInt(num_bigint::BigInt),
+}
+
+impl Value {
+    /// The name of the variant of this value, in lowercase, e.g. `"integer"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Boolean(_) => "boolean",
+            Value::Decimal(_) => "decimal",
+            Value::Float(_) => "float",
+            Value::Integer(_) => "integer",
+            Value::String(_) => "string",
+            Value::Null => "null",
+            Value::Bytes(_) => "bytes",
+            Value::Timestamp(_) => "timestamp",
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => "bigint",
+        }
+    }
+
+    /// Tells whether this value should be considered `true` when used as a condition.
+    ///
+    /// The truthiness rules are as follows:
+    ///
+    /// - `Boolean` values are truthy if they are `true`.
+    /// - Numeric values are truthy if they are different from zero. `NaN` is not truthy.
+    /// - `String` values are truthy if they are not empty.
+    /// - `Bytes` values are truthy unless they are empty, all of their bytes are zero, or all of
+    ///   their bytes are zero but the last one, which is `0x80` (i.e. negative zero). This is the
+    ///   same rule used by `CastToBool` in Bitcoin Script.
+    /// - `Timestamp` values are always truthy, as any point in time is a meaningful value.
+    /// - `Null` is never truthy.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scriptful::core::value::Value::*;
+    ///
+    /// assert!(Integer(-1).is_truthy());
+    /// assert!(!Float(0.).is_truthy());
+    /// assert!(String("false".into()).is_truthy());
+    /// assert!(!Null.is_truthy());
+    /// ```
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Boolean(x) => *x,
+            Value::Decimal(x) => x.mantissa() != 0,
+            Value::Float(x) => *x != 0. && !x.is_nan(),
+            Value::Integer(x) => *x != 0,
+            Value::String(x) => !x.is_empty(),
+            Value::Null => false,
+            Value::Bytes(x) => bytes_are_truthy(x),
+            Value::Timestamp(_) => true,
+            #[cfg(feature = "bigint")]
+            Value::BigInt(x) => x.sign() != num_bigint::Sign::NoSign,
+        }
+    }
+
+    /// Converts this value into a `Boolean` according to its [truthiness][is_truthy].
+    ///
+    /// [is_truthy]: #method.is_truthy
+    pub fn to_boolean(&self) -> Value {
+        Value::Boolean(self.is_truthy())
+    }
+
+    /// Converts this value into a `Float`.
+    ///
+    /// Booleans become `0` or `1`, strings are parsed, timestamps become their number of seconds
+    /// since the Unix epoch, and any other numeric value is approximated.
+    pub fn to_float(&self) -> Result<Value, ConversionError> {
+        f64::try_from(self).map(Value::Float)
+    }
+
+    /// Converts this value into an `Integer`.
+    ///
+    /// Booleans become `0` or `1`, strings are parsed, timestamps become their number of seconds
+    /// since the Unix epoch, and decimals and floats are truncated
+    /// towards zero. Conversion fails for numbers that do not fit into an `i128`, as well as for
+    /// `NaN` and infinite floats.
+    pub fn to_integer(&self) -> Result<Value, ConversionError> {
+        i128::try_from(self).map(Value::Integer)
+    }
+
+    /// Converts this value into a `String`, as formatted by its `Display` implementation.
+    pub fn to_string_value(&self) -> Value {
+        match self {
+            Value::String(x) => Value::String(x.clone()),
+            other => Value::String(other.to_string()),
+        }
+    }
+}
+
+/// Bytes are truthy unless they are empty or encode zero or negative zero, as in Bitcoin Script.
+fn bytes_are_truthy(bytes: &[u8]) -> bool {
+    match bytes.split_last() {
+        Some((last, rest)) => rest.iter().any(|b| *b != 0) || (*last != 0 && *last != 0x80),
+        None => false,
+    }
+}
+
+/// The error that is produced when a [`Value`][Value] cannot be converted into another type.
+///
+/// [Value]: enum.Value.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversionError {
+    /// The [`type_name`][type_name] of the value that failed to be converted.
+    ///
+    /// [type_name]: enum.Value.html#method.type_name
+    pub from: &'static str,
+    /// The name of the type into which the value failed to be converted.
+    pub into: &'static str,
+}
+
+impl core::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "Value of type {} cannot be converted into {}",
+            self.from, self.into
+        )
+    }
+}
+
+impl From<bool> for Value {
+    fn from(input: bool) -> Self {
+        Value::Boolean(input)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(input: f32) -> Self {
+        Value::Float(input.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(input: f64) -> Self {
+        Value::Float(input)
+    }
+}
+
+impl From<decimal::Decimal> for Value {
+    fn from(input: decimal::Decimal) -> Self {
+        Value::Decimal(input)
+    }
+}
+
+impl From<String> for Value {
+    fn from(input: String) -> Self {
+        Value::String(input)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(input: Vec<u8>) -> Self {
+        Value::Bytes(input)
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(input: &[u8]) -> Self {
+        Value::Bytes(input.into())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(input: &str) -> Self {
+        Value::String(input.into())
+    }
+}
+
+impl<T> From<Option<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(input: Option<T>) -> Self {
+        input.map(Into::into).unwrap_or(Value::Null)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(input: $t) -> Self {
+                    Value::Integer(input.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl TryFrom<&Value> for i128 {
+    type Error = ConversionError;
+
+    fn try_from(input: &Value) -> Result<Self, Self::Error> {
+        let error = ConversionError {
+            from: input.type_name(),
+            into: "integer",
+        };
+
+        match input {
+            Value::Boolean(x) => Ok(i128::from(*x)),
+            Value::Decimal(x) => x
+                .rescale(0, decimal::Rounding::Down)
+                .map(|x| x.mantissa())
+                .ok_or(error),
+            // `i128::MIN` is exactly representable as a float, but `i128::MAX` is not.
+            Value::Float(x) if *x >= i128::MIN as f64 && *x < -(i128::MIN as f64) => Ok(*x as i128),
+            Value::Integer(x) => Ok(*x),
+            Value::String(x) => x.parse().map_err(|_| error),
+            Value::Timestamp(x) => Ok(i128::from(*x)),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(x) => i128::try_from(x).map_err(|_| error),
+            _ => Err(error),
+        }
+    }
+}
+
+impl TryFrom<Value> for i128 {
+    type Error = ConversionError;
+
+    fn try_from(input: Value) -> Result<Self, Self::Error> {
+        i128::try_from(&input)
+    }
+}
+
+impl TryFrom<&Value> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(input: &Value) -> Result<Self, Self::Error> {
+        let error = ConversionError {
+            from: input.type_name(),
+            into: "float",
+        };
+
+        match input {
+            Value::Boolean(x) => Ok(f64::from(u8::from(*x))),
+            Value::Decimal(x) => Ok(x.to_f64()),
+            Value::Float(x) => Ok(*x),
+            Value::Integer(x) => Ok(*x as f64),
+            Value::String(x) => x.parse().map_err(|_| error),
+            Value::Timestamp(x) => Ok(*x as f64),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(x) => x.to_f64().ok_or(error),
+            _ => Err(error),
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(input: Value) -> Result<Self, Self::Error> {
+        f64::try_from(&input)
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = ConversionError;
+
+    /// Only `Boolean` values can be converted into `bool`. Use [`is_truthy`][is_truthy] for
+    /// converting any other value.
+    ///
+    /// [is_truthy]: enum.Value.html#method.is_truthy
+    fn try_from(input: Value) -> Result<Self, Self::Error> {
+        match input {
+            Value::Boolean(x) => Ok(x),
+            other => Err(ConversionError {
+                from: other.type_name(),
+                into: "boolean",
+            }),
+        }
+    }
 }
 
+impl TryFrom<Value> for Vec<u8> {
+    type Error = ConversionError;
+
+    /// Only `Bytes` values can be converted into `Vec<u8>`.
+    fn try_from(input: Value) -> Result<Self, Self::Error> {
+        match input {
+            Value::Bytes(x) => Ok(x),
+            other => Err(ConversionError {
+                from: other.type_name(),
+                into: "bytes",
+            }),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = ConversionError;
+
+    /// Only `String` values can be converted into `String`. Use the `Display` implementation for
+    /// formatting any other value.
+    fn try_from(input: Value) -> Result<Self, Self::Error> {
+        match input {
+            Value::String(x) => Ok(x),
+            other => Err(ConversionError {
+                from: other.type_name(),
+                into: "string",
+            }),
+        }
+    }
+}
+
+/// Formats values in the way they would be written in most programming languages, e.g. `true`,
+/// `-1.5`, `42`, `null`. Strings are written as is, without quotes, bytes are written in
+/// hexadecimal, e.g. `0x00ff`, and timestamps are written in UTC as per [RFC 3339], e.g.
+/// `2009-01-03T18:15:05Z`.
+///
+/// [RFC 3339]: https://www.rfc-editor.org/rfc/rfc3339
+impl core::fmt::Display for Value {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Value::Boolean(x) => write!(f, "{}", x),
+            Value::Decimal(x) => write!(f, "{}", x),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Integer(x) => write!(f, "{}", x),
+            Value::String(x) => write!(f, "{}", x),
+            Value::Null => write!(f, "null"),
+            Value::Bytes(x) => {
+                write!(f, "0x")?;
+                x.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+            Value::Timestamp(x) => {
+                let (year, month, day) = civil_from_days(x.div_euclid(86_400));
+                let seconds = x.rem_euclid(86_400);
+                // The sign is not counted in the width of the year, which always has four digits.
+                if year < 0 {
+                    write!(f, "-")?;
+                }
+                write!(
+                    f,
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                    year.unsigned_abs(),
+                    month,
+                    day,
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                )
+            }
+            #[cfg(feature = "bigint")]
+            Value::BigInt(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+/// Converts a number of days since the Unix epoch into a year, month and day in the proleptic
+/// Gregorian calendar, using [Howard Hinnant's algorithm][algorithm].
+///
+/// [algorithm]: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Converts a year, month and day in the proleptic Gregorian calendar into a number of days since
+/// the Unix epoch, or `None` if it does not fit into an `i64`. This is the inverse of
+/// [`civil_from_days`][civil_from_days], and does not check that the date actually exists.
+///
+/// [civil_from_days]: fn.civil_from_days.html
+#[cfg(feature = "codecs")]
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> Option<i64> {
+    let year = i128::from(year) - i128::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = i128::from((month + 9) % 12);
+    let day_of_year = (153 * month_index + 2) / 5 + i128::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    i64::try_from(era * 146_097 + day_of_era - 719_468).ok()
+}
+
+/// Negation of numbers and booleans.
+///
+/// Fails with [`OperatorError::UnsupportedOperands`][UnsupportedOperands] for other types, and with
+/// [`OperatorError::Overflow`][Overflow] if the negated number does not fit in its type.
+///
+/// [UnsupportedOperands]: ../../op_systems/enum.OperatorError.html#variant.UnsupportedOperands
+/// [Overflow]: ../../op_systems/enum.OperatorError.html#variant.Overflow
 impl core::ops::Not for Value {
-    type Output = Self;
+    type Output = Result<Self, OperatorError>;
 
     fn not(self) -> Self::Output {
         use Value::*;
         match self {
-            Boolean(x) => Boolean(!x),
-            Float(x) => Float(-x),
-            Integer(x) => Integer(-x),
-            _ => panic!("Type of {:?} cannot be negated", self),
+            Boolean(x) => Ok(Boolean(!x)),
+            Decimal(x) => x.checked_neg().map(Decimal).ok_or(OperatorError::Overflow),
+            Float(x) => Ok(Float(-x)),
+            Integer(x) => x.checked_neg().map(Integer).ok_or(OperatorError::Overflow),
+            Null => Ok(Null),
+            #[cfg(feature = "bigint")]
+            BigInt(x) => Ok(BigInt(-x)),
+            x => Err(OperatorError::UnsupportedOperands {
+                first: x.type_name(),
+                second: None,
+            }),
         }
     }
 }
 
+/// Addition of numbers, or disjunction of booleans.
+///
+/// Fails with [`OperatorError::UnsupportedOperands`][UnsupportedOperands] for other combinations of
+/// types, and with [`OperatorError::Overflow`][Overflow] if the sum of integers or decimals does not
+/// fit in their type.
+///
+/// [UnsupportedOperands]: ../../op_systems/enum.OperatorError.html#variant.UnsupportedOperands
+/// [Overflow]: ../../op_systems/enum.OperatorError.html#variant.Overflow
 #[allow(clippy::suspicious_arithmetic_impl)]
 impl core::ops::Add for Value {
-    type Output = Self;
+    type Output = Result<Self, OperatorError>;
 
     fn add(self, rhs: Self) -> Self::Output {
         use Value::*;
-        match (self, rhs) {
+        Ok(match (self, rhs) {
             (Boolean(a), Boolean(b)) => Boolean(a || b),
+            (Decimal(a), Decimal(b)) => Decimal(a.checked_add(b).ok_or(OperatorError::Overflow)?),
+            (Decimal(a), Float(b)) => Float(a.to_f64() + b),
+            (Decimal(a), Integer(b)) => {
+                Decimal(a.checked_add(b.into()).ok_or(OperatorError::Overflow)?)
+            }
+            (Float(a), Decimal(b)) => Float(a + b.to_f64()),
             (Float(a), Float(b)) => Float(a + b),
             (Float(a), Integer(b)) => Float(a + b as f64),
-            (Integer(a), Integer(b)) => Integer(a + b),
+            (Integer(a), Integer(b)) => Integer(a.checked_add(b).ok_or(OperatorError::Overflow)?),
+            (Integer(a), Decimal(b)) => Decimal(
+                decimal::Decimal::from(a)
+                    .checked_add(b)
+                    .ok_or(OperatorError::Overflow)?,
+            ),
             (Integer(a), Float(b)) => Float(a as f64 + b),
-            (a, b) => panic!("Types of {:?} and {:?} cannot be added together", a, b),
-        }
+            (Null, _) | (_, Null) => Null,
+            #[cfg(feature = "bigint")]
+            (BigInt(a), BigInt(b)) => BigInt(a + b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Float(b)) => Float(a.to_f64().unwrap_or(f64::NAN) + b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Integer(b)) => BigInt(a + b),
+            #[cfg(feature = "bigint")]
+            (Float(a), BigInt(b)) => Float(a + b.to_f64().unwrap_or(f64::NAN)),
+            #[cfg(feature = "bigint")]
+            (Integer(a), BigInt(b)) => BigInt(a + b),
+            (a, b) => {
+                return Err(OperatorError::UnsupportedOperands {
+                    first: a.type_name(),
+                    second: Some(b.type_name()),
+                })
+            }
+        })
     }
 }
 
+/// Multiplication of numbers, or conjunction of booleans.
+///
+/// Fails with [`OperatorError::UnsupportedOperands`][UnsupportedOperands] for other combinations of
+/// types, and with [`OperatorError::Overflow`][Overflow] if the product of integers or decimals does
+/// not fit in their type.
+///
+/// [UnsupportedOperands]: ../../op_systems/enum.OperatorError.html#variant.UnsupportedOperands
+/// [Overflow]: ../../op_systems/enum.OperatorError.html#variant.Overflow
 #[allow(clippy::suspicious_arithmetic_impl)]
 impl core::ops::Mul for Value {
-    type Output = Self;
+    type Output = Result<Self, OperatorError>;
 
     fn mul(self, rhs: Self) -> Self::Output {
         use Value::*;
-        match (self, rhs) {
+        Ok(match (self, rhs) {
             (Boolean(a), Boolean(b)) => Boolean(a && b),
+            (Decimal(a), Decimal(b)) => Decimal(a.checked_mul(b).ok_or(OperatorError::Overflow)?),
+            (Decimal(a), Float(b)) => Float(a.to_f64() * b),
+            (Decimal(a), Integer(b)) => {
+                Decimal(a.checked_mul(b.into()).ok_or(OperatorError::Overflow)?)
+            }
+            (Float(a), Decimal(b)) => Float(a * b.to_f64()),
             (Float(a), Float(b)) => Float(a * b),
             (Float(a), Integer(b)) => Float(a * b as f64),
-            (Integer(a), Integer(b)) => Integer(a * b),
+            (Integer(a), Integer(b)) => Integer(a.checked_mul(b).ok_or(OperatorError::Overflow)?),
+            (Integer(a), Decimal(b)) => Decimal(
+                decimal::Decimal::from(a)
+                    .checked_mul(b)
+                    .ok_or(OperatorError::Overflow)?,
+            ),
             (Integer(a), Float(b)) => Float(a as f64 * b),
-            (a, b) => panic!("Types of {:?} and {:?} cannot be multiplied together", a, b),
-        }
+            (Null, _) | (_, Null) => Null,
+            #[cfg(feature = "bigint")]
+            (BigInt(a), BigInt(b)) => BigInt(a * b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Float(b)) => Float(a.to_f64().unwrap_or(f64::NAN) * b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Integer(b)) => BigInt(a * b),
+            #[cfg(feature = "bigint")]
+            (Float(a), BigInt(b)) => Float(a * b.to_f64().unwrap_or(f64::NAN)),
+            #[cfg(feature = "bigint")]
+            (Integer(a), BigInt(b)) => BigInt(a * b),
+            (a, b) => {
+                return Err(OperatorError::UnsupportedOperands {
+                    first: a.type_name(),
+                    second: Some(b.type_name()),
+                })
+            }
+        })
     }
 }
 
+/// Subtraction of numbers, or disjunction of a boolean with the negation of another.
+///
+/// Fails with [`OperatorError::UnsupportedOperands`][UnsupportedOperands] for other combinations of
+/// types, and with [`OperatorError::Overflow`][Overflow] if the difference of integers or decimals
+/// does not fit in their type.
+///
+/// [UnsupportedOperands]: ../../op_systems/enum.OperatorError.html#variant.UnsupportedOperands
+/// [Overflow]: ../../op_systems/enum.OperatorError.html#variant.Overflow
 impl core::ops::Sub for Value {
-    type Output = Self;
+    type Output = Result<Self, OperatorError>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        self + !rhs
+        use Value::*;
+        Ok(match (self, rhs) {
+            (Boolean(a), Boolean(b)) => Boolean(a || !b),
+            (Decimal(a), Decimal(b)) => Decimal(a.checked_sub(b).ok_or(OperatorError::Overflow)?),
+            (Decimal(a), Float(b)) => Float(a.to_f64() - b),
+            (Decimal(a), Integer(b)) => {
+                Decimal(a.checked_sub(b.into()).ok_or(OperatorError::Overflow)?)
+            }
+            (Float(a), Decimal(b)) => Float(a - b.to_f64()),
+            (Float(a), Float(b)) => Float(a - b),
+            (Float(a), Integer(b)) => Float(a - b as f64),
+            (Integer(a), Integer(b)) => Integer(a.checked_sub(b).ok_or(OperatorError::Overflow)?),
+            (Integer(a), Decimal(b)) => Decimal(
+                decimal::Decimal::from(a)
+                    .checked_sub(b)
+                    .ok_or(OperatorError::Overflow)?,
+            ),
+            (Integer(a), Float(b)) => Float(a as f64 - b),
+            (Null, _) | (_, Null) => Null,
+            #[cfg(feature = "bigint")]
+            (BigInt(a), BigInt(b)) => BigInt(a - b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Float(b)) => Float(a.to_f64().unwrap_or(f64::NAN) - b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Integer(b)) => BigInt(a - b),
+            #[cfg(feature = "bigint")]
+            (Float(a), BigInt(b)) => Float(a - b.to_f64().unwrap_or(f64::NAN)),
+            #[cfg(feature = "bigint")]
+            (Integer(a), BigInt(b)) => BigInt(a - b),
+            (a, b) => {
+                return Err(OperatorError::UnsupportedOperands {
+                    first: a.type_name(),
+                    second: Some(b.type_name()),
+                })
+            }
+        })
     }
 }
 
@@ -101,11 +652,25 @@ impl core::ops::Sub for Value {
 ///
 /// A floating point values is considered equal to another float or an integer if the difference is
 /// less than `10^9`.
+///
+/// Decimals are compared exactly against other decimals and integers, and approximately against
+/// floats. The same goes for big integers. `Null` is only ever equal to `Null`.
+///
+/// Note that this comparison is not transitive, and thus `Value` cannot implement `Eq` nor `Hash`.
+/// Wrap values into [`CanonicalValue`][CanonicalValue] where total equality, ordering or hashing
+/// are needed.
+///
+/// [CanonicalValue]: struct.CanonicalValue.html
 impl core::cmp::PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         use Value::*;
         match (self, other) {
             (Boolean(a), Boolean(b)) => a == b,
+            (Decimal(a), Decimal(b)) => a == b,
+            (Decimal(a), Integer(b)) | (Integer(b), Decimal(a)) => *a == decimal::Decimal::from(*b),
+            (Decimal(a), Float(b)) | (Float(b), Decimal(a)) => {
+                (a.to_f64() - b) * (a.to_f64() - b) < 0.000_000_000_000_000_000_1
+            }
             (Float(a), Float(b)) => (a - b) * (a - b) < 0.000_000_000_000_000_000_1,
             (Float(a), Integer(b)) => {
                 (a - *b as f64) * (a - *b as f64) < 0.000_000_000_000_000_000_1
@@ -115,94 +680,727 @@ impl core::cmp::PartialEq for Value {
                 (*a as f64 - b) * (*a as f64 - *b) < 0.000_000_000_000_000_000_1
             }
             (String(a), String(b)) => a == b,
+            (Null, Null) => true,
+            (Bytes(a), Bytes(b)) => a == b,
+            (Timestamp(a), Timestamp(b)) => a == b,
+            #[cfg(feature = "bigint")]
+            (BigInt(a), BigInt(b)) => a == b,
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Integer(b)) | (Integer(b), BigInt(a)) => *a == num_bigint::BigInt::from(*b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Float(b)) | (Float(b), BigInt(a)) => {
+                let a = a.to_f64().unwrap_or(f64::NAN);
+                (a - b) * (a - b) < 0.000_000_000_000_000_000_1
+            }
             _ => false,
         }
     }
 }
 
+/// A wrapper around [`Value`][Value] implementing total equality, ordering and hashing, so that
+/// values can be used as keys in maps and sets.
+///
+/// Canonical equality is stricter than the approximate comparison in the `PartialEq`
+/// implementation for [`Value`][Value]:
+///
+/// - Values of different variants are never equal, e.g. `Integer(1)` and `Float(1.)` are different.
+/// - Floats are compared bit by bit, i.e. following the total order defined by IEEE 754, in which
+///   `-0.0` is less than `0.0`. The only exception is `NaN`: all of its representations are
+///   considered to be a single canonical `NaN`, which is equal to itself and greater than any other
+///   float.
+/// - Decimals are compared by their numeric value, so `1.0` equals `1.00`.
+///
+/// Values of different variants are ordered as follows: `Null`, `Boolean`, `Integer`, `BigInt`,
+/// `Decimal`, `Float`, `String`, `Bytes` and `Timestamp`.
+///
+/// # Examples
+///
+/// ```rust
+/// use scriptful::core::value::{CanonicalValue, Value::*};
+///
+/// assert_eq!(Float(f64::NAN) == Float(f64::NAN), false);
+/// assert_eq!(CanonicalValue(Float(f64::NAN)), CanonicalValue(Float(f64::NAN)));
+///
+/// assert_eq!(Float(1.) == Integer(1), true);
+/// assert_ne!(CanonicalValue(Float(1.)), CanonicalValue(Integer(1)));
+/// ```
+///
+/// [Value]: enum.Value.html
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct CanonicalValue(pub Value);
+
+impl CanonicalValue {
+    /// Unwraps the inner [`Value`][Value].
+    ///
+    /// [Value]: enum.Value.html
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+
+    /// The position of the variant of the inner value in the canonical order.
+    fn rank(&self) -> u8 {
+        match self.0 {
+            Value::Null => 0,
+            Value::Boolean(_) => 1,
+            Value::Integer(_) => 2,
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => 3,
+            Value::Decimal(_) => 4,
+            Value::Float(_) => 5,
+            Value::String(_) => 6,
+            Value::Bytes(_) => 7,
+            Value::Timestamp(_) => 8,
+        }
+    }
+}
+
+/// Maps all `NaN`s into a single one, so that they can be compared and hashed consistently.
+fn canonical_float_bits(input: f64) -> u64 {
+    if input.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        input.to_bits()
+    }
+}
+
+impl From<Value> for CanonicalValue {
+    fn from(input: Value) -> Self {
+        CanonicalValue(input)
+    }
+}
+
+impl core::cmp::PartialEq for CanonicalValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == core::cmp::Ordering::Equal
+    }
+}
+
+impl core::cmp::Eq for CanonicalValue {}
+
+impl core::cmp::PartialOrd for CanonicalValue {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl core::cmp::Ord for CanonicalValue {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        use core::cmp::Ordering;
+        use Value::*;
+        match (&self.0, &other.0) {
+            (Boolean(a), Boolean(b)) => a.cmp(b),
+            (Decimal(a), Decimal(b)) => a.cmp(b),
+            (Float(a), Float(b)) => match (a.is_nan(), b.is_nan()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => a.total_cmp(b),
+            },
+            (Integer(a), Integer(b)) => a.cmp(b),
+            (String(a), String(b)) => a.cmp(b),
+            (Null, Null) => Ordering::Equal,
+            (Bytes(a), Bytes(b)) => a.cmp(b),
+            (Timestamp(a), Timestamp(b)) => a.cmp(b),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), BigInt(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl core::hash::Hash for CanonicalValue {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        use Value::*;
+        self.rank().hash(state);
+        match &self.0 {
+            Boolean(x) => x.hash(state),
+            Decimal(x) => x.hash(state),
+            Float(x) => canonical_float_bits(*x).hash(state),
+            Integer(x) => x.hash(state),
+            String(x) => x.hash(state),
+            Null => {}
+            Bytes(x) => x.hash(state),
+            Timestamp(x) => x.hash(state),
+            #[cfg(feature = "bigint")]
+            BigInt(x) => x.hash(state),
+        }
+    }
+}
+
+/// A borrowed counterpart of [`Value`][Value], whose `String` and `Bytes` payloads point into
+/// someone else's memory, e.g. the encoded script they were decoded from, instead of owning a copy.
+///
+/// This allows for decoding and executing scripts without allocating, as done by
+/// [`SliceCodec`][SliceCodec]. A [`Machine`][Machine] can operate on `ValueRef`s just like on any
+/// other type of values, as long as its operator system does.
+///
+/// Comparison follows the very same rules as the `PartialEq` implementation for [`Value`][Value].
+///
+/// # Examples
+///
+/// ```rust
+/// use scriptful::core::value::{Value, ValueRef};
+///
+/// let bytes = b"Hello, World!";
+/// let value = ValueRef::String(core::str::from_utf8(&bytes[..5]).unwrap());
+///
+/// assert_eq!(value, ValueRef::String("Hello"));
+/// assert_eq!(ValueRef::Integer(1), ValueRef::Float(1.));
+/// assert_eq!(Value::from(value), Value::String("Hello".into()));
+/// ```
+///
+/// [Value]: enum.Value.html
+/// [SliceCodec]: ../../codecs/simple/struct.SliceCodec.html
+/// [Machine]: ../machine/struct.Machine.html
+#[derive(Clone, Copy, Debug)]
+pub enum ValueRef<'a> {
+    /// Same as [`Value::Boolean`][Value::Boolean].
+    ///
+    /// [Value::Boolean]: enum.Value.html#variant.Boolean
+    Boolean(bool),
+    /// Same as [`Value::Decimal`][Value::Decimal].
+    ///
+    /// [Value::Decimal]: enum.Value.html#variant.Decimal
+    Decimal(decimal::Decimal),
+    /// Same as [`Value::Float`][Value::Float].
+    ///
+    /// [Value::Float]: enum.Value.html#variant.Float
+    Float(f64),
+    /// Same as [`Value::Integer`][Value::Integer].
+    ///
+    /// [Value::Integer]: enum.Value.html#variant.Integer
+    Integer(i128),
+    /// A borrowed string of characters.
+    String(&'a str),
+    /// Same as [`Value::Null`][Value::Null].
+    ///
+    /// [Value::Null]: enum.Value.html#variant.Null
+    Null,
+    /// A borrowed sequence of bytes.
+    Bytes(&'a [u8]),
+    /// Same as [`Value::Timestamp`][Value::Timestamp].
+    ///
+    /// [Value::Timestamp]: enum.Value.html#variant.Timestamp
+    Timestamp(i64),
+    /// A signed integer of arbitrary precision, as its little endian two's complement bytes, i.e.
+    /// as taken by `BigInt::from_signed_bytes_le`.
+    #[cfg(feature = "bigint")]
+    BigInt(&'a [u8]),
+}
+
+impl ValueRef<'_> {
+    /// The name of the variant of this value, in lowercase, e.g. `"integer"`, same as
+    /// [`Value::type_name`][type_name].
+    ///
+    /// [type_name]: enum.Value.html#method.type_name
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ValueRef::Boolean(_) => "boolean",
+            ValueRef::Decimal(_) => "decimal",
+            ValueRef::Float(_) => "float",
+            ValueRef::Integer(_) => "integer",
+            ValueRef::String(_) => "string",
+            ValueRef::Null => "null",
+            ValueRef::Bytes(_) => "bytes",
+            ValueRef::Timestamp(_) => "timestamp",
+            #[cfg(feature = "bigint")]
+            ValueRef::BigInt(_) => "bigint",
+        }
+    }
+
+    /// Tells whether this value should be considered `true` when used as a condition, following
+    /// the same rules as [`Value::is_truthy`][is_truthy].
+    ///
+    /// [is_truthy]: enum.Value.html#method.is_truthy
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            ValueRef::String(x) => !x.is_empty(),
+            ValueRef::Bytes(x) => bytes_are_truthy(x),
+            #[cfg(feature = "bigint")]
+            ValueRef::BigInt(x) => x.iter().any(|byte| *byte != 0),
+            other => Value::from(*other).is_truthy(),
+        }
+    }
+}
+
+impl From<ValueRef<'_>> for Value {
+    fn from(input: ValueRef<'_>) -> Self {
+        match input {
+            ValueRef::Boolean(x) => Value::Boolean(x),
+            ValueRef::Decimal(x) => Value::Decimal(x),
+            ValueRef::Float(x) => Value::Float(x),
+            ValueRef::Integer(x) => Value::Integer(x),
+            ValueRef::String(x) => Value::String(x.into()),
+            ValueRef::Null => Value::Null,
+            ValueRef::Bytes(x) => Value::Bytes(x.into()),
+            ValueRef::Timestamp(x) => Value::Timestamp(x),
+            #[cfg(feature = "bigint")]
+            ValueRef::BigInt(x) => Value::BigInt(num_bigint::BigInt::from_signed_bytes_le(x)),
+        }
+    }
+}
+
+/// Compares values just like the `PartialEq` implementation for [`Value`][Value], without
+/// allocating unless big integers are involved.
+///
+/// [Value]: enum.Value.html
+impl core::cmp::PartialEq for ValueRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        use ValueRef::*;
+        match (self, other) {
+            (String(a), String(b)) => a == b,
+            (Bytes(a), Bytes(b)) => a == b,
+            (String(_) | Bytes(_), _) | (_, String(_) | Bytes(_)) => false,
+            (a, b) => Value::from(*a) == Value::from(*b),
+        }
+    }
+}
+
 // The `Float` variant is untested because floating point numbers cannot be trivially nor accurately
 // compared.
 #[cfg(test)]
 mod tests {
-    use crate::core::value::Value::*;
+    use alloc::collections::BTreeSet;
+
+    use crate::core::decimal;
+    use crate::core::value::{CanonicalValue, Value::*};
+
+    fn dec(mantissa: i128, scale: u8) -> decimal::Decimal {
+        decimal::Decimal::new(mantissa, scale).unwrap()
+    }
 
     #[test]
     fn test_negation() {
-        assert_eq!(!Boolean(true), Boolean(false));
-        assert_eq!(!Boolean(false), Boolean(true));
-        assert_eq!(!Float(0.), Float(0.));
-        assert_eq!(!Float(1.1), Float(-1.1));
-        assert_eq!(!Integer(0), Integer(0));
-        assert_eq!(!Integer(1), Integer(-1));
+        assert_eq!(!Boolean(true), Ok(Boolean(false)));
+        assert_eq!(!Boolean(false), Ok(Boolean(true)));
+        assert_eq!(!Float(0.), Ok(Float(0.)));
+        assert_eq!(!Float(1.1), Ok(Float(-1.1)));
+        assert_eq!(!Integer(0), Ok(Integer(0)));
+        assert_eq!(!Integer(1), Ok(Integer(-1)));
+        assert_eq!(!Null, Ok(Null));
+        assert_eq!(!Decimal(dec(11, 1)), Ok(Decimal(dec(-11, 1))));
     }
 
     #[test]
     fn test_addition() {
-        assert_eq!(Boolean(false) + Boolean(false), Boolean(false));
-        assert_eq!(Boolean(false) + Boolean(true), Boolean(true));
-        assert_eq!(Boolean(true) + Boolean(false), Boolean(true));
-        assert_eq!(Boolean(true) + Boolean(true), Boolean(true));
-        assert_eq!(Float(1.1) + Float(2.2), Float(3.3));
-        assert_eq!(Float(1.1) + Float(-2.2), Float(-1.1));
-        assert_eq!(Float(1.1) + Integer(2), Float(3.1));
-        assert_eq!(Float(1.1) + Integer(-2), Float(-0.9));
-        assert_eq!(Integer(1) + Integer(2), Integer(3));
-        assert_eq!(Integer(1) + Integer(-2), Integer(-1));
-        assert_eq!(Integer(1) + Float(2.2), Float(3.2));
-        assert_eq!(Integer(1) + Float(-2.1), Float(-1.1));
+        assert_eq!(Boolean(false) + Boolean(false), Ok(Boolean(false)));
+        assert_eq!(Boolean(false) + Boolean(true), Ok(Boolean(true)));
+        assert_eq!(Boolean(true) + Boolean(false), Ok(Boolean(true)));
+        assert_eq!(Boolean(true) + Boolean(true), Ok(Boolean(true)));
+        assert_eq!(Float(1.1) + Float(2.2), Ok(Float(3.3)));
+        assert_eq!(Float(1.1) + Float(-2.2), Ok(Float(-1.1)));
+        assert_eq!(Float(1.1) + Integer(2), Ok(Float(3.1)));
+        assert_eq!(Float(1.1) + Integer(-2), Ok(Float(-0.9)));
+        assert_eq!(Integer(1) + Integer(2), Ok(Integer(3)));
+        assert_eq!(Integer(1) + Integer(-2), Ok(Integer(-1)));
+        assert_eq!(Integer(1) + Float(2.2), Ok(Float(3.2)));
+        assert_eq!(Integer(1) + Float(-2.1), Ok(Float(-1.1)));
+        assert_eq!(Null + Null, Ok(Null));
+        assert_eq!(Null + Integer(1), Ok(Null));
+        assert_eq!(Float(1.1) + Null, Ok(Null));
+        assert_eq!(Null + String("foo".into()), Ok(Null));
+        assert_eq!(
+            Decimal(dec(1, 1)) + Decimal(dec(2, 1)),
+            Ok(Decimal(dec(3, 1)))
+        );
+        assert_eq!(Decimal(dec(1, 1)) + Integer(2), Ok(Decimal(dec(21, 1))));
+        assert_eq!(Integer(2) + Decimal(dec(1, 1)), Ok(Decimal(dec(21, 1))));
+        assert_eq!(Decimal(dec(1, 1)) + Float(2.2), Ok(Float(2.3)));
     }
 
     #[test]
     fn test_subtraction() {
-        assert_eq!(Boolean(false) - Boolean(false), Boolean(true));
-        assert_eq!(Boolean(false) - Boolean(true), Boolean(false));
-        assert_eq!(Boolean(true) - Boolean(false), Boolean(true));
-        assert_eq!(Boolean(true) - Boolean(true), Boolean(true));
-        assert_eq!(Float(1.1) - Float(2.2), Float(-1.1));
-        assert_eq!(Float(1.1) - Float(-2.2), Float(3.3));
-        assert_eq!(Float(1.1) - Integer(2), Float(-0.9));
-        assert_eq!(Float(1.1) - Integer(-2), Float(3.1));
-        assert_eq!(Integer(1) - Integer(2), Integer(-1));
-        assert_eq!(Integer(1) - Integer(-2), Integer(3));
-        assert_eq!(Integer(1) - Float(2.2), Float(-1.2));
-        assert_eq!(Integer(1) - Float(-2.2), Float(3.2));
+        assert_eq!(Boolean(false) - Boolean(false), Ok(Boolean(true)));
+        assert_eq!(Boolean(false) - Boolean(true), Ok(Boolean(false)));
+        assert_eq!(Boolean(true) - Boolean(false), Ok(Boolean(true)));
+        assert_eq!(Boolean(true) - Boolean(true), Ok(Boolean(true)));
+        assert_eq!(Float(1.1) - Float(2.2), Ok(Float(-1.1)));
+        assert_eq!(Float(1.1) - Float(-2.2), Ok(Float(3.3)));
+        assert_eq!(Float(1.1) - Integer(2), Ok(Float(-0.9)));
+        assert_eq!(Float(1.1) - Integer(-2), Ok(Float(3.1)));
+        assert_eq!(Integer(1) - Integer(2), Ok(Integer(-1)));
+        assert_eq!(Integer(1) - Integer(-2), Ok(Integer(3)));
+        assert_eq!(Integer(1) - Float(2.2), Ok(Float(-1.2)));
+        assert_eq!(Integer(1) - Float(-2.2), Ok(Float(3.2)));
+        assert_eq!(Null - Integer(1), Ok(Null));
+        assert_eq!(Integer(1) - Null, Ok(Null));
+        assert_eq!(
+            Decimal(dec(1, 1)) - Decimal(dec(3, 1)),
+            Ok(Decimal(dec(-2, 1)))
+        );
+        assert_eq!(Integer(1) - Decimal(dec(1, 2)), Ok(Decimal(dec(99, 2))));
+        assert_eq!(Integer(-1) - Integer(i128::MIN), Ok(Integer(i128::MAX)));
+        assert_eq!(
+            Decimal(dec(-1, 2)) - Decimal(dec(i128::MIN, 2)),
+            Ok(Decimal(dec(i128::MAX, 2)))
+        );
     }
 
     #[test]
     fn test_multiplication() {
-        assert_eq!(Boolean(false) * Boolean(false), Boolean(false));
-        assert_eq!(Boolean(false) * Boolean(true), Boolean(false));
-        assert_eq!(Boolean(true) * Boolean(false), Boolean(false));
-        assert_eq!(Boolean(true) * Boolean(true), Boolean(true));
-        assert_eq!(Float(1.1) * Float(2.2), Float(2.42));
-        assert_eq!(Float(1.1) * Float(-2.2), Float(-2.42));
-        assert_eq!(Float(1.1) * Integer(2), Float(2.2));
-        assert_eq!(Float(1.1) * Integer(-2), Float(-2.2));
-        assert_eq!(Integer(1) * Integer(2), Integer(2));
-        assert_eq!(Integer(1) * Integer(-2), Integer(-2));
-        assert_eq!(Integer(1) * Float(2.2), Float(2.2));
-        assert_eq!(Integer(1) * Float(-2.2), Float(-2.2));
+        assert_eq!(Boolean(false) * Boolean(false), Ok(Boolean(false)));
+        assert_eq!(Boolean(false) * Boolean(true), Ok(Boolean(false)));
+        assert_eq!(Boolean(true) * Boolean(false), Ok(Boolean(false)));
+        assert_eq!(Boolean(true) * Boolean(true), Ok(Boolean(true)));
+        assert_eq!(Float(1.1) * Float(2.2), Ok(Float(2.42)));
+        assert_eq!(Float(1.1) * Float(-2.2), Ok(Float(-2.42)));
+        assert_eq!(Float(1.1) * Integer(2), Ok(Float(2.2)));
+        assert_eq!(Float(1.1) * Integer(-2), Ok(Float(-2.2)));
+        assert_eq!(Integer(1) * Integer(2), Ok(Integer(2)));
+        assert_eq!(Integer(1) * Integer(-2), Ok(Integer(-2)));
+        assert_eq!(Integer(1) * Float(2.2), Ok(Float(2.2)));
+        assert_eq!(Integer(1) * Float(-2.2), Ok(Float(-2.2)));
+        assert_eq!(Null * Null, Ok(Null));
+        assert_eq!(Null * Boolean(true), Ok(Null));
+        assert_eq!(Integer(2) * Null, Ok(Null));
+        assert_eq!(
+            Decimal(dec(11, 1)) * Decimal(dec(22, 1)),
+            Ok(Decimal(dec(242, 2)))
+        );
+        assert_eq!(Decimal(dec(11, 1)) * Integer(-2), Ok(Decimal(dec(-22, 1))));
+        assert_eq!(Float(2.) * Decimal(dec(11, 1)), Ok(Float(2.2)));
+    }
+
+    #[test]
+    fn test_arithmetic_errors() {
+        use crate::op_systems::OperatorError::{Overflow, UnsupportedOperands};
+
+        assert_eq!(
+            !String("foo".into()),
+            Err(UnsupportedOperands {
+                first: "string",
+                second: None
+            })
+        );
+        assert_eq!(
+            String("foo".into()) + Integer(1),
+            Err(UnsupportedOperands {
+                first: "string",
+                second: Some("integer")
+            })
+        );
+        assert_eq!(
+            Integer(1) - String("foo".into()),
+            Err(UnsupportedOperands {
+                first: "integer",
+                second: Some("string")
+            })
+        );
+        assert_eq!(
+            Boolean(true) * Float(1.),
+            Err(UnsupportedOperands {
+                first: "boolean",
+                second: Some("float")
+            })
+        );
+        assert_eq!(!Integer(i128::MIN), Err(Overflow));
+        assert_eq!(Integer(i128::MAX) + Integer(1), Err(Overflow));
+        assert_eq!(Integer(i128::MIN) - Integer(1), Err(Overflow));
+        assert_eq!(Integer(i128::MAX) * Integer(2), Err(Overflow));
+        assert_eq!(!Decimal(dec(i128::MIN, 2)), Err(Overflow));
+        assert_eq!(Decimal(dec(i128::MAX, 2)) + Integer(1), Err(Overflow));
+        assert_eq!(Integer(2) * Decimal(dec(i128::MAX, 2)), Err(Overflow));
     }
 
     #[test]
     fn test_comparison() {
-        assert_eq!(Boolean(false) == Boolean(false), true);
-        assert_eq!(Boolean(false) == Boolean(true), false);
-        assert_eq!(Boolean(true) == Boolean(false), false);
-        assert_eq!(Boolean(true) == Boolean(true), true);
-        assert_eq!(Float(1.1) == Float(1.1), true);
-        assert_eq!(Float(1.1) == Float(2.2), false);
-        assert_eq!(Float(-1.1) == Float(-1.1), true);
-        assert_eq!(Float(-1.1) == Float(-2.2), false);
-        assert_eq!(Float(1.1) == Float(-1.1), false);
-        assert_eq!(Float(1.) == Integer(1), true);
-        assert_eq!(Float(-1.) == Integer(-1), true);
-        assert_eq!(Integer(1) == Integer(1), true);
-        assert_eq!(Integer(1) == Integer(2), false);
-        assert_eq!(Integer(-1) == Integer(-1), true);
-        assert_eq!(Integer(-1) == Integer(-2), false);
-        assert_eq!(Integer(1) == Integer(-1), false);
-        assert_eq!(Integer(1) == Float(1.), true);
-        assert_eq!(Integer(-1) == Float(-1.), true);
+        assert!(Boolean(false) == Boolean(false));
+        assert!(!(Boolean(false) == Boolean(true)));
+        assert!(!(Boolean(true) == Boolean(false)));
+        assert!(Boolean(true) == Boolean(true));
+        assert!(Float(1.1) == Float(1.1));
+        assert!(!(Float(1.1) == Float(2.2)));
+        assert!(Float(-1.1) == Float(-1.1));
+        assert!(!(Float(-1.1) == Float(-2.2)));
+        assert!(!(Float(1.1) == Float(-1.1)));
+        assert!(Float(1.) == Integer(1));
+        assert!(Float(-1.) == Integer(-1));
+        assert!(Integer(1) == Integer(1));
+        assert!(!(Integer(1) == Integer(2)));
+        assert!(Integer(-1) == Integer(-1));
+        assert!(!(Integer(-1) == Integer(-2)));
+        assert!(!(Integer(1) == Integer(-1)));
+        assert!(Integer(1) == Float(1.));
+        assert!(Integer(-1) == Float(-1.));
+        assert!(Null == Null);
+        assert!(!(Null == Boolean(false)));
+        assert!(!(Null == Integer(0)));
+        assert!(!(String("".into()) == Null));
+        assert!(Decimal(dec(10, 1)) == Decimal(dec(100, 2)));
+        assert!(Decimal(dec(10, 1)) == Integer(1));
+        assert!(!(Integer(1) == Decimal(dec(11, 1))));
+        assert!(Decimal(dec(11, 1)) == Float(1.1));
+        assert!(!(Decimal(dec(1, 0)) == Boolean(true)));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint() {
+        use crate::core::value::BigInt as Big;
+
+        let max = || BigInt(Big::from(i128::MAX));
+
+        assert_eq!(!max(), Ok(BigInt(-Big::from(i128::MAX))));
+        assert_eq!(max() + Integer(1), Ok(BigInt(Big::from(i128::MAX) + 1)));
+        assert_eq!(Integer(1) + max(), Ok(BigInt(Big::from(i128::MAX) + 1)));
+        assert_eq!(max() - max(), Ok(Integer(0)));
+        assert_eq!(
+            max() * max(),
+            Ok(BigInt(Big::from(i128::MAX) * Big::from(i128::MAX)))
+        );
+        assert_eq!(BigInt(Big::from(2)) * Float(1.5), Ok(Float(3.)));
+        assert!(BigInt(Big::from(2)) == Float(2.));
+        assert!(BigInt(Big::from(2)) == Integer(2));
+        assert!(!(Integer(3) == BigInt(Big::from(2))));
+        assert_eq!(max() + Null, Ok(Null));
+        assert!(BigInt(Big::from(1)) < BigInt(Big::from(2)));
+    }
+
+    fn hash_of(value: &CanonicalValue) -> u64 {
+        use core::hash::{Hash, Hasher};
+
+        // A simple FNV-1a hasher, as `no_std` provides none.
+        struct Fnv(u64);
+
+        impl Hasher for Fnv {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                for byte in bytes {
+                    self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100_0000_01b3);
+                }
+            }
+        }
+
+        let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+        value.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_canonical_equality() {
+        let canonical = |value| CanonicalValue(value);
+
+        assert_eq!(canonical(Float(f64::NAN)), canonical(Float(-f64::NAN)));
+        assert_ne!(canonical(Float(0.)), canonical(Float(-0.)));
+        assert_ne!(canonical(Float(1.)), canonical(Integer(1)));
+        assert_ne!(canonical(Float(1e20)), canonical(Float(1e20 + 65536.)));
+        assert_eq!(
+            canonical(Decimal(dec(10, 1))),
+            canonical(Decimal(dec(1, 0)))
+        );
+        assert_eq!(canonical(Null), canonical(Null));
+        assert_ne!(canonical(Null), canonical(Boolean(false)));
+
+        assert_eq!(
+            hash_of(&canonical(Float(f64::NAN))),
+            hash_of(&canonical(Float(-f64::NAN)))
+        );
+        assert_eq!(
+            hash_of(&canonical(Decimal(dec(10, 1)))),
+            hash_of(&canonical(Decimal(dec(1, 0))))
+        );
+        assert_ne!(
+            hash_of(&canonical(Integer(1))),
+            hash_of(&canonical(Float(1.)))
+        );
+    }
+
+    #[test]
+    fn test_canonical_ordering() {
+        let set: BTreeSet<CanonicalValue> = [
+            String("a".into()),
+            Float(f64::NAN),
+            Float(-0.),
+            Float(0.),
+            Float(f64::NEG_INFINITY),
+            Decimal(dec(1, 0)),
+            Integer(2),
+            Integer(-2),
+            Boolean(true),
+            Null,
+            Integer(2),
+            Decimal(dec(100, 2)),
+        ]
+        .into_iter()
+        .map(CanonicalValue::from)
+        .collect();
+
+        let sorted: alloc::vec::Vec<_> = set.into_iter().map(CanonicalValue::into_inner).collect();
+        let expected = [
+            Null,
+            Boolean(true),
+            Integer(-2),
+            Integer(2),
+            Decimal(dec(1, 0)),
+            Float(f64::NEG_INFINITY),
+            Float(-0.),
+            Float(0.),
+            Float(f64::NAN),
+            String("a".into()),
+        ];
+
+        assert_eq!(sorted.len(), expected.len());
+        for (value, expected) in sorted.iter().zip(expected.iter()) {
+            assert_eq!(
+                CanonicalValue(value.clone()),
+                CanonicalValue(expected.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn test_truthiness() {
+        assert!(Boolean(true).is_truthy());
+        assert!(!Boolean(false).is_truthy());
+        assert!(Integer(2).is_truthy());
+        assert!(!Integer(0).is_truthy());
+        assert!(Float(-0.1).is_truthy());
+        assert!(!Float(-0.).is_truthy());
+        assert!(!Float(f64::NAN).is_truthy());
+        assert!(Decimal(dec(1, 38)).is_truthy());
+        assert!(!Decimal(dec(0, 3)).is_truthy());
+        assert!(String("0".into()).is_truthy());
+        assert!(!String("".into()).is_truthy());
+        assert!(!Null.is_truthy());
+        assert!(Bytes([0, 1].into()).is_truthy());
+        assert!(Bytes([1, 0].into()).is_truthy());
+        assert!(Bytes([0, 0x80, 0].into()).is_truthy());
+        assert!(!Bytes([0, 0].into()).is_truthy());
+        assert!(!Bytes([0, 0x80].into()).is_truthy());
+        assert!(!Bytes([].into()).is_truthy());
+    }
+
+    #[test]
+    fn test_conversions() {
+        use crate::core::value::{ConversionError, Value};
+
+        assert_eq!(Value::from(true), Boolean(true));
+        assert_eq!(Value::from(42u8), Integer(42));
+        assert_eq!(Value::from(-42i64), Integer(-42));
+        assert_eq!(Value::from(1.5), Float(1.5));
+        assert_eq!(Value::from("foo"), String("foo".into()));
+        assert_eq!(Value::from(None::<i32>), Null);
+        assert_eq!(Value::from(Some(1)), Integer(1));
+
+        assert_eq!(String("42".into()).to_integer(), Ok(Integer(42)));
+        assert_eq!(Float(-1.9).to_integer(), Ok(Integer(-1)));
+        assert_eq!(Decimal(dec(-19, 1)).to_integer(), Ok(Integer(-1)));
+        assert_eq!(Boolean(true).to_integer(), Ok(Integer(1)));
+        assert_eq!(
+            String("4.2".into()).to_integer(),
+            Err(ConversionError {
+                from: "string",
+                into: "integer"
+            })
+        );
+        assert!(Float(f64::NAN).to_integer().is_err());
+        assert!(Float(f64::INFINITY).to_integer().is_err());
+        assert!(Float(1e39).to_integer().is_err());
+        assert!(Null.to_integer().is_err());
+
+        assert_eq!(Integer(42).to_float(), Ok(Float(42.)));
+        assert_eq!(String("-4.2".into()).to_float(), Ok(Float(-4.2)));
+        assert_eq!(Decimal(dec(15, 1)).to_float(), Ok(Float(1.5)));
+        assert!(String("foo".into()).to_float().is_err());
+
+        assert_eq!(Integer(0).to_boolean(), Boolean(false));
+        assert_eq!(Integer(-7).to_string_value(), String("-7".into()));
+        assert_eq!(Float(1.5).to_string_value(), String("1.5".into()));
+        assert_eq!(
+            Decimal(dec(150, 2)).to_string_value(),
+            String("1.50".into())
+        );
+        assert_eq!(Null.to_string_value(), String("null".into()));
+        assert_eq!(
+            Bytes([0, 255].into()).to_string_value(),
+            String("0x00ff".into())
+        );
+        assert!(Bytes([1].into()).to_integer().is_err());
+        assert_eq!(Value::from(&[1u8, 2][..]), Bytes([1, 2].into()));
+        assert_eq!(
+            alloc::vec::Vec::try_from(Bytes([1, 2].into())),
+            Ok([1u8, 2].into())
+        );
+
+        assert_eq!(i128::try_from(String("7".into())), Ok(7));
+        assert_eq!(bool::try_from(Boolean(true)), Ok(true));
+        assert!(bool::try_from(Integer(1)).is_err());
+        assert_eq!(
+            alloc::string::String::try_from(String("foo".into())),
+            Ok("foo".into())
+        );
+    }
+
+    #[test]
+    fn test_timestamp_display() {
+        use alloc::string::ToString;
+
+        for (seconds, expected) in [
+            (0, "1970-01-01T00:00:00Z"),
+            (1_231_006_505, "2009-01-03T18:15:05Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (-1, "1969-12-31T23:59:59Z"),
+            (253_402_300_799, "9999-12-31T23:59:59Z"),
+            (-62_198_755_200, "-0001-01-01T00:00:00Z"),
+            (-93_692_592_000, "-0999-01-01T00:00:00Z"),
+        ] {
+            assert_eq!(Timestamp(seconds).to_string(), expected);
+        }
+
+        assert_eq!(Timestamp(7).to_integer(), Ok(Integer(7)));
+        assert!(Timestamp(0).is_truthy());
+    }
+
+    #[test]
+    fn test_value_ref() {
+        use crate::core::value::{Value, ValueRef};
+
+        let values = [
+            ValueRef::Boolean(false),
+            ValueRef::Decimal(dec(-1234, 2)),
+            ValueRef::Float(0.5),
+            ValueRef::Integer(0),
+            ValueRef::String(""),
+            ValueRef::String("foo"),
+            ValueRef::Null,
+            ValueRef::Bytes(&[0, 0x80]),
+            ValueRef::Bytes(&[0xca, 0xfe]),
+            ValueRef::Timestamp(1_231_006_505),
+        ];
+
+        // Borrowed values behave just like their owned counterparts.
+        for a in values {
+            let owned = Value::from(a);
+            assert_eq!(a.type_name(), owned.type_name());
+            assert_eq!(a.is_truthy(), owned.is_truthy());
+
+            for b in values {
+                assert_eq!(a == b, owned == Value::from(b), "{:?} == {:?}", a, b);
+            }
+        }
+
+        assert_eq!(ValueRef::Integer(1), ValueRef::Float(1.));
+        assert_eq!(ValueRef::Decimal(dec(100, 2)), ValueRef::Integer(1));
+        assert_ne!(ValueRef::String("1"), ValueRef::Integer(1));
+        assert_eq!(Value::from(ValueRef::String("foo")), String("foo".into()));
+        assert_eq!(Value::from(ValueRef::Bytes(&[1, 2])), Bytes([1, 2].into()));
+
+        #[cfg(feature = "bigint")]
+        {
+            let huge = ValueRef::BigInt(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80]);
+            assert_eq!(
+                Value::from(huge),
+                BigInt(-(num_bigint::BigInt::from(1u8) << 135u8))
+            );
+            assert!(huge.is_truthy());
+            assert!(!ValueRef::BigInt(&[0, 0]).is_truthy());
+            assert_eq!(ValueRef::BigInt(&[1]), ValueRef::Integer(1));
+            assert_eq!(huge.type_name(), "bigint");
+        }
     }
 }
@@ -29,6 +29,7 @@
 //! ```rust
 //! use scriptful::prelude::*;
 //! use scriptful::core::value::Value::*;
+//! use scriptful::op_systems::OperatorError;
 //!
 //! // You can define your own operators.
 //! #[derive(Debug, PartialEq, Eq)]
@@ -39,24 +40,30 @@
 //! }
 //!
 //! // An operator system decides what to do with the stack when each operator is applied on it.
-//! fn my_operator_system(stack: &mut Stack, operator: &MyOperator) {
+//! fn my_operator_system(
+//!     stack: &mut Stack,
+//!     operator: &MyOperator,
+//!     _if_stack: &mut ConditionStack,
+//! ) -> Result<(), OperatorError> {
 //!     match operator {
 //!         MyOperator::Add => {
-//!             let a = stack.pop();
-//!             let b = stack.pop();
-//!             stack.push(a + b);
+//!             let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+//!             let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+//!             stack.push((a + b)?);
 //!         }
 //!         MyOperator::Equal => {
-//!             let a = stack.pop();
-//!             let b = stack.pop();
+//!             let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+//!             let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
 //!             stack.push(Boolean(a == b));
 //!         }
 //!         MyOperator::Sub => {
-//!             let a = stack.pop();
-//!             let b = stack.pop();
-//!             stack.push(a - b);
+//!             let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+//!             let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+//!             stack.push((a - b)?);
 //!         }
 //!     }
+//!
+//!     Ok(())
 //! }
 //!
 //! // Instantiate the machine with a reference to your operator system.
@@ -70,13 +77,13 @@
 //! ]));
 //!
 //! // The result should unsurprisingly be 3.
-//! assert_eq!(result, Some(&Integer(3)));
+//! assert_eq!(result, Ok(Some(&Integer(3))));
 //! ```
 //!
 //! # Known limitations
 //!
 //! - _Beware of unwraps!_ This is a proof-of-concept and it is modelled to panic upon errors.
-//! Making the library safe for production usage is in the near horizon though.
+//!   Making the library safe for production usage is in the near horizon though.
 //!
 //! # License
 //!
@@ -117,6 +124,8 @@ pub mod codecs;
 pub mod core;
 /// Some ready-to-use operator systems that may be useful for _someone_, _somewhere_, _somewhen_.
 pub mod op_systems;
+#[cfg(test)]
+pub(crate) mod test_utils;
 
 /// Re-exports the most frequently used parts of this library so that they can be used more conveniently.
 pub mod prelude {
@@ -0,0 +1,1273 @@
+//! An operator system that mimics the semantics of [Bitcoin Script][BitcoinScript].
+//!
+//! All the values handled by this operator system are expected to be `Bytes`, as Bitcoin Script
+//! has no other data type. Numbers are represented with the same variable length, little-endian,
+//! sign-magnitude encoding used by Bitcoin (see [`encode_script_num`][encode_script_num]), and
+//! arithmetic operators only accept operands of up to 4 bytes.
+//!
+//! Constants and data pushes (`OP_0`, `OP_PUSHDATA*`, `OP_1NEGATE`, `OP_1` to `OP_16`) are not
+//! operators but plain `Bytes` values. The [`bitcoin` codec][codec] takes care of translating them
+//! from and into raw script bytes.
+//!
+//! Some of the Bitcoin consensus rules are not related to any operator in particular, but to the
+//! evaluation of a whole script (e.g. the maximum size of the stack, or the requirement that all
+//! conditionals are closed). Those are enforced by [`eval_script`][eval_script],
+//! [`verify_script`][verify_script] and [`verify_raw_script`][verify_raw_script], which are the
+//! preferred way to run this operator system.
+//!
+//! Signatures are checked through a [`SignatureChecker`][SignatureChecker] provided by the host
+//! application, given that the message being signed (the spending transaction) is unknown to the
+//! script. `OP_CHECKLOCKTIMEVERIFY` and `OP_CHECKSEQUENCEVERIFY` behave as the `OP_NOP2` and
+//! `OP_NOP3` they replaced, for the same reason.
+//!
+//! This module is only available when the `bitcoin` feature is enabled.
+//!
+//! [BitcoinScript]: https://en.bitcoin.it/wiki/Script
+//! [encode_script_num]: fn.encode_script_num.html
+//! [codec]: ../../codecs/bitcoin/index.html
+//! [eval_script]: fn.eval_script.html
+//! [verify_script]: fn.verify_script.html
+//! [verify_raw_script]: fn.verify_raw_script.html
+//! [SignatureChecker]: trait.SignatureChecker.html
+
+use alloc::vec::Vec;
+use sha2::Digest;
+
+use crate::codecs::bitcoin::from_slice;
+use crate::core::value::Value;
+use crate::core::ScriptRef;
+use crate::prelude::*;
+
+/// The maximum size in bytes of a value that can be pushed into the stack.
+pub const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+/// The maximum number of operators (other than constants and data pushes) in a single script.
+pub const MAX_OPS_PER_SCRIPT: usize = 201;
+/// The maximum number of public keys that a `CheckMultiSig` operator can take.
+pub const MAX_PUBKEYS_PER_MULTISIG: i64 = 20;
+/// The maximum size in bytes of a script.
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+/// The maximum number of values in the `main` and `alt` sub-stacks combined.
+pub const MAX_STACK_SIZE: usize = 1_000;
+
+macro_rules! bitcoin_operators {
+    ($($(#[$doc:meta])* $variant:ident = $opcode:literal, $name:literal;)*) => {
+        /// The operators in Bitcoin Script, i.e. all the opcodes other than constants and data
+        /// pushes.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+        pub enum BitcoinOperator {
+            $($(#[$doc])* $variant,)*
+            /// Any of the unassigned opcodes, from `0xba` to `0xff`. Fails the script if executed.
+            Invalid(u8),
+        }
+
+        impl BitcoinOperator {
+            /// Returns the opcode of this operator, i.e. the byte that represents it in a raw
+            /// Bitcoin script.
+            pub fn opcode(&self) -> u8 {
+                match self {
+                    $(BitcoinOperator::$variant => $opcode,)*
+                    BitcoinOperator::Invalid(opcode) => *opcode,
+                }
+            }
+
+            /// Returns the operator for an opcode, or `None` if the opcode is a constant or a
+            /// data push.
+            pub fn from_opcode(opcode: u8) -> Option<Self> {
+                match opcode {
+                    $($opcode => Some(BitcoinOperator::$variant),)*
+                    0xba..=0xff => Some(BitcoinOperator::Invalid(opcode)),
+                    _ => None,
+                }
+            }
+
+            /// Returns the name of this operator as used by Bitcoin Core, e.g. `OP_CHECKSIG`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(BitcoinOperator::$variant => $name,)*
+                    BitcoinOperator::Invalid(_) => "OP_UNKNOWN",
+                }
+            }
+
+            /// Returns the operator with a given name, which may or may not be prefixed with
+            /// `OP_`, e.g. both `OP_CHECKSIG` and `CHECKSIG` are accepted.
+            pub fn from_name(name: &str) -> Option<Self> {
+                let name = name.strip_prefix("OP_").unwrap_or(name);
+
+                match name {
+                    $(_ if name == &$name[3..] => Some(BitcoinOperator::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+bitcoin_operators! {
+    /// Fails the script if executed (`OP_RESERVED`).
+    Reserved = 0x50, "OP_RESERVED";
+    /// Does nothing (`OP_NOP`).
+    Nop = 0x61, "OP_NOP";
+    /// Fails the script if executed (`OP_VER`).
+    Ver = 0x62, "OP_VER";
+    /// Executes the following operators if the topmost value is truthy (`OP_IF`).
+    If = 0x63, "OP_IF";
+    /// Executes the following operators if the topmost value is not truthy (`OP_NOTIF`).
+    NotIf = 0x64, "OP_NOTIF";
+    /// Fails the script, even if not executed (`OP_VERIF`).
+    VerIf = 0x65, "OP_VERIF";
+    /// Fails the script, even if not executed (`OP_VERNOTIF`).
+    VerNotIf = 0x66, "OP_VERNOTIF";
+    /// Toggles whether the following operators are executed (`OP_ELSE`).
+    Else = 0x67, "OP_ELSE";
+    /// Closes an `If` or `NotIf` block (`OP_ENDIF`).
+    EndIf = 0x68, "OP_ENDIF";
+    /// Fails the script unless the topmost value is truthy, which is removed (`OP_VERIFY`).
+    Verify = 0x69, "OP_VERIFY";
+    /// Fails the script (`OP_RETURN`).
+    Return = 0x6a, "OP_RETURN";
+    /// `( x -- )`, moving `x` into the `alt` sub-stack (`OP_TOALTSTACK`).
+    ToAltStack = 0x6b, "OP_TOALTSTACK";
+    /// `( -- x )`, moving `x` from the `alt` sub-stack (`OP_FROMALTSTACK`).
+    FromAltStack = 0x6c, "OP_FROMALTSTACK";
+    /// `( x1 x2 -- )` (`OP_2DROP`).
+    TwoDrop = 0x6d, "OP_2DROP";
+    /// `( x1 x2 -- x1 x2 x1 x2 )` (`OP_2DUP`).
+    TwoDup = 0x6e, "OP_2DUP";
+    /// `( x1 x2 x3 -- x1 x2 x3 x1 x2 x3 )` (`OP_3DUP`).
+    ThreeDup = 0x6f, "OP_3DUP";
+    /// `( x1 x2 x3 x4 -- x1 x2 x3 x4 x1 x2 )` (`OP_2OVER`).
+    TwoOver = 0x70, "OP_2OVER";
+    /// `( x1 x2 x3 x4 x5 x6 -- x3 x4 x5 x6 x1 x2 )` (`OP_2ROT`).
+    TwoRot = 0x71, "OP_2ROT";
+    /// `( x1 x2 x3 x4 -- x3 x4 x1 x2 )` (`OP_2SWAP`).
+    TwoSwap = 0x72, "OP_2SWAP";
+    /// `( x -- x x )` if `x` is truthy, `( x -- x )` otherwise (`OP_IFDUP`).
+    IfDup = 0x73, "OP_IFDUP";
+    /// `( -- length )` (`OP_DEPTH`).
+    Depth = 0x74, "OP_DEPTH";
+    /// `( x -- )` (`OP_DROP`).
+    Drop = 0x75, "OP_DROP";
+    /// `( x -- x x )` (`OP_DUP`).
+    Dup = 0x76, "OP_DUP";
+    /// `( x1 x2 -- x2 )` (`OP_NIP`).
+    Nip = 0x77, "OP_NIP";
+    /// `( x1 x2 -- x1 x2 x1 )` (`OP_OVER`).
+    Over = 0x78, "OP_OVER";
+    /// `( xn ... x0 n -- xn ... x0 xn )` (`OP_PICK`).
+    Pick = 0x79, "OP_PICK";
+    /// `( xn ... x0 n -- ... x0 xn )` (`OP_ROLL`).
+    Roll = 0x7a, "OP_ROLL";
+    /// `( x1 x2 x3 -- x2 x3 x1 )` (`OP_ROT`).
+    Rot = 0x7b, "OP_ROT";
+    /// `( x1 x2 -- x2 x1 )` (`OP_SWAP`).
+    Swap = 0x7c, "OP_SWAP";
+    /// `( x1 x2 -- x2 x1 x2 )` (`OP_TUCK`).
+    Tuck = 0x7d, "OP_TUCK";
+    /// Disabled (`OP_CAT`).
+    Cat = 0x7e, "OP_CAT";
+    /// Disabled (`OP_SUBSTR`).
+    Substr = 0x7f, "OP_SUBSTR";
+    /// Disabled (`OP_LEFT`).
+    Left = 0x80, "OP_LEFT";
+    /// Disabled (`OP_RIGHT`).
+    Right = 0x81, "OP_RIGHT";
+    /// `( x -- x size )` (`OP_SIZE`).
+    Size = 0x82, "OP_SIZE";
+    /// Disabled (`OP_INVERT`).
+    Invert = 0x83, "OP_INVERT";
+    /// Disabled (`OP_AND`).
+    And = 0x84, "OP_AND";
+    /// Disabled (`OP_OR`).
+    Or = 0x85, "OP_OR";
+    /// Disabled (`OP_XOR`).
+    Xor = 0x86, "OP_XOR";
+    /// `( x1 x2 -- x1 == x2 )`, comparing bytes (`OP_EQUAL`).
+    Equal = 0x87, "OP_EQUAL";
+    /// Same as `Equal` followed by `Verify` (`OP_EQUALVERIFY`).
+    EqualVerify = 0x88, "OP_EQUALVERIFY";
+    /// Fails the script if executed (`OP_RESERVED1`).
+    Reserved1 = 0x89, "OP_RESERVED1";
+    /// Fails the script if executed (`OP_RESERVED2`).
+    Reserved2 = 0x8a, "OP_RESERVED2";
+    /// `( a -- a + 1 )` (`OP_1ADD`).
+    OneAdd = 0x8b, "OP_1ADD";
+    /// `( a -- a - 1 )` (`OP_1SUB`).
+    OneSub = 0x8c, "OP_1SUB";
+    /// Disabled (`OP_2MUL`).
+    TwoMul = 0x8d, "OP_2MUL";
+    /// Disabled (`OP_2DIV`).
+    TwoDiv = 0x8e, "OP_2DIV";
+    /// `( a -- -a )` (`OP_NEGATE`).
+    Negate = 0x8f, "OP_NEGATE";
+    /// `( a -- |a| )` (`OP_ABS`).
+    Abs = 0x90, "OP_ABS";
+    /// `( a -- a == 0 )` (`OP_NOT`).
+    Not = 0x91, "OP_NOT";
+    /// `( a -- a != 0 )` (`OP_0NOTEQUAL`).
+    ZeroNotEqual = 0x92, "OP_0NOTEQUAL";
+    /// `( a b -- a + b )` (`OP_ADD`).
+    Add = 0x93, "OP_ADD";
+    /// `( a b -- a - b )` (`OP_SUB`).
+    Sub = 0x94, "OP_SUB";
+    /// Disabled (`OP_MUL`).
+    Mul = 0x95, "OP_MUL";
+    /// Disabled (`OP_DIV`).
+    Div = 0x96, "OP_DIV";
+    /// Disabled (`OP_MOD`).
+    Mod = 0x97, "OP_MOD";
+    /// Disabled (`OP_LSHIFT`).
+    LShift = 0x98, "OP_LSHIFT";
+    /// Disabled (`OP_RSHIFT`).
+    RShift = 0x99, "OP_RSHIFT";
+    /// `( a b -- a != 0 && b != 0 )` (`OP_BOOLAND`).
+    BoolAnd = 0x9a, "OP_BOOLAND";
+    /// `( a b -- a != 0 || b != 0 )` (`OP_BOOLOR`).
+    BoolOr = 0x9b, "OP_BOOLOR";
+    /// `( a b -- a == b )`, comparing numbers (`OP_NUMEQUAL`).
+    NumEqual = 0x9c, "OP_NUMEQUAL";
+    /// Same as `NumEqual` followed by `Verify` (`OP_NUMEQUALVERIFY`).
+    NumEqualVerify = 0x9d, "OP_NUMEQUALVERIFY";
+    /// `( a b -- a != b )` (`OP_NUMNOTEQUAL`).
+    NumNotEqual = 0x9e, "OP_NUMNOTEQUAL";
+    /// `( a b -- a < b )` (`OP_LESSTHAN`).
+    LessThan = 0x9f, "OP_LESSTHAN";
+    /// `( a b -- a > b )` (`OP_GREATERTHAN`).
+    GreaterThan = 0xa0, "OP_GREATERTHAN";
+    /// `( a b -- a <= b )` (`OP_LESSTHANOREQUAL`).
+    LessThanOrEqual = 0xa1, "OP_LESSTHANOREQUAL";
+    /// `( a b -- a >= b )` (`OP_GREATERTHANOREQUAL`).
+    GreaterThanOrEqual = 0xa2, "OP_GREATERTHANOREQUAL";
+    /// `( a b -- min(a, b) )` (`OP_MIN`).
+    Min = 0xa3, "OP_MIN";
+    /// `( a b -- max(a, b) )` (`OP_MAX`).
+    Max = 0xa4, "OP_MAX";
+    /// `( x min max -- min <= x < max )` (`OP_WITHIN`).
+    Within = 0xa5, "OP_WITHIN";
+    /// `( x -- ripemd160(x) )` (`OP_RIPEMD160`).
+    Ripemd160 = 0xa6, "OP_RIPEMD160";
+    /// `( x -- sha1(x) )` (`OP_SHA1`).
+    Sha1 = 0xa7, "OP_SHA1";
+    /// `( x -- sha256(x) )` (`OP_SHA256`).
+    Sha256 = 0xa8, "OP_SHA256";
+    /// `( x -- ripemd160(sha256(x)) )` (`OP_HASH160`).
+    Hash160 = 0xa9, "OP_HASH160";
+    /// `( x -- sha256(sha256(x)) )` (`OP_HASH256`).
+    Hash256 = 0xaa, "OP_HASH256";
+    /// Does nothing, as it only affects signature hashing (`OP_CODESEPARATOR`).
+    CodeSeparator = 0xab, "OP_CODESEPARATOR";
+    /// `( sig pubkey -- valid )` (`OP_CHECKSIG`).
+    CheckSig = 0xac, "OP_CHECKSIG";
+    /// Same as `CheckSig` followed by `Verify` (`OP_CHECKSIGVERIFY`).
+    CheckSigVerify = 0xad, "OP_CHECKSIGVERIFY";
+    /// `( dummy sig_1 ... sig_m m pubkey_1 ... pubkey_n n -- valid )` (`OP_CHECKMULTISIG`).
+    CheckMultiSig = 0xae, "OP_CHECKMULTISIG";
+    /// Same as `CheckMultiSig` followed by `Verify` (`OP_CHECKMULTISIGVERIFY`).
+    CheckMultiSigVerify = 0xaf, "OP_CHECKMULTISIGVERIFY";
+    /// Does nothing (`OP_NOP1`).
+    Nop1 = 0xb0, "OP_NOP1";
+    /// Does nothing, as it would need to know the spending transaction
+    /// (`OP_CHECKLOCKTIMEVERIFY`).
+    CheckLockTimeVerify = 0xb1, "OP_CHECKLOCKTIMEVERIFY";
+    /// Does nothing, as it would need to know the spending transaction
+    /// (`OP_CHECKSEQUENCEVERIFY`).
+    CheckSequenceVerify = 0xb2, "OP_CHECKSEQUENCEVERIFY";
+    /// Does nothing (`OP_NOP4`).
+    Nop4 = 0xb3, "OP_NOP4";
+    /// Does nothing (`OP_NOP5`).
+    Nop5 = 0xb4, "OP_NOP5";
+    /// Does nothing (`OP_NOP6`).
+    Nop6 = 0xb5, "OP_NOP6";
+    /// Does nothing (`OP_NOP7`).
+    Nop7 = 0xb6, "OP_NOP7";
+    /// Does nothing (`OP_NOP8`).
+    Nop8 = 0xb7, "OP_NOP8";
+    /// Does nothing (`OP_NOP9`).
+    Nop9 = 0xb8, "OP_NOP9";
+    /// Does nothing (`OP_NOP10`).
+    Nop10 = 0xb9, "OP_NOP10";
+}
+
+impl BitcoinOperator {
+    /// Tells whether this operator has been disabled in Bitcoin, which makes a script fail even
+    /// if the operator is not executed.
+    pub fn is_disabled(&self) -> bool {
+        use BitcoinOperator::*;
+
+        matches!(
+            self,
+            Cat | Substr
+                | Left
+                | Right
+                | Invert
+                | And
+                | Or
+                | Xor
+                | TwoMul
+                | TwoDiv
+                | Mul
+                | Div
+                | Mod
+                | LShift
+                | RShift
+        )
+    }
+}
+
+/// The errors that can make a Bitcoin script fail, named after their counterparts in Bitcoin
+/// Core.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScriptError {
+    /// An invalid operator was executed, or a disabled one was found.
+    BadOpcode,
+    /// A `CheckMultiSigVerify` operator found invalid signatures.
+    CheckMultiSigVerify,
+    /// A `CheckSigVerify` operator found an invalid signature.
+    CheckSigVerify,
+    /// A disabled operator was found, even if not executed.
+    DisabledOpcode,
+    /// An `EqualVerify` operator found two different values.
+    EqualVerify,
+    /// The script finished with an empty stack or a topmost value that is not truthy.
+    EvalFalse,
+    /// A `FromAltStack` operator found the `alt` sub-stack empty.
+    InvalidAltstackOperation,
+    /// An operator needed more values than there were in the stack, or a `Pick` or `Roll`
+    /// operator was given an out of range index.
+    InvalidStackOperation,
+    /// A `NumEqualVerify` operator found two different numbers.
+    NumEqualVerify,
+    /// The script exceeded [`MAX_OPS_PER_SCRIPT`][MAX_OPS_PER_SCRIPT].
+    ///
+    /// [MAX_OPS_PER_SCRIPT]: constant.MAX_OPS_PER_SCRIPT.html
+    OpCount,
+    /// A `Return` operator was executed.
+    OpReturn,
+    /// A `CheckMultiSig` operator was given a negative number of public keys, or more than
+    /// [`MAX_PUBKEYS_PER_MULTISIG`][MAX_PUBKEYS_PER_MULTISIG].
+    ///
+    /// [MAX_PUBKEYS_PER_MULTISIG]: constant.MAX_PUBKEYS_PER_MULTISIG.html
+    PubkeyCount,
+    /// A value exceeded [`MAX_SCRIPT_ELEMENT_SIZE`][MAX_SCRIPT_ELEMENT_SIZE].
+    ///
+    /// [MAX_SCRIPT_ELEMENT_SIZE]: constant.MAX_SCRIPT_ELEMENT_SIZE.html
+    PushSize,
+    /// A number operand was longer than 4 bytes.
+    ScriptNum,
+    /// A raw script given to [`verify_raw_script`][verify_raw_script] exceeded
+    /// [`MAX_SCRIPT_SIZE`][MAX_SCRIPT_SIZE].
+    ///
+    /// [verify_raw_script]: fn.verify_raw_script.html
+    /// [MAX_SCRIPT_SIZE]: constant.MAX_SCRIPT_SIZE.html
+    ScriptSize,
+    /// A `CheckMultiSig` operator was given a negative number of signatures, or more signatures
+    /// than public keys.
+    SigCount,
+    /// The stack exceeded [`MAX_STACK_SIZE`][MAX_STACK_SIZE].
+    ///
+    /// [MAX_STACK_SIZE]: constant.MAX_STACK_SIZE.html
+    StackSize,
+    /// An operator found a value that is not `Bytes`.
+    TypeMismatch {
+        /// The [`type_name`][type_name] of the value that was found instead.
+        ///
+        /// [type_name]: ../../core/value/enum.Value.html#method.type_name
+        found: &'static str,
+    },
+    /// An `If` or `NotIf` operator was not closed by an `EndIf`, or found no condition in the
+    /// stack, or an `Else` or `EndIf` was found without a matching `If` or `NotIf`.
+    UnbalancedConditional,
+    /// A `Verify` operator found a value that is not truthy.
+    Verify,
+}
+
+/// Checks the signatures found by `CheckSig` and `CheckMultiSig` operators on behalf of the
+/// operator system, which does not know the message being signed.
+///
+/// It is implemented for any `Fn(&[u8], &[u8]) -> bool` closure taking the signature and the
+/// public key, in that order.
+pub trait SignatureChecker {
+    /// Tells whether `signature` is a valid signature by the owner of `public_key`.
+    fn check_signature(&self, signature: &[u8], public_key: &[u8]) -> bool;
+}
+
+impl<F> SignatureChecker for F
+where
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    fn check_signature(&self, signature: &[u8], public_key: &[u8]) -> bool {
+        self(signature, public_key)
+    }
+}
+
+/// A [`SignatureChecker`][SignatureChecker] that rejects all signatures, as the one used by
+/// Bitcoin Core when there is no transaction to check signatures against.
+///
+/// [SignatureChecker]: trait.SignatureChecker.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BaseSignatureChecker;
+
+impl SignatureChecker for BaseSignatureChecker {
+    fn check_signature(&self, _signature: &[u8], _public_key: &[u8]) -> bool {
+        false
+    }
+}
+
+/// The state that [`bitcoin_op_sys`][bitcoin_op_sys] keeps in between operators.
+///
+/// [bitcoin_op_sys]: fn.bitcoin_op_sys.html
+pub struct BitcoinContext<'a> {
+    checker: &'a dyn SignatureChecker,
+    op_count: usize,
+}
+
+impl<'a> BitcoinContext<'a> {
+    /// Creates a context that checks signatures with `checker`.
+    pub fn new(checker: &'a dyn SignatureChecker) -> Self {
+        Self {
+            checker,
+            op_count: 0,
+        }
+    }
+
+    /// Returns the number of operators that count towards the
+    /// [`MAX_OPS_PER_SCRIPT`][MAX_OPS_PER_SCRIPT] limit found so far.
+    ///
+    /// [MAX_OPS_PER_SCRIPT]: constant.MAX_OPS_PER_SCRIPT.html
+    pub fn op_count(&self) -> usize {
+        self.op_count
+    }
+
+    fn add_ops(&mut self, count: usize) -> Result<(), ScriptError> {
+        self.op_count += count;
+        if self.op_count > MAX_OPS_PER_SCRIPT {
+            return Err(ScriptError::OpCount);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for BitcoinContext<'static> {
+    fn default() -> Self {
+        Self::new(&BaseSignatureChecker)
+    }
+}
+
+impl core::fmt::Debug for BitcoinContext<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("BitcoinContext")
+            .field("op_count", &self.op_count)
+            .finish()
+    }
+}
+
+/// An operator system that decides how each of the variants of
+/// [`BitcoinOperator`][BitcoinOperator] trigger push and pulls on the [`Stack`][Stack] inside a
+/// [`Machine`][Machine], keeping track of its state in a [`BitcoinContext`][BitcoinContext].
+///
+/// [BitcoinOperator]: enum.BitcoinOperator.html
+/// [BitcoinContext]: struct.BitcoinContext.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn bitcoin_op_sys(
+    stack: &mut Stack,
+    operator: &BitcoinOperator,
+    if_stack: &mut ConditionStack,
+    context: &mut BitcoinContext,
+) -> Result<(), ScriptError> {
+    use BitcoinOperator::*;
+
+    // These checks apply even to the operators that are not executed.
+    if *operator != Reserved {
+        context.add_ops(1)?;
+    }
+    if operator.is_disabled() {
+        return Err(ScriptError::DisabledOpcode);
+    }
+
+    match operator {
+        If | NotIf => {
+            let mut condition = false;
+            if if_stack.all_true() {
+                let value = stack.pop().ok_or(ScriptError::UnbalancedConditional)?;
+                condition = value.is_truthy() == (*operator == If);
+            }
+            if_stack.push_back(condition);
+
+            return Ok(());
+        }
+        Else => {
+            return if_stack
+                .toggle_top()
+                .ok_or(ScriptError::UnbalancedConditional)
+        }
+        EndIf => {
+            return if_stack
+                .pop_back()
+                .ok_or(ScriptError::UnbalancedConditional)
+        }
+        VerIf | VerNotIf => return Err(ScriptError::BadOpcode),
+        _ if !if_stack.all_true() => return Ok(()),
+        _ => {}
+    }
+
+    match operator {
+        Nop | Nop1 | CheckLockTimeVerify | CheckSequenceVerify | Nop4 | Nop5 | Nop6 | Nop7
+        | Nop8 | Nop9 | Nop10 | CodeSeparator => {}
+        Verify => {
+            if !pop(stack)?.is_truthy() {
+                return Err(ScriptError::Verify);
+            }
+        }
+        Return => return Err(ScriptError::OpReturn),
+        ToAltStack => {
+            require(stack, 1)?;
+            stack.pop_into_alt();
+        }
+        FromAltStack => {
+            if stack.alt_length() == 0 {
+                return Err(ScriptError::InvalidAltstackOperation);
+            }
+            stack.push_from_alt();
+        }
+        TwoDrop => {
+            require(stack, 2)?;
+            stack.pop();
+            stack.pop();
+        }
+        TwoDup => copy(stack, &[1, 1])?,
+        ThreeDup => copy(stack, &[2, 2, 2])?,
+        TwoOver => copy(stack, &[3, 3])?,
+        TwoRot => roll(stack, &[5, 5])?,
+        TwoSwap => roll(stack, &[3, 3])?,
+        IfDup => {
+            require(stack, 1)?;
+            if stack.topmost().is_some_and(Value::is_truthy) {
+                copy(stack, &[0])?;
+            }
+        }
+        Depth => stack.push(number(stack.length() as i64)),
+        Drop => {
+            pop(stack)?;
+        }
+        Dup => copy(stack, &[0])?,
+        Nip => {
+            require(stack, 2)?;
+            stack.remove(1);
+        }
+        Over => copy(stack, &[1])?,
+        Pick | Roll => {
+            require(stack, 2)?;
+            let depth = usize::try_from(pop_number(stack)?)
+                .ok()
+                .filter(|depth| *depth < stack.length())
+                .ok_or(ScriptError::InvalidStackOperation)?;
+            if *operator == Pick {
+                copy(stack, &[depth])?;
+            } else {
+                roll(stack, &[depth])?;
+            }
+        }
+        Rot => roll(stack, &[2])?,
+        Swap => roll(stack, &[1])?,
+        Tuck => {
+            require(stack, 2)?;
+            let x2 = stack.pop().ok_or(ScriptError::InvalidStackOperation)?;
+            let x1 = stack.pop().ok_or(ScriptError::InvalidStackOperation)?;
+            stack.push(x2.clone());
+            stack.push(x1);
+            stack.push(x2);
+        }
+        Size => {
+            let size = match stack.topmost() {
+                Some(Value::Bytes(bytes)) => bytes.len(),
+                Some(other) => {
+                    return Err(ScriptError::TypeMismatch {
+                        found: other.type_name(),
+                    })
+                }
+                None => return Err(ScriptError::InvalidStackOperation),
+            };
+            stack.push(number(size as i64));
+        }
+        Equal | EqualVerify => {
+            require(stack, 2)?;
+            let b = pop_bytes(stack)?;
+            let a = pop_bytes(stack)?;
+            if *operator == Equal {
+                stack.push(boolean(a == b));
+            } else if a != b {
+                return Err(ScriptError::EqualVerify);
+            }
+        }
+        OneAdd | OneSub | Negate | Abs | Not | ZeroNotEqual => {
+            let a = pop_number(stack)?;
+            stack.push(match operator {
+                OneAdd => number(a + 1),
+                OneSub => number(a - 1),
+                Negate => number(-a),
+                Abs => number(a.abs()),
+                Not => boolean(a == 0),
+                _ => boolean(a != 0),
+            });
+        }
+        Add | Sub | BoolAnd | BoolOr | NumEqual | NumEqualVerify | NumNotEqual | LessThan
+        | GreaterThan | LessThanOrEqual | GreaterThanOrEqual | Min | Max => {
+            require(stack, 2)?;
+            let b = pop_number(stack)?;
+            let a = pop_number(stack)?;
+            if *operator == NumEqualVerify {
+                return if a == b {
+                    Ok(())
+                } else {
+                    Err(ScriptError::NumEqualVerify)
+                };
+            }
+            stack.push(match operator {
+                Add => number(a + b),
+                Sub => number(a - b),
+                BoolAnd => boolean(a != 0 && b != 0),
+                BoolOr => boolean(a != 0 || b != 0),
+                NumEqual => boolean(a == b),
+                NumNotEqual => boolean(a != b),
+                LessThan => boolean(a < b),
+                GreaterThan => boolean(a > b),
+                LessThanOrEqual => boolean(a <= b),
+                GreaterThanOrEqual => boolean(a >= b),
+                Min => number(a.min(b)),
+                _ => number(a.max(b)),
+            });
+        }
+        Within => {
+            require(stack, 3)?;
+            let max = pop_number(stack)?;
+            let min = pop_number(stack)?;
+            let x = pop_number(stack)?;
+            stack.push(boolean(min <= x && x < max));
+        }
+        Ripemd160 | Sha1 | Sha256 | Hash160 | Hash256 => {
+            let data = pop_bytes(stack)?;
+            stack.push(Value::Bytes(match operator {
+                Ripemd160 => ripemd::Ripemd160::digest(&data).to_vec(),
+                Sha1 => sha1::Sha1::digest(&data).to_vec(),
+                Sha256 => sha2::Sha256::digest(&data).to_vec(),
+                Hash160 => ripemd::Ripemd160::digest(sha2::Sha256::digest(&data)).to_vec(),
+                _ => sha2::Sha256::digest(sha2::Sha256::digest(&data)).to_vec(),
+            }));
+        }
+        CheckSig | CheckSigVerify => {
+            require(stack, 2)?;
+            let public_key = pop_bytes(stack)?;
+            let signature = pop_bytes(stack)?;
+            let valid = context.checker.check_signature(&signature, &public_key);
+            if *operator == CheckSig {
+                stack.push(boolean(valid));
+            } else if !valid {
+                return Err(ScriptError::CheckSigVerify);
+            }
+        }
+        CheckMultiSig | CheckMultiSigVerify => {
+            let valid = check_multisig(stack, context)?;
+            if *operator == CheckMultiSig {
+                stack.push(boolean(valid));
+            } else if !valid {
+                return Err(ScriptError::CheckMultiSigVerify);
+            }
+        }
+        Reserved | Ver | Reserved1 | Reserved2 | Invalid(_) => return Err(ScriptError::BadOpcode),
+        // Flow control and disabled operators have already been dealt with above.
+        If | NotIf | VerIf | VerNotIf | Else | EndIf | Cat | Substr | Left | Right | Invert
+        | And | Or | Xor | TwoMul | TwoDiv | Mul | Div | Mod | LShift | RShift => {
+            unreachable!()
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluates a whole script on a stack, as Bitcoin Core's `EvalScript` does.
+///
+/// On top of running every operator through [`bitcoin_op_sys`][bitcoin_op_sys], this enforces
+/// [`MAX_SCRIPT_ELEMENT_SIZE`][MAX_SCRIPT_ELEMENT_SIZE] and [`MAX_STACK_SIZE`][MAX_STACK_SIZE],
+/// and checks that all conditionals are closed at the end of the script. The operator count in
+/// `context` is reset, and the `alt` sub-stack is emptied before starting.
+///
+/// [bitcoin_op_sys]: fn.bitcoin_op_sys.html
+/// [MAX_SCRIPT_ELEMENT_SIZE]: constant.MAX_SCRIPT_ELEMENT_SIZE.html
+/// [MAX_STACK_SIZE]: constant.MAX_STACK_SIZE.html
+pub fn eval_script(
+    stack: &mut Stack,
+    script: ScriptRef<BitcoinOperator>,
+    context: &mut BitcoinContext,
+) -> Result<(), ScriptError> {
+    let mut if_stack = ConditionStack::default();
+    context.op_count = 0;
+    while stack.alt_length() > 0 {
+        stack.push_from_alt();
+        stack.pop();
+    }
+
+    for item in script {
+        match item {
+            Item::Operator(operator) => bitcoin_op_sys(stack, operator, &mut if_stack, context)?,
+            Item::Value(value) => {
+                if matches!(value, Value::Bytes(bytes) if bytes.len() > MAX_SCRIPT_ELEMENT_SIZE) {
+                    return Err(ScriptError::PushSize);
+                }
+                if if_stack.all_true() {
+                    stack.push(value.clone());
+                }
+            }
+        }
+
+        if stack.length() + stack.alt_length() > MAX_STACK_SIZE {
+            return Err(ScriptError::StackSize);
+        }
+    }
+
+    if !if_stack.is_empty() {
+        return Err(ScriptError::UnbalancedConditional);
+    }
+
+    Ok(())
+}
+
+/// Tells whether `script_sig` satisfies `script_pubkey`, as Bitcoin Core's `VerifyScript` does
+/// when no script verification flags are set.
+///
+/// Both scripts are evaluated one after the other on the same stack with
+/// [`eval_script`][eval_script], and the verification succeeds if the topmost value in the stack
+/// is truthy at the end.
+///
+/// [eval_script]: fn.eval_script.html
+pub fn verify_script(
+    script_sig: ScriptRef<BitcoinOperator>,
+    script_pubkey: ScriptRef<BitcoinOperator>,
+    checker: &dyn SignatureChecker,
+) -> Result<(), ScriptError> {
+    let mut stack = Stack::default();
+    let mut context = BitcoinContext::new(checker);
+
+    eval_script(&mut stack, script_sig, &mut context)?;
+    eval_script(&mut stack, script_pubkey, &mut context)?;
+
+    match stack.topmost() {
+        Some(value) if value.is_truthy() => Ok(()),
+        _ => Err(ScriptError::EvalFalse),
+    }
+}
+
+/// Tells whether the raw `script_sig` satisfies the raw `script_pubkey`, just like
+/// [`verify_script`][verify_script] does for decoded scripts.
+///
+/// Scripts longer than [`MAX_SCRIPT_SIZE`][MAX_SCRIPT_SIZE] fail with
+/// [`ScriptError::ScriptSize`][ScriptSize], and scripts that cannot be decoded with
+/// [`from_slice`][from_slice] fail with [`ScriptError::BadOpcode`][BadOpcode], as Bitcoin Core
+/// does.
+///
+/// [verify_script]: fn.verify_script.html
+/// [MAX_SCRIPT_SIZE]: constant.MAX_SCRIPT_SIZE.html
+/// [ScriptSize]: enum.ScriptError.html#variant.ScriptSize
+/// [from_slice]: ../../codecs/bitcoin/fn.from_slice.html
+/// [BadOpcode]: enum.ScriptError.html#variant.BadOpcode
+pub fn verify_raw_script(
+    script_sig: &[u8],
+    script_pubkey: &[u8],
+    checker: &dyn SignatureChecker,
+) -> Result<(), ScriptError> {
+    let decode = |bytes: &[u8]| {
+        if bytes.len() > MAX_SCRIPT_SIZE {
+            return Err(ScriptError::ScriptSize);
+        }
+        from_slice(bytes).map_err(|_| ScriptError::BadOpcode)
+    };
+
+    verify_script(&decode(script_sig)?, &decode(script_pubkey)?, checker)
+}
+
+/// Encodes a number as a Bitcoin Script number, i.e. little-endian, sign-magnitude, using as few
+/// bytes as possible. Zero is encoded as an empty vector.
+pub fn encode_script_num(input: i64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut magnitude = input.unsigned_abs();
+    while magnitude > 0 {
+        bytes.push(magnitude as u8);
+        magnitude >>= 8;
+    }
+
+    // The most significant bit of the last byte is reserved for the sign.
+    match bytes.last_mut() {
+        Some(last) if *last & 0x80 != 0 => bytes.push(if input < 0 { 0x80 } else { 0x00 }),
+        Some(last) if input < 0 => *last |= 0x80,
+        _ => {}
+    }
+
+    bytes
+}
+
+/// Decodes a Bitcoin Script number that is at most `max_length` bytes long.
+///
+/// Non-minimal encodings (e.g. with extra zero bytes) are accepted.
+///
+/// # Panics
+///
+/// Panics if `max_length` is greater than 8.
+pub fn decode_script_num(bytes: &[u8], max_length: usize) -> Result<i64, ScriptError> {
+    assert!(max_length <= 8);
+    if bytes.len() > max_length {
+        return Err(ScriptError::ScriptNum);
+    }
+
+    let magnitude = bytes
+        .iter()
+        .rev()
+        .fold(0u64, |magnitude, byte| (magnitude << 8) | u64::from(*byte));
+
+    match bytes.last() {
+        Some(last) if last & 0x80 != 0 => {
+            let sign_bit = 0x80u64 << (8 * (bytes.len() - 1));
+            Ok(-((magnitude & !sign_bit) as i64))
+        }
+        _ => Ok(magnitude as i64),
+    }
+}
+
+/// Runs the signature checking logic of `CheckMultiSig`, as implemented by Bitcoin Core,
+/// including the extra dummy value that is popped from the stack.
+fn check_multisig(stack: &mut Stack, context: &mut BitcoinContext) -> Result<bool, ScriptError> {
+    // Positions are counted from the top of the stack, starting at 1.
+    let mut i = 1;
+    require(stack, i)?;
+    let mut keys_count = decode_script_num(peek_bytes(stack, i)?, 4)?;
+    if !(0..=MAX_PUBKEYS_PER_MULTISIG).contains(&keys_count) {
+        return Err(ScriptError::PubkeyCount);
+    }
+    context.add_ops(keys_count as usize)?;
+    i += 1;
+    let mut key_position = i;
+    i += keys_count as usize;
+    require(stack, i)?;
+
+    let mut sigs_count = decode_script_num(peek_bytes(stack, i)?, 4)?;
+    if !(0..=keys_count).contains(&sigs_count) {
+        return Err(ScriptError::SigCount);
+    }
+    i += 1;
+    let mut sig_position = i;
+    i += sigs_count as usize;
+    require(stack, i)?;
+
+    let mut valid = true;
+    while valid && sigs_count > 0 {
+        let signature = peek_bytes(stack, sig_position)?;
+        let public_key = peek_bytes(stack, key_position)?;
+        if context.checker.check_signature(signature, public_key) {
+            sig_position += 1;
+            sigs_count -= 1;
+        }
+        key_position += 1;
+        keys_count -= 1;
+
+        // There are not enough public keys left for the remaining signatures.
+        if sigs_count > keys_count {
+            valid = false;
+        }
+    }
+
+    // Pop all the values that were used, plus the dummy value.
+    for _ in 0..i {
+        stack.pop();
+    }
+
+    Ok(valid)
+}
+
+/// Makes sure that there are at least `count` values in the stack.
+fn require(stack: &Stack, count: usize) -> Result<(), ScriptError> {
+    if stack.length() < count {
+        return Err(ScriptError::InvalidStackOperation);
+    }
+
+    Ok(())
+}
+
+/// Pushes copies of the values at the given depths, in order, each depth being counted after the
+/// previous value has been pushed.
+fn copy(stack: &mut Stack, depths: &[usize]) -> Result<(), ScriptError> {
+    for depth in depths {
+        let value = stack
+            .peek(*depth)
+            .cloned()
+            .ok_or(ScriptError::InvalidStackOperation)?;
+        stack.push(value);
+    }
+
+    Ok(())
+}
+
+/// Moves the values at the given depths to the top of the stack, in order, each depth being
+/// counted after the previous value has been moved.
+fn roll(stack: &mut Stack, depths: &[usize]) -> Result<(), ScriptError> {
+    require(stack, depths.iter().max().map_or(0, |depth| depth + 1))?;
+    for depth in depths {
+        let value = stack
+            .remove(*depth)
+            .ok_or(ScriptError::InvalidStackOperation)?;
+        stack.push(value);
+    }
+
+    Ok(())
+}
+
+/// Pops a value from the stack.
+fn pop(stack: &mut Stack) -> Result<Value, ScriptError> {
+    stack.pop().ok_or(ScriptError::InvalidStackOperation)
+}
+
+/// Pops a value from the stack, making sure it is `Bytes`.
+fn pop_bytes(stack: &mut Stack) -> Result<Vec<u8>, ScriptError> {
+    match pop(stack)? {
+        Value::Bytes(bytes) => Ok(bytes),
+        other => Err(ScriptError::TypeMismatch {
+            found: other.type_name(),
+        }),
+    }
+}
+
+/// Pops a value from the stack, making sure it is a number of up to 4 bytes.
+fn pop_number(stack: &mut Stack) -> Result<i64, ScriptError> {
+    decode_script_num(&pop_bytes(stack)?, 4)
+}
+
+/// Returns the `Bytes` value at the given position, counting from the top of the stack and
+/// starting at 1.
+fn peek_bytes(stack: &Stack, position: usize) -> Result<&[u8], ScriptError> {
+    match stack.peek(position - 1) {
+        Some(Value::Bytes(bytes)) => Ok(bytes),
+        Some(other) => Err(ScriptError::TypeMismatch {
+            found: other.type_name(),
+        }),
+        None => Err(ScriptError::InvalidStackOperation),
+    }
+}
+
+fn number(input: i64) -> Value {
+    Value::Bytes(encode_script_num(input))
+}
+
+fn boolean(input: bool) -> Value {
+    number(input.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::core::value::Value::*;
+    use crate::op_systems::bitcoin::*;
+    use crate::prelude::Item::*;
+
+    #[test]
+    fn test_script_num() {
+        for (number, bytes) in [
+            (0i64, &[][..]),
+            (1, &[0x01]),
+            (-1, &[0x81]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x00]),
+            (-128, &[0x80, 0x80]),
+            (255, &[0xff, 0x00]),
+            (256, &[0x00, 0x01]),
+            (-256, &[0x00, 0x81]),
+            (2147483647, &[0xff, 0xff, 0xff, 0x7f]),
+            (-2147483647, &[0xff, 0xff, 0xff, 0xff]),
+            (2147483648, &[0x00, 0x00, 0x00, 0x80, 0x00]),
+        ] {
+            assert_eq!(encode_script_num(number), bytes);
+            assert_eq!(decode_script_num(bytes, 5), Ok(number));
+        }
+
+        // Non-minimal encodings are accepted, including negative zero.
+        assert_eq!(decode_script_num(&[0x01, 0x00], 4), Ok(1));
+        assert_eq!(decode_script_num(&[0x80], 4), Ok(0));
+        // Operands are limited to 4 bytes.
+        assert_eq!(
+            decode_script_num(&[0x00, 0x00, 0x00, 0x80, 0x00], 4),
+            Err(ScriptError::ScriptNum)
+        );
+    }
+
+    #[test]
+    fn test_machine() {
+        let mut context = BitcoinContext::default();
+        let mut machine = Machine::new(
+            |stack: &mut Stack, operator: &BitcoinOperator, if_stack: &mut ConditionStack| {
+                bitcoin_op_sys(stack, operator, if_stack, &mut context)
+            },
+        );
+
+        let result = machine.run_script(&[
+            Value(Bytes([2].into())),
+            Value(Bytes([3].into())),
+            Operator(BitcoinOperator::Add),
+            Operator(BitcoinOperator::Dup),
+            Operator(BitcoinOperator::Negate),
+            Operator(BitcoinOperator::Sub),
+        ]);
+        assert_eq!(result, Ok(Some(&Bytes([10].into()))));
+
+        let result = machine.run_script(&[Value(Integer(1)), Operator(BitcoinOperator::OneAdd)]);
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 1,
+                error: ScriptError::TypeMismatch { found: "integer" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_raw_scripts() {
+        // `OP_1` against an empty script.
+        assert_eq!(
+            verify_raw_script(&[0x51], &[], &BaseSignatureChecker),
+            Ok(())
+        );
+        // `OP_NOP` repeated beyond the maximum script size.
+        assert_eq!(
+            verify_raw_script(&[0x61; MAX_SCRIPT_SIZE + 1], &[0x51], &BaseSignatureChecker),
+            Err(ScriptError::ScriptSize)
+        );
+        // A truncated `OP_PUSHDATA1`.
+        assert_eq!(
+            verify_raw_script(&[0x4c], &[0x51], &BaseSignatureChecker),
+            Err(ScriptError::BadOpcode)
+        );
+        // `OP_IF OP_ENDIF` with no condition in the stack.
+        assert_eq!(
+            verify_raw_script(&[], &[0x63, 0x68], &BaseSignatureChecker),
+            Err(ScriptError::UnbalancedConditional)
+        );
+    }
+
+    #[test]
+    fn test_pay_to_pubkey_hash() {
+        let public_key = Vec::from([0x02; 33]);
+        let signature = Vec::from([0x30; 71]);
+        let checker = |sig: &[u8], key: &[u8]| sig == [0x30; 71] && key == [0x02; 33];
+
+        let script_sig = [Value(Bytes(signature)), Value(Bytes(public_key.clone()))];
+        let public_key_hash = {
+            let mut stack = Stack::default();
+            let mut context = BitcoinContext::default();
+            eval_script(
+                &mut stack,
+                &[Value(Bytes(public_key)), Operator(BitcoinOperator::Hash160)],
+                &mut context,
+            )
+            .unwrap();
+            stack.pop().unwrap()
+        };
+        let script_pubkey = [
+            Operator(BitcoinOperator::Dup),
+            Operator(BitcoinOperator::Hash160),
+            Value(public_key_hash),
+            Operator(BitcoinOperator::EqualVerify),
+            Operator(BitcoinOperator::CheckSig),
+        ];
+
+        assert_eq!(verify_script(&script_sig, &script_pubkey, &checker), Ok(()));
+        assert_eq!(
+            verify_script(&script_sig, &script_pubkey, &BaseSignatureChecker),
+            Err(ScriptError::EvalFalse)
+        );
+    }
+
+    #[test]
+    fn test_check_multisig() {
+        let checker = |sig: &[u8], key: &[u8]| sig[0] >> 4 == key[0] >> 4;
+        let script_pubkey = [
+            Value(Bytes([2].into())),
+            Value(Bytes([0xa1].into())),
+            Value(Bytes([0xb1].into())),
+            Value(Bytes([0xc1].into())),
+            Value(Bytes([3].into())),
+            Operator(BitcoinOperator::CheckMultiSig),
+        ];
+
+        for (signatures, expected) in [
+            (&[0xa0, 0xb0][..], Ok(())),
+            (&[0xa0, 0xc0], Ok(())),
+            (&[0xb0, 0xc0], Ok(())),
+            // Signatures must be in the same order as public keys.
+            (&[0xb0, 0xa0], Err(ScriptError::EvalFalse)),
+            (&[0xa0, 0xa0], Err(ScriptError::EvalFalse)),
+        ] {
+            let mut script_sig = Vec::from([Value(Bytes(Vec::new()))]);
+            script_sig.extend(signatures.iter().map(|sig| Value(Bytes([*sig].into()))));
+
+            assert_eq!(
+                verify_script(&script_sig, &script_pubkey, &checker),
+                expected
+            );
+        }
+
+        // Without the dummy value.
+        let script_sig = [Value(Bytes([0xa0].into())), Value(Bytes([0xb0].into()))];
+        assert_eq!(
+            verify_script(&script_sig, &script_pubkey, &checker),
+            Err(ScriptError::InvalidStackOperation)
+        );
+    }
+
+    /// The name used by Bitcoin Core's `script_tests.json` for an evaluation result.
+    fn result_name(result: &Result<(), ScriptError>) -> &'static str {
+        match result {
+            Ok(()) => "OK",
+            Err(ScriptError::BadOpcode) => "BAD_OPCODE",
+            Err(ScriptError::CheckMultiSigVerify) => "CHECKMULTISIGVERIFY",
+            Err(ScriptError::CheckSigVerify) => "CHECKSIGVERIFY",
+            Err(ScriptError::DisabledOpcode) => "DISABLED_OPCODE",
+            Err(ScriptError::EqualVerify) => "EQUALVERIFY",
+            Err(ScriptError::EvalFalse) => "EVAL_FALSE",
+            Err(ScriptError::InvalidAltstackOperation) => "INVALID_ALTSTACK_OPERATION",
+            Err(ScriptError::InvalidStackOperation) => "INVALID_STACK_OPERATION",
+            Err(ScriptError::NumEqualVerify) => "NUMEQUALVERIFY",
+            Err(ScriptError::OpCount) => "OP_COUNT",
+            Err(ScriptError::OpReturn) => "OP_RETURN",
+            Err(ScriptError::PubkeyCount) => "PUBKEY_COUNT",
+            Err(ScriptError::PushSize) => "PUSH_SIZE",
+            Err(ScriptError::ScriptNum) => "SCRIPTNUM",
+            Err(ScriptError::ScriptSize) => "SCRIPT_SIZE",
+            Err(ScriptError::SigCount) => "SIG_COUNT",
+            Err(ScriptError::StackSize) => "STACK_SIZE",
+            Err(ScriptError::TypeMismatch { .. }) => "TYPE_MISMATCH",
+            Err(ScriptError::UnbalancedConditional) => "UNBALANCED_CONDITIONAL",
+            Err(ScriptError::Verify) => "VERIFY",
+        }
+    }
+
+    /// Assembles a script written in the format used by Bitcoin Core's `script_tests.json`.
+    fn assemble(asm: &str) -> Vec<u8> {
+        fn push(bytes: &mut Vec<u8>, data: &[u8]) {
+            match data.len() {
+                length @ 0..=0x4b => bytes.push(length as u8),
+                length @ 0x4c..=0xff => bytes.extend([0x4c, length as u8]),
+                length @ 0x100..=0xffff => {
+                    bytes.push(0x4d);
+                    bytes.extend((length as u16).to_le_bytes());
+                }
+                length => {
+                    bytes.push(0x4e);
+                    bytes.extend((length as u32).to_le_bytes());
+                }
+            }
+            bytes.extend(data);
+        }
+
+        let mut bytes = Vec::new();
+        for word in asm.split_whitespace() {
+            if let Ok(number) = word.parse::<i64>() {
+                match number {
+                    0 => bytes.push(0x00),
+                    -1 => bytes.push(0x4f),
+                    1..=16 => bytes.push(0x50 + number as u8),
+                    _ => push(&mut bytes, &encode_script_num(number)),
+                }
+            } else if let Some(hex) = word.strip_prefix("0x") {
+                bytes.extend(
+                    (0..hex.len())
+                        .step_by(2)
+                        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()),
+                );
+            } else if word.len() >= 2 && word.starts_with('\'') && word.ends_with('\'') {
+                push(&mut bytes, &word.as_bytes()[1..word.len() - 1]);
+            } else {
+                let operator = BitcoinOperator::from_name(word)
+                    .unwrap_or_else(|| panic!("Unknown opcode {}", word));
+                bytes.push(operator.opcode());
+            }
+        }
+
+        bytes
+    }
+
+    /// Runs the test vectors in Bitcoin Core's `script_tests.json`, vendored from
+    /// https://github.com/bitcoin/bitcoin/blob/master/src/test/data/script_tests.json under the
+    /// MIT license.
+    ///
+    /// Only the test cases that do not depend on script verification flags other than `P2SH` and
+    /// `STRICTENC` are run, as those are not implemented by this operator system. Test cases that
+    /// spend pay-to-script-hash outputs, or that need valid signatures, are skipped too.
+    #[test]
+    fn test_script_tests_json() {
+        let tests: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../../tests/data/script_tests.json")).unwrap();
+
+        let mut count = 0;
+        let mut failures = Vec::<String>::new();
+        for test in &tests {
+            let Some(fields) = test
+                .as_array()
+                .and_then(|test| test.iter().map(|x| x.as_str()).collect::<Option<Vec<_>>>())
+            else {
+                // Witness test cases.
+                continue;
+            };
+            let [script_sig, script_pubkey, flags, expected, ..] = fields[..] else {
+                // Comments.
+                continue;
+            };
+
+            if !flags
+                .split(',')
+                .all(|flag| ["", "P2SH", "STRICTENC"].contains(&flag))
+            {
+                continue;
+            }
+
+            let script_sig = assemble(script_sig);
+            let script_pubkey = assemble(script_pubkey);
+
+            // Pay-to-script-hash outputs, i.e. `HASH160 <20 bytes> EQUAL`.
+            let is_p2sh = script_pubkey.len() == 23
+                && script_pubkey[0] == 0xa9
+                && script_pubkey[1] == 0x14
+                && script_pubkey[22] == 0x87;
+            if flags.contains("P2SH") && is_p2sh {
+                continue;
+            }
+
+            // Test cases whose result depends on the validity of signatures.
+            let uses_signatures = [&script_sig, &script_pubkey]
+                .iter()
+                .any(|script| script.iter().any(|byte| (0xac..=0xaf).contains(byte)));
+            if uses_signatures
+                && (expected == "OK"
+                    || expected == "EVAL_FALSE"
+                    || expected.starts_with("SIG_")
+                    || expected == "PUBKEYTYPE"
+                    || expected == "NULLFAIL")
+            {
+                continue;
+            }
+
+            count += 1;
+            let result = verify_raw_script(&script_sig, &script_pubkey, &BaseSignatureChecker);
+            if result_name(&result) != expected {
+                failures.push(alloc::format!(
+                    "{} => expected {}, got {:?}",
+                    test,
+                    expected,
+                    result
+                ));
+            }
+        }
+
+        assert!(failures.is_empty(), "{:#?}", failures);
+        assert!(count > 700, "Only {} test cases were run", count);
+    }
+}
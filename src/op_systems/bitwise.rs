@@ -0,0 +1,337 @@
+//! Operators for manipulating the bits of [`Integer`][Integer] and [`Bytes`][Bytes] values.
+//!
+//! Integers are operated in their 128-bit two's complement representation. None of the operators
+//! in this module can overflow: bits that are shifted out are simply discarded, and shift amounts
+//! are bounded to the `0..=127` range.
+//!
+//! Bytes are operated byte by byte, so the binary operators require both of their operands to have
+//! the same length. Shifts are only available for integers.
+//!
+//! [Integer]: ../../core/value/enum.Value.html#variant.Integer
+//! [Bytes]: ../../core/value/enum.Value.html#variant.Bytes
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Operators for manipulating bits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum BitwiseOperator {
+    /// Bitwise conjunction of two integers, or of two bytes values of the same length (`a & b`).
+    And,
+    /// Flips every bit of an integer or bytes value (`!a`).
+    Invert,
+    /// Bitwise disjunction of two integers, or of two bytes values of the same length (`a | b`).
+    Or,
+    /// Counts the bits that are set in an integer or bytes value.
+    ///
+    /// As integers are operated in two's complement, negative numbers have many bits set, e.g.
+    /// `-1` has all of its 128 bits set.
+    PopCount,
+    /// Shifts the bits of an integer `n` positions to the left (`a << n`), discarding the bits that
+    /// are shifted out and filling the least significant bits with zeros.
+    ///
+    /// Fails with [`OperatorError::OutOfRange`][OutOfRange] if `n` is not in the `0..=127` range.
+    ///
+    /// [OutOfRange]: ../enum.OperatorError.html#variant.OutOfRange
+    ShiftLeft,
+    /// Shifts the bits of an integer `n` positions to the right (`a >> n`), discarding the bits
+    /// that are shifted out and preserving the sign, i.e. this is an arithmetic shift.
+    ///
+    /// Fails with [`OperatorError::OutOfRange`][OutOfRange] if `n` is not in the `0..=127` range.
+    ///
+    /// [OutOfRange]: ../enum.OperatorError.html#variant.OutOfRange
+    ShiftRight,
+    /// Bitwise exclusive disjunction of two integers, or of two bytes values of the same length
+    /// (`a ^ b`).
+    Xor,
+}
+
+/// An operator system that decides how each of the variants of
+/// [`BitwiseOperator`][BitwiseOperator] trigger push and pulls on the [`Stack`][Stack] inside a
+/// [`Machine`][Machine].
+///
+/// Operands are taken in the order in which they were pushed, i.e. `a n ShiftLeft` computes
+/// `a << n`. Binary operators on bytes values of different lengths fail with
+/// [`OperatorError::OutOfRange`][OutOfRange].
+///
+/// [OutOfRange]: ../enum.OperatorError.html#variant.OutOfRange
+/// [BitwiseOperator]: enum.BitwiseOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn bitwise_op_sys(
+    stack: &mut Stack,
+    operator: &BitwiseOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    let result = match operator {
+        BitwiseOperator::And => binary(stack, |a, b| a & b, |a, b| a & b)?,
+        BitwiseOperator::Invert => match pop(stack)? {
+            Value::Integer(a) => Value::Integer(!a),
+            Value::Bytes(a) => Value::Bytes(a.iter().map(|a| !a).collect()),
+            other => return Err(mismatch(&other)),
+        },
+        BitwiseOperator::Or => binary(stack, |a, b| a | b, |a, b| a | b)?,
+        BitwiseOperator::PopCount => match pop(stack)? {
+            Value::Integer(a) => Value::Integer(i128::from(a.count_ones())),
+            Value::Bytes(a) => Value::Integer(a.iter().map(|a| i128::from(a.count_ones())).sum()),
+            other => return Err(mismatch(&other)),
+        },
+        BitwiseOperator::ShiftLeft => {
+            let n = pop_shift_amount(stack)?;
+            let a = pop_integer(stack)?;
+            Value::Integer(a << n)
+        }
+        BitwiseOperator::ShiftRight => {
+            let n = pop_shift_amount(stack)?;
+            let a = pop_integer(stack)?;
+            Value::Integer(a >> n)
+        }
+        BitwiseOperator::Xor => binary(stack, |a, b| a ^ b, |a, b| a ^ b)?,
+    };
+    stack.push(result);
+
+    Ok(())
+}
+
+/// Pops two operands from the stack and applies `integers` on them if both are `Integer`, or
+/// `bytes` on every pair of their bytes if both are `Bytes` of the same length.
+fn binary(
+    stack: &mut Stack,
+    integers: fn(i128, i128) -> i128,
+    bytes: fn(u8, u8) -> u8,
+) -> Result<Value, OperatorError> {
+    let b = pop(stack)?;
+    let a = pop(stack)?;
+
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(integers(a, b))),
+        (Value::Bytes(a), Value::Bytes(b)) if a.len() == b.len() => Ok(Value::Bytes(
+            a.iter().zip(&b).map(|(a, b)| bytes(*a, *b)).collect(),
+        )),
+        (Value::Bytes(_), Value::Bytes(_)) => Err(OperatorError::OutOfRange),
+        (a @ (Value::Integer(_) | Value::Bytes(_)), b @ (Value::Integer(_) | Value::Bytes(_))) => {
+            Err(OperatorError::TypeMismatch {
+                expected: a.type_name(),
+                found: b.type_name(),
+            })
+        }
+        (Value::Integer(_) | Value::Bytes(_), other) | (other, _) => Err(mismatch(&other)),
+    }
+}
+
+/// Pops a value from the stack, failing if it is empty.
+fn pop(stack: &mut Stack) -> Result<Value, OperatorError> {
+    stack.pop().ok_or(OperatorError::StackUnderflow)
+}
+
+/// The error for a value that is neither an `Integer` nor `Bytes`.
+fn mismatch(value: &Value) -> OperatorError {
+    OperatorError::TypeMismatch {
+        expected: "integer",
+        found: value.type_name(),
+    }
+}
+
+/// Pops a value from the stack, making sure it is an `Integer`.
+fn pop_integer(stack: &mut Stack) -> Result<i128, OperatorError> {
+    match pop(stack)? {
+        Value::Integer(integer) => Ok(integer),
+        other => Err(mismatch(&other)),
+    }
+}
+
+/// Pops a value from the stack, making sure it is an `Integer` in the `0..=127` range.
+fn pop_shift_amount(stack: &mut Stack) -> Result<u32, OperatorError> {
+    let n = pop_integer(stack)?;
+    if (0..i128::BITS as i128).contains(&n) {
+        Ok(n as u32)
+    } else {
+        Err(OperatorError::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::Value::*;
+    use crate::op_systems::bitwise::{bitwise_op_sys, BitwiseOperator::*};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::prelude::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_binary_operators() {
+        for (operator, expected) in [(And, 0b1000), (Or, 0b1110), (Xor, 0b0110)] {
+            let machine = &mut Machine::new(&bitwise_op_sys);
+            let result = machine
+                .run_script(&Vec::from([
+                    Value(Integer(0b1100)),
+                    Value(Integer(0b1010)),
+                    Operator(operator),
+                ]))
+                .unwrap();
+
+            assert_eq!(result, Some(&Integer(expected)));
+        }
+    }
+
+    #[test]
+    fn test_unary_operators() {
+        for (operator, input, expected) in [
+            (Invert, 0, -1),
+            (Invert, -1, 0),
+            (PopCount, 0b1011, 3),
+            (PopCount, -1, 128),
+            (PopCount, i128::MIN, 1),
+        ] {
+            let machine = &mut Machine::new(&bitwise_op_sys);
+            let result = machine
+                .run_script(&Vec::from([Value(Integer(input)), Operator(operator)]))
+                .unwrap();
+
+            assert_eq!(result, Some(&Integer(expected)));
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        for (operator, expected) in [(And, [0x88, 0x00]), (Or, [0xee, 0xff]), (Xor, [0x66, 0xff])] {
+            let machine = &mut Machine::new(&bitwise_op_sys);
+            let result = machine
+                .run_script(&Vec::from([
+                    Value(Bytes([0xcc, 0x0f].into())),
+                    Value(Bytes([0xaa, 0xf0].into())),
+                    Operator(operator),
+                ]))
+                .unwrap();
+
+            assert_eq!(result, Some(&Bytes(expected.into())));
+        }
+
+        for (operator, expected) in [(Invert, Bytes([0xff, 0x0f].into())), (PopCount, Integer(4))] {
+            let machine = &mut Machine::new(&bitwise_op_sys);
+            let result = machine
+                .run_script(&Vec::from([
+                    Value(Bytes([0x00, 0xf0].into())),
+                    Operator(operator),
+                ]))
+                .unwrap();
+
+            assert_eq!(result, Some(&expected));
+        }
+
+        let machine = &mut Machine::new(&bitwise_op_sys);
+        let result = machine
+            .run_script(&Vec::from([Value(Bytes(Vec::new())), Operator(Invert)]))
+            .unwrap();
+        assert_eq!(result, Some(&Bytes(Vec::new())));
+    }
+
+    #[test]
+    fn test_shifts() {
+        for (operator, input, n, expected) in [
+            (ShiftLeft, 1, 4, 16),
+            (ShiftLeft, 1, 127, i128::MIN),
+            (ShiftLeft, i128::MAX, 1, -2),
+            (ShiftRight, 16, 4, 1),
+            (ShiftRight, -16, 2, -4),
+            (ShiftRight, -1, 127, -1),
+            (ShiftRight, i128::MAX, 127, 0),
+        ] {
+            let machine = &mut Machine::new(&bitwise_op_sys);
+            let result = machine
+                .run_script(&Vec::from([
+                    Value(Integer(input)),
+                    Value(Integer(n)),
+                    Operator(operator),
+                ]))
+                .unwrap();
+
+            assert_eq!(result, Some(&Integer(expected)));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let machine = &mut Machine::new(&bitwise_op_sys);
+
+        for n in [-1, 128, i128::MAX] {
+            let result = machine.run_script(&Vec::from([
+                Value(Integer(1)),
+                Value(Integer(n)),
+                Operator(ShiftLeft),
+            ]));
+            assert_eq!(
+                result,
+                Err(ScriptFailure {
+                    index: 2,
+                    error: OperatorError::OutOfRange
+                })
+            );
+        }
+
+        let result = machine.run_script(&Vec::from([Value(Float(1.)), Operator(Invert)]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 1,
+                error: OperatorError::TypeMismatch {
+                    expected: "integer",
+                    found: "float"
+                }
+            })
+        );
+
+        let result = machine.run_script(&Vec::from([
+            Value(Bytes([1].into())),
+            Value(Bytes([1, 2].into())),
+            Operator(Xor),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::OutOfRange
+            })
+        );
+
+        let result = machine.run_script(&Vec::from([
+            Value(Integer(1)),
+            Value(Bytes([1].into())),
+            Operator(And),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::TypeMismatch {
+                    expected: "integer",
+                    found: "bytes"
+                }
+            })
+        );
+
+        let result = machine.run_script(&Vec::from([
+            Value(Bytes([1].into())),
+            Value(Integer(1)),
+            Operator(ShiftLeft),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::TypeMismatch {
+                    expected: "integer",
+                    found: "bytes"
+                }
+            })
+        );
+    }
+}
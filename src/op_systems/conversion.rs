@@ -0,0 +1,143 @@
+//! Operators for converting values between the different variants of [`Value`][Value].
+//!
+//! [Value]: ../../core/value/enum.Value.html
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Operators for converting values into other types, and telling their types.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ConversionOperator {
+    /// Converts a value into a `Boolean`, according to its [truthiness][is_truthy].
+    ///
+    /// [is_truthy]: ../../core/value/enum.Value.html#method.is_truthy
+    ToBool,
+    /// Converts a value into a `Float`, as told by [`Value::to_float`][to_float].
+    ///
+    /// [to_float]: ../../core/value/enum.Value.html#method.to_float
+    ToFloat,
+    /// Converts a value into an `Integer`, as told by [`Value::to_integer`][to_integer].
+    ///
+    /// [to_integer]: ../../core/value/enum.Value.html#method.to_integer
+    ToInt,
+    /// Converts a value into a `String`, as told by its `Display` implementation.
+    ToString,
+    /// Replaces a value with a `String` containing the name of its type, as told by
+    /// [`Value::type_name`][type_name].
+    ///
+    /// [type_name]: ../../core/value/enum.Value.html#method.type_name
+    TypeOf,
+}
+
+/// An operator system that decides how each of the variants of
+/// [`ConversionOperator`][ConversionOperator] trigger push and pulls on the [`Stack`][Stack] inside
+/// a [`Machine`][Machine].
+///
+/// [ConversionOperator]: enum.ConversionOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn conversion_op_sys(
+    stack: &mut Stack,
+    operator: &ConversionOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    let x = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+    stack.push(match operator {
+        ConversionOperator::ToBool => x.to_boolean(),
+        ConversionOperator::ToFloat => x.to_float()?,
+        ConversionOperator::ToInt => x.to_integer()?,
+        ConversionOperator::ToString => x.to_string_value(),
+        ConversionOperator::TypeOf => Value::String(x.type_name().into()),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::{ConversionError, Value::*};
+    use crate::op_systems::conversion::{conversion_op_sys, ConversionOperator::*};
+    use crate::op_systems::flow_control::{flow_control_op_sys, FlowOperator};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::prelude::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_conversions() {
+        let machine = &mut Machine::new(&conversion_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([Value(String("42".into())), Operator(ToInt)]))
+            .unwrap();
+        assert_eq!(result, Some(&Integer(42)));
+
+        let result = machine.operate(&Operator(ToFloat)).unwrap();
+        assert_eq!(result, Some(&Float(42.)));
+
+        let result = machine.operate(&Operator(ToString)).unwrap();
+        assert_eq!(result, Some(&String("42".into())));
+
+        let result = machine.operate(&Operator(ToBool)).unwrap();
+        assert_eq!(result, Some(&Boolean(true)));
+
+        let result = machine.operate(&Operator(TypeOf)).unwrap();
+        assert_eq!(result, Some(&String("boolean".into())));
+
+        assert_eq!(machine.stack_length(), 1);
+    }
+
+    #[test]
+    fn test_invalid_conversion() {
+        let machine = &mut Machine::new(&conversion_op_sys);
+
+        let result = machine.run_script(&Vec::from([Value(Null), Operator(ToInt)]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 1,
+                error: OperatorError::Conversion(ConversionError {
+                    from: "null",
+                    into: "integer"
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn test_inside_conditional() {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Op {
+            Conversion(crate::op_systems::conversion::ConversionOperator),
+            Flow(FlowOperator),
+        }
+
+        let machine = &mut Machine::new(
+            |stack: &mut Stack, operator: &Op, if_stack: &mut ConditionStack| match operator {
+                Op::Conversion(op) => conversion_op_sys(stack, op, if_stack),
+                Op::Flow(op) => flow_control_op_sys(stack, op, if_stack),
+            },
+        );
+
+        // `ToInt` would fail on a string, but it is never evaluated.
+        let result = machine
+            .run_script(&Vec::from([
+                Value(String("foo".into())),
+                Value(Integer(0)),
+                Operator(Op::Conversion(ToBool)),
+                Operator(Op::Flow(FlowOperator::If)),
+                Operator(Op::Conversion(ToInt)),
+                Operator(Op::Flow(FlowOperator::EndIf)),
+            ]))
+            .unwrap();
+
+        assert_eq!(result, Some(&String("foo".into())));
+    }
+}
@@ -0,0 +1,154 @@
+//! Operators for conditional execution of scripts, in the likes of `OP_IF`, `OP_NOTIF`, `OP_ELSE`
+//! and `OP_ENDIF` in Bitcoin Script.
+//!
+//! Flow control relies on the [`ConditionStack`][ConditionStack] that the [`Machine`][Machine]
+//! passes to operator systems. While any of the conditions in it is false, values are not pushed
+//! into the stack, and operator systems are expected to do nothing but tracking nested
+//! conditionals. The operator systems in the [`op_systems`][op_systems] module can be easily
+//! composed with this one, as they all honor that rule.
+//!
+//! [ConditionStack]: ../../core/condition_stack/struct.ConditionStack.html
+//! [Machine]: ../../core/machine/struct.Machine.html
+//! [op_systems]: ../
+
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Operators for conditional execution.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FlowOperator {
+    /// Toggles between the two branches of the innermost conditional.
+    Else,
+    /// Closes the innermost conditional.
+    EndIf,
+    /// Pops a value, and executes the following items only if it is [truthy][is_truthy].
+    ///
+    /// [is_truthy]: ../../core/value/enum.Value.html#method.is_truthy
+    If,
+    /// Pops a value, and executes the following items only if it is not [truthy][is_truthy].
+    ///
+    /// [is_truthy]: ../../core/value/enum.Value.html#method.is_truthy
+    NotIf,
+}
+
+/// An operator system that decides how each of the variants of [`FlowOperator`][FlowOperator]
+/// trigger push and pulls on the [`Stack`][Stack] and the [`ConditionStack`][ConditionStack] inside
+/// a [`Machine`][Machine].
+///
+/// [FlowOperator]: enum.FlowOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [ConditionStack]: ../../core/condition_stack/struct.ConditionStack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn flow_control_op_sys(
+    stack: &mut Stack,
+    operator: &FlowOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    match operator {
+        FlowOperator::Else => if_stack
+            .toggle_top()
+            .ok_or(OperatorError::UnbalancedConditional),
+        FlowOperator::EndIf => if_stack
+            .pop_back()
+            .ok_or(OperatorError::UnbalancedConditional),
+        FlowOperator::If | FlowOperator::NotIf => {
+            // Conditions inside inactive branches are not evaluated, but still need to be tracked.
+            let condition = if if_stack.all_true() {
+                let value = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+                value.is_truthy() == (*operator == FlowOperator::If)
+            } else {
+                false
+            };
+            if_stack.push_back(condition);
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::Value::*;
+    use crate::op_systems::flow_control::{flow_control_op_sys, FlowOperator::*};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::prelude::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_if_else() {
+        let script = |condition| {
+            Vec::from([
+                Value(condition),
+                Operator(If),
+                Value(String("then".into())),
+                Operator(Else),
+                Value(String("else".into())),
+                Operator(EndIf),
+            ])
+        };
+
+        let machine = &mut Machine::new(&flow_control_op_sys);
+        let result = machine.run_script(&script(Integer(1))).unwrap();
+        assert_eq!(result, Some(&String("then".into())));
+        assert_eq!(machine.stack_length(), 1);
+
+        let machine = &mut Machine::new(&flow_control_op_sys);
+        let result = machine.run_script(&script(String("".into()))).unwrap();
+        assert_eq!(result, Some(&String("else".into())));
+        assert_eq!(machine.stack_length(), 1);
+    }
+
+    #[test]
+    fn test_nested() {
+        let machine = &mut Machine::new(&flow_control_op_sys);
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Boolean(true)),
+                Value(Boolean(false)),
+                Operator(If),
+                Value(Boolean(true)),
+                Operator(NotIf),
+                Value(Integer(1)),
+                Operator(Else),
+                Value(Integer(2)),
+                Operator(EndIf),
+                Operator(Else),
+                Value(Integer(3)),
+                Operator(EndIf),
+            ]))
+            .unwrap();
+
+        assert_eq!(result, Some(&Integer(3)));
+        assert_eq!(machine.stack_length(), 2);
+    }
+
+    #[test]
+    fn test_unbalanced() {
+        let machine = &mut Machine::new(&flow_control_op_sys);
+
+        assert_eq!(
+            machine.run_script(&Vec::from([Operator(Else)])),
+            Err(ScriptFailure {
+                index: 0,
+                error: OperatorError::UnbalancedConditional
+            })
+        );
+        assert_eq!(
+            machine.run_script(&Vec::from([Operator(EndIf)])),
+            Err(ScriptFailure {
+                index: 0,
+                error: OperatorError::UnbalancedConditional
+            })
+        );
+        assert_eq!(
+            machine.run_script(&Vec::from([Operator(If)])),
+            Err(ScriptFailure {
+                index: 0,
+                error: OperatorError::StackUnderflow
+            })
+        );
+    }
+}
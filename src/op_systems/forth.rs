@@ -0,0 +1,690 @@
+//! A subset of the [core word set of Forth][core], along with a textual front-end for it.
+//!
+//! The words that simply manipulate the stack are implemented as variants of
+//! [`ForthOperator`][ForthOperator], and can be used in any [`Script`][Script] like the operators
+//! in any other operator system. Output words (`.`, `EMIT` and `CR`) write into a `String`
+//! provided by the host application.
+//!
+//! Control structures (`IF ELSE THEN`, `DO LOOP`, `BEGIN UNTIL`) and definitions (`: ;`) cannot
+//! be expressed as a linear script, because loops need to jump backwards. Those are handled by the
+//! [`Forth`][Forth] front-end, which parses source code and drives a [`Machine`][Machine]
+//! accordingly:
+//!
+//! ```rust
+//! use scriptful::prelude::*;
+//! use scriptful::op_systems::forth::*;
+//!
+//! let mut output = String::new();
+//! let mut machine = Machine::new(|stack: &mut Stack, operator: &ForthOperator, if_stack: &mut ConditionStack| {
+//!     forth_op_sys(stack, operator, if_stack, &mut output)
+//! });
+//! let mut forth = Forth::default();
+//!
+//! forth.interpret(&mut machine, ": SQUARE DUP * ;  5 0 DO I SQUARE . LOOP").unwrap();
+//!
+//! drop(machine);
+//! assert_eq!(output, "0 1 4 9 16 ");
+//! ```
+//!
+//! Cells are `Integer` values, and flags follow the Forth convention of `-1` for true and `0`
+//! for false. Arithmetic wraps around on overflow, and division truncates towards zero.
+//!
+//! [core]: https://forth-standard.org/standard/core
+//! [ForthOperator]: enum.ForthOperator.html
+//! [Forth]: struct.Forth.html
+//! [Script]: ../../core/type.Script.html
+//! [Machine]: ../../core/machine/struct.Machine.html
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// The words in the Forth core word set that do not alter the control flow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ForthOperator {
+    /// `( a b -- a+b )`, the `+` word.
+    Add,
+    /// `( a b -- a&b )`, bitwise, the `AND` word.
+    And,
+    /// `( -- )`, writes a line break, the `CR` word.
+    Cr,
+    /// `( a b -- a/b )`, the `/` word.
+    Div,
+    /// `( a -- )`, writes `a` followed by a space, the `.` word.
+    Dot,
+    /// `( a -- )`, the `DROP` word.
+    Drop,
+    /// `( a -- a a )`, the `DUP` word.
+    Dup,
+    /// `( a -- )`, writes the character whose code point is `a`, the `EMIT` word.
+    Emit,
+    /// `( a b -- flag )`, the `=` word.
+    Equal,
+    /// `( a b -- flag )`, the `>` word.
+    GreaterThan,
+    /// `( a -- ~a )`, bitwise, the `INVERT` word.
+    Invert,
+    /// `( a b -- flag )`, the `<` word.
+    LessThan,
+    /// `( a b -- a%b )`, the `MOD` word.
+    Mod,
+    /// `( a b -- a*b )`, the `*` word.
+    Mul,
+    /// `( a b -- a|b )`, bitwise, the `OR` word.
+    Or,
+    /// `( a b -- a b a )`, the `OVER` word.
+    Over,
+    /// `( a b c -- b c a )`, the `ROT` word.
+    Rot,
+    /// `( a b -- a-b )`, the `-` word.
+    Sub,
+    /// `( a b -- b a )`, the `SWAP` word.
+    Swap,
+}
+
+impl ForthOperator {
+    /// Returns the operator for a Forth word, if any. Words are case insensitive.
+    pub fn from_word(word: &str) -> Option<Self> {
+        let operator = match word.to_ascii_uppercase().as_str() {
+            "+" => ForthOperator::Add,
+            "AND" => ForthOperator::And,
+            "CR" => ForthOperator::Cr,
+            "/" => ForthOperator::Div,
+            "." => ForthOperator::Dot,
+            "DROP" => ForthOperator::Drop,
+            "DUP" => ForthOperator::Dup,
+            "EMIT" => ForthOperator::Emit,
+            "=" => ForthOperator::Equal,
+            ">" => ForthOperator::GreaterThan,
+            "INVERT" => ForthOperator::Invert,
+            "<" => ForthOperator::LessThan,
+            "MOD" => ForthOperator::Mod,
+            "*" => ForthOperator::Mul,
+            "OR" => ForthOperator::Or,
+            "OVER" => ForthOperator::Over,
+            "ROT" => ForthOperator::Rot,
+            "-" => ForthOperator::Sub,
+            "SWAP" => ForthOperator::Swap,
+            _ => return None,
+        };
+
+        Some(operator)
+    }
+}
+
+/// An operator system that decides how each of the variants of [`ForthOperator`][ForthOperator]
+/// trigger push and pulls on the [`Stack`][Stack] inside a [`Machine`][Machine], writing the
+/// output of `.`, `EMIT` and `CR` into `output`.
+///
+/// [ForthOperator]: enum.ForthOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn forth_op_sys(
+    stack: &mut Stack,
+    operator: &ForthOperator,
+    if_stack: &mut ConditionStack,
+    output: &mut String,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    match operator {
+        ForthOperator::Cr => output.push('\n'),
+        ForthOperator::Dot => {
+            let a = pop_integer(stack)?;
+            write!(output, "{} ", a).map_err(|_| OperatorError::from_str("Cannot write output"))?;
+        }
+        ForthOperator::Drop => {
+            stack.pop().ok_or(OperatorError::StackUnderflow)?;
+        }
+        ForthOperator::Dup => {
+            let a = stack
+                .topmost()
+                .ok_or(OperatorError::StackUnderflow)?
+                .clone();
+            stack.push(a);
+        }
+        ForthOperator::Emit => {
+            let a = pop_integer(stack)?;
+            let character = u32::try_from(a)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(OperatorError::OutOfRange)?;
+            output.push(character);
+        }
+        ForthOperator::Invert => {
+            let a = pop_integer(stack)?;
+            stack.push(Value::Integer(!a));
+        }
+        ForthOperator::Over => {
+            let a = stack.peek(1).ok_or(OperatorError::StackUnderflow)?.clone();
+            stack.push(a);
+        }
+        ForthOperator::Rot => {
+            if stack.length() < 3 {
+                return Err(OperatorError::StackUnderflow);
+            }
+            let a = stack.remove(2).ok_or(OperatorError::StackUnderflow)?;
+            stack.push(a);
+        }
+        ForthOperator::Swap => {
+            if stack.length() < 2 {
+                return Err(OperatorError::StackUnderflow);
+            }
+            let a = stack.remove(1).ok_or(OperatorError::StackUnderflow)?;
+            stack.push(a);
+        }
+        binary => {
+            let b = pop_integer(stack)?;
+            let a = pop_integer(stack)?;
+            stack.push(Value::Integer(match binary {
+                ForthOperator::Add => a.wrapping_add(b),
+                ForthOperator::And => a & b,
+                ForthOperator::Div => nonzero(b).map(|b| a.wrapping_div(b))?,
+                ForthOperator::Equal => flag(a == b),
+                ForthOperator::GreaterThan => flag(a > b),
+                ForthOperator::LessThan => flag(a < b),
+                ForthOperator::Mod => nonzero(b).map(|b| a.wrapping_rem(b))?,
+                ForthOperator::Mul => a.wrapping_mul(b),
+                ForthOperator::Or => a | b,
+                ForthOperator::Sub => a.wrapping_sub(b),
+                _ => unreachable!(),
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+/// The errors that can happen when interpreting Forth source code with [`Forth`][Forth].
+///
+/// [Forth]: struct.Forth.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum ForthError {
+    /// An operator failed.
+    Operator(OperatorError),
+    /// A word is neither a number, a built-in word, nor a previously defined word.
+    UndefinedWord(String),
+    /// A control structure or definition is not properly opened or closed, or a word can only be
+    /// used inside some control structure, e.g. `THEN` without `IF`, or `I` outside `DO LOOP`.
+    Unbalanced(String),
+}
+
+impl core::fmt::Display for ForthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ForthError::Operator(error) => error.fmt(f),
+            ForthError::UndefinedWord(word) => write!(f, "Undefined word `{}`", word),
+            ForthError::Unbalanced(word) => write!(f, "Unbalanced control word `{}`", word),
+        }
+    }
+}
+
+impl From<OperatorError> for ForthError {
+    fn from(input: OperatorError) -> Self {
+        ForthError::Operator(input)
+    }
+}
+
+/// The compiled form of Forth source code.
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    BeginUntil(Vec<Node>),
+    Call(usize),
+    DoLoop(Vec<Node>),
+    If(Vec<Node>, Vec<Node>),
+    Index,
+    Item(Item<ForthOperator>),
+}
+
+/// The control structures that are open while compiling.
+enum Frame {
+    Begin,
+    Definition(String),
+    Do,
+    If,
+    Else(Vec<Node>),
+}
+
+/// A textual front-end for the [`forth`][forth] operator system, which keeps a dictionary of the
+/// words defined with `: ;`.
+///
+/// Besides the words in [`ForthOperator`][ForthOperator], it supports:
+///
+/// - Integer literals.
+/// - `IF ELSE THEN`, popping a flag that is true unless it is zero.
+/// - `DO LOOP`, popping an index and a limit, and running the loop body at least once. The
+///   current index can be pushed with `I`.
+/// - `BEGIN UNTIL`, popping a flag after every run of the loop body.
+/// - `: name ... ;` definitions. As in Forth, the words in a definition are looked up when
+///   defining it, so redefining a word does not affect the previous definitions using it.
+/// - `( comments )` and `\ line comments`.
+///
+/// Unlike in Forth, control structures can also be used outside definitions.
+///
+/// [forth]: index.html
+/// [ForthOperator]: enum.ForthOperator.html
+#[derive(Debug, Default)]
+pub struct Forth {
+    definitions: Vec<Vec<Node>>,
+    dictionary: BTreeMap<String, usize>,
+}
+
+impl Forth {
+    /// Interprets Forth source code on a [`Machine`][Machine] that uses the
+    /// [`forth_op_sys`][forth_op_sys] operator system.
+    ///
+    /// The whole source code is compiled before running it, so nothing is run if there are syntax
+    /// errors. The definitions found in the source code are kept for later calls, even if running
+    /// it fails.
+    ///
+    /// [Machine]: ../../core/machine/struct.Machine.html
+    /// [forth_op_sys]: fn.forth_op_sys.html
+    pub fn interpret<F>(
+        &mut self,
+        machine: &mut Machine<ForthOperator, Value, F, OperatorError>,
+        source: &str,
+    ) -> Result<(), ForthError>
+    where
+        F: FnMut(&mut Stack, &ForthOperator, &mut ConditionStack) -> Result<(), OperatorError>,
+    {
+        let nodes = self.compile(source)?;
+
+        self.run(machine, &nodes, &mut Vec::new())
+    }
+
+    /// Tells whether a word is in the dictionary, i.e. whether it has been defined with `: ;`.
+    pub fn is_defined(&self, word: &str) -> bool {
+        self.dictionary.contains_key(&word.to_ascii_uppercase())
+    }
+
+    fn compile(&mut self, source: &str) -> Result<Vec<Node>, ForthError> {
+        let mut words = words(source);
+        // The nodes in each of the open control structures, the outermost being the source itself.
+        let mut nodes = Vec::from([Vec::new()]);
+        let mut frames = Vec::new();
+
+        while let Some(word) = words.next() {
+            let upper = word.to_ascii_uppercase();
+            let node = match upper.as_str() {
+                ":" => {
+                    if frames
+                        .iter()
+                        .any(|frame| matches!(frame, Frame::Definition(_)))
+                    {
+                        return Err(ForthError::Unbalanced(word.into()));
+                    }
+                    let name = words
+                        .next()
+                        .ok_or_else(|| ForthError::Unbalanced(word.into()))?;
+                    frames.push(Frame::Definition(name.to_ascii_uppercase()));
+                    nodes.push(Vec::new());
+                    continue;
+                }
+                "IF" => {
+                    frames.push(Frame::If);
+                    nodes.push(Vec::new());
+                    continue;
+                }
+                "ELSE" => {
+                    let Some(Frame::If) = frames.pop() else {
+                        return Err(ForthError::Unbalanced(word.into()));
+                    };
+                    frames.push(Frame::Else(nodes.pop().unwrap_or_default()));
+                    nodes.push(Vec::new());
+                    continue;
+                }
+                "DO" => {
+                    frames.push(Frame::Do);
+                    nodes.push(Vec::new());
+                    continue;
+                }
+                "BEGIN" => {
+                    frames.push(Frame::Begin);
+                    nodes.push(Vec::new());
+                    continue;
+                }
+                ";" | "THEN" | "LOOP" | "UNTIL" => {
+                    let body = nodes.pop().unwrap_or_default();
+                    match (upper.as_str(), frames.pop()) {
+                        (";", Some(Frame::Definition(name))) => {
+                            self.definitions.push(body);
+                            self.dictionary.insert(name, self.definitions.len() - 1);
+                            continue;
+                        }
+                        ("THEN", Some(Frame::If)) => Node::If(body, Vec::new()),
+                        ("THEN", Some(Frame::Else(then))) => Node::If(then, body),
+                        ("LOOP", Some(Frame::Do)) => Node::DoLoop(body),
+                        ("UNTIL", Some(Frame::Begin)) => Node::BeginUntil(body),
+                        _ => return Err(ForthError::Unbalanced(word.into())),
+                    }
+                }
+                "I" => {
+                    if !frames.iter().any(|frame| matches!(frame, Frame::Do)) {
+                        return Err(ForthError::Unbalanced(word.into()));
+                    }
+                    Node::Index
+                }
+                _ => {
+                    if let Some(index) = self.dictionary.get(&upper) {
+                        Node::Call(*index)
+                    } else if let Some(operator) = ForthOperator::from_word(word) {
+                        Node::Item(Item::Operator(operator))
+                    } else if let Ok(integer) = word.parse::<i128>() {
+                        Node::Item(Item::Value(Value::Integer(integer)))
+                    } else {
+                        return Err(ForthError::UndefinedWord(word.into()));
+                    }
+                }
+            };
+
+            nodes
+                .last_mut()
+                .expect("There is always at least one list of nodes")
+                .push(node);
+        }
+
+        match frames.last() {
+            None => Ok(nodes.pop().unwrap_or_default()),
+            Some(Frame::Begin) => Err(ForthError::Unbalanced("BEGIN".into())),
+            Some(Frame::Definition(_)) => Err(ForthError::Unbalanced(":".into())),
+            Some(Frame::Do) => Err(ForthError::Unbalanced("DO".into())),
+            Some(Frame::If) => Err(ForthError::Unbalanced("IF".into())),
+            Some(Frame::Else(_)) => Err(ForthError::Unbalanced("ELSE".into())),
+        }
+    }
+
+    fn run<F>(
+        &self,
+        machine: &mut Machine<ForthOperator, Value, F, OperatorError>,
+        nodes: &[Node],
+        indexes: &mut Vec<i128>,
+    ) -> Result<(), ForthError>
+    where
+        F: FnMut(&mut Stack, &ForthOperator, &mut ConditionStack) -> Result<(), OperatorError>,
+    {
+        for node in nodes {
+            match node {
+                Node::BeginUntil(body) => loop {
+                    self.run(machine, body, indexes)?;
+                    if pop_integer_from(machine)? != 0 {
+                        break;
+                    }
+                },
+                Node::Call(index) => self.run(machine, &self.definitions[*index], indexes)?,
+                Node::DoLoop(body) => {
+                    let mut index = pop_integer_from(machine)?;
+                    let limit = pop_integer_from(machine)?;
+                    loop {
+                        indexes.push(index);
+                        let result = self.run(machine, body, indexes);
+                        indexes.pop();
+                        result?;
+
+                        // An index that cannot be incremented any further is past any limit.
+                        match index.checked_add(1) {
+                            Some(next) if next < limit => index = next,
+                            _ => break,
+                        }
+                    }
+                }
+                Node::If(then, otherwise) => {
+                    if pop_integer_from(machine)? != 0 {
+                        self.run(machine, then, indexes)?;
+                    } else {
+                        self.run(machine, otherwise, indexes)?;
+                    }
+                }
+                Node::Index => {
+                    let index = indexes
+                        .last()
+                        .ok_or_else(|| ForthError::Unbalanced("I".to_string()))?;
+                    machine.operate(&Item::Value(Value::Integer(*index)))?;
+                }
+                Node::Item(item) => {
+                    machine.operate(item)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits source code into words, skipping comments.
+fn words(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .lines()
+        .map(|line| match line.split_once(" \\") {
+            Some((code, _)) => code,
+            None if line.trim_start().starts_with('\\') => "",
+            None => line,
+        })
+        .flat_map(str::split_whitespace)
+        .scan(false, |in_comment, word| {
+            Some(match (*in_comment, word) {
+                (false, "(") => {
+                    *in_comment = true;
+                    None
+                }
+                (true, _) => {
+                    *in_comment = !word.ends_with(')');
+                    None
+                }
+                (false, word) => Some(word),
+            })
+        })
+        .flatten()
+}
+
+/// Pops a value from the stack, making sure it is an `Integer`.
+fn pop_integer(stack: &mut Stack) -> Result<i128, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::Integer(integer) => Ok(integer),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "integer",
+            found: other.type_name(),
+        }),
+    }
+}
+
+/// Pops a value from the stack of a machine, making sure it is an `Integer`.
+fn pop_integer_from<F>(
+    machine: &mut Machine<ForthOperator, Value, F, OperatorError>,
+) -> Result<i128, ForthError>
+where
+    F: FnMut(&mut Stack, &ForthOperator, &mut ConditionStack) -> Result<(), OperatorError>,
+{
+    let integer = match machine.topmost() {
+        Some(Value::Integer(integer)) => *integer,
+        Some(other) => {
+            return Err(ForthError::Operator(OperatorError::TypeMismatch {
+                expected: "integer",
+                found: other.type_name(),
+            }))
+        }
+        None => return Err(ForthError::Operator(OperatorError::StackUnderflow)),
+    };
+    machine.operate(&Item::Operator(ForthOperator::Drop))?;
+
+    Ok(integer)
+}
+
+/// Makes sure that a divisor is not zero.
+fn nonzero(divisor: i128) -> Result<i128, OperatorError> {
+    if divisor == 0 {
+        Err(OperatorError::DivisionByZero)
+    } else {
+        Ok(divisor)
+    }
+}
+
+/// Converts a boolean into a Forth flag.
+fn flag(input: bool) -> i128 {
+    if input {
+        -1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use crate::core::value::Value::*;
+    use crate::op_systems::forth::*;
+    use crate::op_systems::OperatorError;
+
+    /// Interprets some source code on a new machine, returning the topmost value and the output.
+    fn interpret(source: &str) -> Result<(Option<Value>, String), ForthError> {
+        let mut output = String::new();
+        let mut machine = Machine::new(
+            |stack: &mut Stack, operator: &ForthOperator, if_stack: &mut ConditionStack| {
+                forth_op_sys(stack, operator, if_stack, &mut output)
+            },
+        );
+        Forth::default().interpret(&mut machine, source)?;
+        let topmost = machine.topmost().cloned();
+        drop(machine);
+
+        Ok((topmost, output))
+    }
+
+    #[test]
+    fn test_stack_words() {
+        assert_eq!(interpret("1 2 SWAP").unwrap().0, Some(Integer(1)));
+        assert_eq!(interpret("1 2 OVER").unwrap().0, Some(Integer(1)));
+        assert_eq!(interpret("1 2 3 ROT").unwrap().0, Some(Integer(1)));
+        assert_eq!(interpret("1 2 3 ROT DROP").unwrap().0, Some(Integer(3)));
+        assert_eq!(interpret("7 dup *").unwrap().0, Some(Integer(49)));
+    }
+
+    #[test]
+    fn test_arithmetic_and_logic() {
+        for (source, expected) in [
+            ("2 3 +", 5),
+            ("2 3 -", -1),
+            ("6 7 *", 42),
+            ("7 2 /", 3),
+            ("-7 2 /", -3),
+            ("7 2 MOD", 1),
+            ("2 2 =", -1),
+            ("1 2 <", -1),
+            ("1 2 >", 0),
+            ("12 10 AND", 8),
+            ("12 10 OR", 14),
+            ("0 INVERT", -1),
+        ] {
+            assert_eq!(interpret(source).unwrap().0, Some(Integer(expected)));
+        }
+
+        assert_eq!(
+            interpret("1 0 /"),
+            Err(ForthError::Operator(OperatorError::DivisionByZero))
+        );
+    }
+
+    #[test]
+    fn test_output() {
+        let (_, output) = interpret("1 . 2 . CR 72 EMIT 105 EMIT").unwrap();
+        assert_eq!(output, "1 2 \nHi");
+    }
+
+    #[test]
+    fn test_control_structures() {
+        assert_eq!(interpret("1 IF 2 ELSE 3 THEN").unwrap().0, Some(Integer(2)));
+        assert_eq!(interpret("0 IF 2 ELSE 3 THEN").unwrap().0, Some(Integer(3)));
+        assert_eq!(interpret("5 0 IF DROP 2 THEN").unwrap().0, Some(Integer(5)));
+
+        let (_, output) = interpret("3 0 DO I . LOOP").unwrap();
+        assert_eq!(output, "0 1 2 ");
+
+        // Nested loops and conditionals.
+        let (_, output) = interpret("3 0 DO 2 0 DO I 1 = IF 42 EMIT THEN LOOP LOOP").unwrap();
+        assert_eq!(output, "***");
+
+        // A loop whose index cannot be incremented any further ends after a single iteration.
+        let (_, output) =
+            interpret("0 170141183460469231731687303715884105727 DO 1 . LOOP").unwrap();
+        assert_eq!(output, "1 ");
+
+        // Countdown.
+        let (_, output) = interpret("3 BEGIN DUP . 1 - DUP 0 = UNTIL DROP").unwrap();
+        assert_eq!(output, "3 2 1 ");
+    }
+
+    #[test]
+    fn test_definitions() {
+        let mut output = String::new();
+        let mut machine = Machine::new(
+            |stack: &mut Stack, operator: &ForthOperator, if_stack: &mut ConditionStack| {
+                forth_op_sys(stack, operator, if_stack, &mut output)
+            },
+        );
+        let mut forth = Forth::default();
+
+        forth
+            .interpret(&mut machine, ": SQUARE ( n -- n*n ) DUP * ;")
+            .unwrap();
+        forth
+            .interpret(&mut machine, ": CUBE DUP SQUARE * ; \\ uses SQUARE")
+            .unwrap();
+        assert!(forth.is_defined("square"));
+
+        forth.interpret(&mut machine, "3 CUBE .").unwrap();
+
+        // Redefining a word does not affect previous definitions.
+        forth
+            .interpret(&mut machine, ": SQUARE 0 ; 2 CUBE . 2 SQUARE .")
+            .unwrap();
+
+        // Built-in words can be redefined too.
+        forth.interpret(&mut machine, ": DUP 1 ; 5 DUP .").unwrap();
+
+        drop(machine);
+        assert_eq!(output, "27 8 0 1 ");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            interpret("1 FOO"),
+            Err(ForthError::UndefinedWord("FOO".into()))
+        );
+        assert_eq!(
+            interpret("1 IF 2"),
+            Err(ForthError::Unbalanced("IF".into()))
+        );
+        assert_eq!(
+            interpret("THEN"),
+            Err(ForthError::Unbalanced("THEN".into()))
+        );
+        assert_eq!(interpret("1 ;"), Err(ForthError::Unbalanced(";".into())));
+        assert_eq!(interpret("I"), Err(ForthError::Unbalanced("I".into())));
+        assert_eq!(
+            interpret(": FOO : BAR ; ;"),
+            Err(ForthError::Unbalanced(":".into()))
+        );
+        assert_eq!(
+            interpret("DROP"),
+            Err(ForthError::Operator(OperatorError::StackUnderflow))
+        );
+
+        assert_eq!(
+            ForthError::UndefinedWord("FOO".into()).to_string(),
+            "Undefined word `FOO`"
+        );
+        assert_eq!(
+            ForthError::Operator(OperatorError::DivisionByZero).to_string(),
+            "Division by zero"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Cryptographic hash operators, as found in Bitcoin Script.
+//!
+//! The operators in this module take either a `Bytes` or a `String` value, and push the digest of
+//! its contents as a `Bytes` value. Strings are hashed as their UTF-8 representation. Any other
+//! type of value fails with an [`OperatorError::TypeMismatch`][TypeMismatch].
+//!
+//! This module is only available when the `crypto` feature is enabled. The hash functions are
+//! provided by the pure-Rust, `no_std` [`sha2`][sha2] and [`ripemd`][ripemd] crates.
+//!
+//! [TypeMismatch]: ../enum.OperatorError.html#variant.TypeMismatch
+//! [sha2]: https://docs.rs/sha2
+//! [ripemd]: https://docs.rs/ripemd
+
+use alloc::vec::Vec;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Cryptographic hash operators.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum HashOperator {
+    /// RIPEMD-160 of SHA-256 (`OP_HASH160`), producing 20 bytes.
+    Hash160,
+    /// Double SHA-256 (`OP_HASH256`), producing 32 bytes.
+    Hash256,
+    /// RIPEMD-160 (`OP_RIPEMD160`), producing 20 bytes.
+    Ripemd160,
+    /// SHA-256 (`OP_SHA256`), producing 32 bytes.
+    Sha256,
+}
+
+/// An operator system that decides how each of the variants of [`HashOperator`][HashOperator]
+/// trigger push and pulls on the [`Stack`][Stack] inside a [`Machine`][Machine].
+///
+/// [HashOperator]: enum.HashOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn hash_op_sys(
+    stack: &mut Stack,
+    operator: &HashOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    let data = pop_hashable(stack)?;
+    let digest = match operator {
+        HashOperator::Hash160 => Ripemd160::digest(Sha256::digest(&data)).to_vec(),
+        HashOperator::Hash256 => Sha256::digest(Sha256::digest(&data)).to_vec(),
+        HashOperator::Ripemd160 => Ripemd160::digest(&data).to_vec(),
+        HashOperator::Sha256 => Sha256::digest(&data).to_vec(),
+    };
+    stack.push(Value::Bytes(digest));
+
+    Ok(())
+}
+
+/// Pops a value from the stack, making sure it is either `Bytes` or a `String`.
+fn pop_hashable(stack: &mut Stack) -> Result<Vec<u8>, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::Bytes(bytes) => Ok(bytes),
+        Value::String(string) => Ok(string.into_bytes()),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "bytes",
+            found: other.type_name(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::Value::*;
+    use crate::op_systems::hash::{hash_op_sys, HashOperator};
+    use crate::op_systems::simple_math::{simple_math_op_sys, MathOperator};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::prelude::*;
+    use crate::test_utils::hex;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_digests() {
+        for (operator, input, expected) in [
+            (
+                HashOperator::Sha256,
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                HashOperator::Sha256,
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                HashOperator::Ripemd160,
+                "",
+                "9c1185a5c5e9fc54612808977ee8f548b2258d31",
+            ),
+            (
+                HashOperator::Ripemd160,
+                "abc",
+                "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc",
+            ),
+            (
+                HashOperator::Hash160,
+                "",
+                "b472a266d0bd89c13706a4132ccfb16f7c3b9fcb",
+            ),
+            (
+                HashOperator::Hash256,
+                "",
+                "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456",
+            ),
+        ] {
+            let machine = &mut Machine::new(&hash_op_sys);
+
+            let result = machine
+                .run_script(&Vec::from([
+                    Value(String(input.into())),
+                    Operator(operator),
+                ]))
+                .unwrap();
+            assert_eq!(result, Some(&Bytes(hex(expected))));
+        }
+    }
+
+    #[test]
+    fn test_strings_and_bytes_hash_alike() {
+        let machine = &mut Machine::new(&hash_op_sys);
+
+        let from_string = machine
+            .run_script(&Vec::from([
+                Value(String("abc".into())),
+                Operator(HashOperator::Sha256),
+            ]))
+            .unwrap()
+            .cloned();
+        let from_bytes = machine
+            .run_script(&Vec::from([
+                Value(Bytes(b"abc".to_vec())),
+                Operator(HashOperator::Sha256),
+            ]))
+            .unwrap()
+            .cloned();
+        assert_eq!(from_string, from_bytes);
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        let machine = &mut Machine::new(&hash_op_sys);
+
+        let result = machine.run_script(&Vec::from([
+            Value(Integer(1)),
+            Operator(HashOperator::Sha256),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 1,
+                error: OperatorError::TypeMismatch {
+                    expected: "bytes",
+                    found: "integer"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_hashlock() {
+        #[derive(Debug, PartialEq, Eq)]
+        enum HashlockOperator {
+            Hash(HashOperator),
+            Math(MathOperator),
+        }
+
+        let op_sys = |stack: &mut Stack,
+                      operator: &HashlockOperator,
+                      if_stack: &mut ConditionStack|
+         -> Result<(), OperatorError> {
+            match operator {
+                HashlockOperator::Hash(operator) => hash_op_sys(stack, operator, if_stack),
+                HashlockOperator::Math(operator) => simple_math_op_sys(stack, operator, if_stack),
+            }
+        };
+
+        // The unlocking script pushes the preimage, and the locking script is
+        // `SHA256 <digest> EQUAL`, where the digest is that of "abc".
+        let script = |preimage: &[u8]| {
+            Vec::from([
+                Value(Bytes(preimage.into())),
+                Operator(HashlockOperator::Hash(HashOperator::Sha256)),
+                Value(Bytes(hex(
+                    "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                ))),
+                Operator(HashlockOperator::Math(MathOperator::Equal)),
+            ])
+        };
+
+        // Unlocking with the right preimage.
+        let machine = &mut Machine::new(op_sys);
+        let result = machine.run_script(&script(b"abc")).unwrap();
+        assert_eq!(result, Some(&Boolean(true)));
+
+        // Unlocking with a wrong preimage.
+        let machine = &mut Machine::new(op_sys);
+        let result = machine.run_script(&script(b"abd")).unwrap();
+        assert_eq!(result, Some(&Boolean(false)));
+    }
+}
@@ -0,0 +1,291 @@
+//! Operators for boolean logic.
+//!
+//! Unlike the `Add` and `Mul` implementations for [`Value`][Value], which respectively act as OR
+//! and AND on booleans as a side effect of their arithmetic nature, the operators in this module
+//! have explicit boolean semantics:
+//!
+//! - `And`, `Or`, `Xor` and `Not` only operate on `Boolean` values, and fail with an
+//!   [`OperatorError::TypeMismatch`][TypeMismatch] for any other type.
+//! - `BoolAnd`, `BoolOr`, `Verify` take values of any type, and interpret them according to their
+//!   [truthiness][is_truthy], similarly to how `OP_BOOLAND`, `OP_BOOLOR` and `OP_VERIFY` cast their
+//!   operands in Bitcoin Script.
+//!
+//! All operators evaluate all of their operands, i.e. there is no short-circuiting.
+//!
+//! `Abort`, `Assert`, `Return` and `Verify` allow scripts to fail deliberately, e.g. for rejecting
+//! some input in a validation script. `Abort` and `Assert` take a message value of any type that
+//! tells the reason for the failure, which can be later retrieved from the error with
+//! [`OperatorError::message`][message].
+//!
+//! [Value]: ../../core/value/enum.Value.html
+//! [TypeMismatch]: ../enum.OperatorError.html#variant.TypeMismatch
+//! [is_truthy]: ../../core/value/enum.Value.html#method.is_truthy
+//! [message]: ../enum.OperatorError.html#method.message
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Operators for boolean logic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LogicOperator {
+    /// Pops a message value of any type, and fails with
+    /// [`OperatorError::Aborted`][Aborted] carrying it.
+    ///
+    /// [Aborted]: ../enum.OperatorError.html#variant.Aborted
+    Abort,
+    /// Conjunction of two booleans (`a && b`).
+    And,
+    /// Pops a message value of any type and then a value of any type, and fails with
+    /// [`OperatorError::AssertionFailed`][AssertionFailed] carrying the message if the latter is
+    /// not truthy.
+    ///
+    /// [AssertionFailed]: ../enum.OperatorError.html#variant.AssertionFailed
+    Assert,
+    /// Conjunction of the truthiness of two values of any type (`truthy(a) && truthy(b)`).
+    BoolAnd,
+    /// Disjunction of the truthiness of two values of any type (`truthy(a) || truthy(b)`).
+    BoolOr,
+    /// Negation of a boolean (`!a`).
+    Not,
+    /// Disjunction of two booleans (`a || b`).
+    Or,
+    /// Marks the script as invalid, failing with [`OperatorError::Returned`][Returned].
+    ///
+    /// [Returned]: ../enum.OperatorError.html#variant.Returned
+    Return,
+    /// Pops a value of any type, and fails with
+    /// [`OperatorError::VerificationFailed`][VerificationFailed] if it is not truthy.
+    ///
+    /// [VerificationFailed]: ../enum.OperatorError.html#variant.VerificationFailed
+    Verify,
+    /// Exclusive disjunction of two booleans (`a ^ b`).
+    Xor,
+}
+
+/// An operator system that decides how each of the variants of [`LogicOperator`][LogicOperator]
+/// trigger push and pulls on the [`Stack`][Stack] inside a [`Machine`][Machine].
+///
+/// [LogicOperator]: enum.LogicOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn logic_op_sys(
+    stack: &mut Stack,
+    operator: &LogicOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    match operator {
+        LogicOperator::Abort => {
+            let message = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            return Err(OperatorError::Aborted(message));
+        }
+        LogicOperator::And => {
+            let a = pop_boolean(stack)?;
+            let b = pop_boolean(stack)?;
+            stack.push(Value::Boolean(a && b));
+        }
+        LogicOperator::Assert => {
+            let message = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let x = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            if !x.is_truthy() {
+                return Err(OperatorError::AssertionFailed(message));
+            }
+        }
+        LogicOperator::BoolAnd => {
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push(Value::Boolean(a.is_truthy() && b.is_truthy()));
+        }
+        LogicOperator::BoolOr => {
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push(Value::Boolean(a.is_truthy() || b.is_truthy()));
+        }
+        LogicOperator::Not => {
+            let x = pop_boolean(stack)?;
+            stack.push(Value::Boolean(!x));
+        }
+        LogicOperator::Or => {
+            let a = pop_boolean(stack)?;
+            let b = pop_boolean(stack)?;
+            stack.push(Value::Boolean(a || b));
+        }
+        LogicOperator::Return => return Err(OperatorError::Returned),
+        LogicOperator::Verify => {
+            let x = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            if !x.is_truthy() {
+                return Err(OperatorError::VerificationFailed);
+            }
+        }
+        LogicOperator::Xor => {
+            let a = pop_boolean(stack)?;
+            let b = pop_boolean(stack)?;
+            stack.push(Value::Boolean(a ^ b));
+        }
+    }
+
+    Ok(())
+}
+
+/// Pops a value from the stack, making sure it is a `Boolean`.
+fn pop_boolean(stack: &mut Stack) -> Result<bool, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::Boolean(boolean) => Ok(boolean),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "boolean",
+            found: other.type_name(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::Value::*;
+    use crate::op_systems::logic::{logic_op_sys, LogicOperator::*};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::prelude::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_truth_tables() {
+        for (operator, expected) in [
+            (And, [false, false, false, true]),
+            (Or, [false, true, true, true]),
+            (Xor, [false, true, true, false]),
+            (BoolAnd, [false, false, false, true]),
+            (BoolOr, [false, true, true, true]),
+        ] {
+            for (i, (a, b)) in [(false, false), (false, true), (true, false), (true, true)]
+                .into_iter()
+                .enumerate()
+            {
+                let machine = &mut Machine::new(&logic_op_sys);
+                let result = machine
+                    .run_script(&Vec::from([
+                        Value(Boolean(a)),
+                        Value(Boolean(b)),
+                        Operator(operator.clone()),
+                    ]))
+                    .unwrap();
+
+                assert_eq!(result, Some(&Boolean(expected[i])));
+            }
+        }
+    }
+
+    #[test]
+    fn test_not() {
+        let machine = &mut Machine::new(&logic_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([Value(Boolean(false)), Operator(Not)]))
+            .unwrap();
+        assert_eq!(result, Some(&Boolean(true)));
+
+        let result = machine.run_script(&Vec::from([Value(Integer(0)), Operator(Not)]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 1,
+                error: OperatorError::TypeMismatch {
+                    expected: "boolean",
+                    found: "integer"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_truthiness() {
+        let machine = &mut Machine::new(&logic_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Integer(2)),
+                Value(String("".into())),
+                Operator(BoolOr),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&Boolean(true)));
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Integer(2)),
+                Value(Float(0.)),
+                Operator(BoolAnd),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&Boolean(false)));
+    }
+
+    #[test]
+    fn test_verify_and_return() {
+        let machine = &mut Machine::new(&logic_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([Value(Integer(1)), Operator(Verify)]))
+            .unwrap();
+        assert_eq!(result, None);
+
+        let result = machine.run_script(&Vec::from([Value(Null), Operator(Verify)]));
+        assert_eq!(
+            result.map_err(|failure| failure.error),
+            Err(OperatorError::VerificationFailed)
+        );
+
+        let result = machine.run_script(&Vec::from([Operator(Return)]));
+        assert_eq!(
+            result.map_err(|failure| failure.error),
+            Err(OperatorError::Returned)
+        );
+    }
+
+    #[test]
+    fn test_assert_and_abort() {
+        let machine = &mut Machine::new(&logic_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Integer(1)),
+                Value(String("Unreachable".into())),
+                Operator(Assert),
+            ]))
+            .unwrap();
+        assert_eq!(result, None);
+
+        let result = machine.run_script(&Vec::from([
+            Value(Boolean(true)),
+            Operator(Verify),
+            Value(Boolean(false)),
+            Value(Integer(42)),
+            Operator(Assert),
+            Operator(Return),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 4,
+                error: OperatorError::AssertionFailed(Integer(42))
+            })
+        );
+
+        let result = machine.run_script(&Vec::from([
+            Value(String("Expired".into())),
+            Operator(Abort),
+            Value(Integer(1)),
+        ]));
+        let failure = result.unwrap_err();
+        assert_eq!(failure.index, 1);
+        assert_eq!(failure.error.message(), Some(&String("Expired".into())));
+
+        let result = machine.run_script(&Vec::from([Operator(Return)]));
+        assert_eq!(result.unwrap_err().error.message(), None);
+    }
+}
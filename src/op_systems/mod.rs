@@ -1,2 +1,134 @@
+use alloc::string::String;
+
+use crate::core::value::{ConversionError, Value};
+use crate::prelude::Error;
+
+#[cfg(feature = "bitcoin")]
+pub mod bitcoin;
+pub mod bitwise;
+pub mod conversion;
+pub mod flow_control;
+pub mod forth;
+#[cfg(feature = "crypto")]
+pub mod hash;
+pub mod logic;
+pub mod null;
 pub mod pokemon;
+#[cfg(feature = "signatures")]
+pub mod signature;
 pub mod simple_math;
+pub mod string;
+pub mod time;
+
+/// The error type returned by the operator systems in this module.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OperatorError {
+    /// An operator tried to pop a value from an empty [`Stack`][Stack].
+    ///
+    /// [Stack]: ../core/stack/struct.Stack.html
+    StackUnderflow,
+    /// A value could not be converted into another type.
+    Conversion(ConversionError),
+    /// A flow control operator such as `Else` or `EndIf` was found without a matching `If`.
+    UnbalancedConditional,
+    /// An operator found a value of a type other than the one it expected.
+    TypeMismatch {
+        /// The [`type_name`][type_name] of the expected value.
+        ///
+        /// [type_name]: ../core/value/enum.Value.html#method.type_name
+        expected: &'static str,
+        /// The [`type_name`][type_name] of the value that was found instead.
+        ///
+        /// [type_name]: ../core/value/enum.Value.html#method.type_name
+        found: &'static str,
+    },
+    /// An index or length argument was negative or exceeded the bounds of the value being operated.
+    OutOfRange,
+    /// A division or modulo operator found a zero divisor.
+    DivisionByZero,
+    /// A `Format` operator found a format string with a brace that is neither part of a `{}`
+    /// placeholder nor escaped as `{{` or `}}`.
+    InvalidFormat,
+    /// An arithmetic operator found values of types it cannot operate, e.g. a string and an
+    /// integer for an addition, or bytes for a negation.
+    UnsupportedOperands {
+        /// The [`type_name`][type_name] of the first operand.
+        ///
+        /// [type_name]: ../core/value/enum.Value.html#method.type_name
+        first: &'static str,
+        /// The [`type_name`][type_name] of the second operand, or `None` for unary operators.
+        ///
+        /// [type_name]: ../core/value/enum.Value.html#method.type_name
+        second: Option<&'static str>,
+    },
+    /// The result of an arithmetic operator does not fit in the type of its operands.
+    Overflow,
+    /// A `Verify` operator found a value that is not truthy.
+    VerificationFailed,
+    /// A `Return` operator was found, which marks the script as invalid.
+    Returned,
+    /// An `Assert` operator found a value that is not truthy. Carries the message value telling the
+    /// reason for the failure.
+    AssertionFailed(Value),
+    /// An `Abort` operator was found. Carries the message value telling the reason for the failure.
+    Aborted(Value),
+    /// Any other error, described in free text.
+    Other(String),
+}
+
+impl OperatorError {
+    /// The message value telling the reason for a deliberate failure, if any, as provided to the
+    /// `Abort` and `Assert` operators.
+    pub fn message(&self) -> Option<&Value> {
+        match self {
+            OperatorError::AssertionFailed(message) | OperatorError::Aborted(message) => {
+                Some(message)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for OperatorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            OperatorError::StackUnderflow => write!(f, "Stack underflow"),
+            OperatorError::Conversion(error) => error.fmt(f),
+            OperatorError::UnbalancedConditional => write!(f, "Unbalanced conditional"),
+            OperatorError::TypeMismatch { expected, found } => write!(
+                f,
+                "Expected a value of type {} but found {}",
+                expected, found
+            ),
+            OperatorError::OutOfRange => write!(f, "Argument out of range"),
+            OperatorError::DivisionByZero => write!(f, "Division by zero"),
+            OperatorError::InvalidFormat => write!(f, "Invalid format string"),
+            OperatorError::UnsupportedOperands {
+                first,
+                second: None,
+            } => write!(f, "Unsupported operand of type {}", first),
+            OperatorError::UnsupportedOperands {
+                first,
+                second: Some(second),
+            } => write!(f, "Unsupported operands of types {} and {}", first, second),
+            OperatorError::Overflow => write!(f, "Arithmetic overflow"),
+            OperatorError::VerificationFailed => write!(f, "Verification failed"),
+            OperatorError::Returned => write!(f, "Script returned early"),
+            OperatorError::AssertionFailed(message) => write!(f, "Assertion failed: {}", message),
+            OperatorError::Aborted(message) => write!(f, "Aborted: {}", message),
+            OperatorError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for OperatorError {
+    fn from_str(input: &str) -> Self {
+        OperatorError::Other(input.into())
+    }
+}
+
+impl From<ConversionError> for OperatorError {
+    fn from(input: ConversionError) -> Self {
+        OperatorError::Conversion(input)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Operators for dealing with optional values, i.e. values that may be [`Null`][Null].
+///
+/// [Null]: ../../core/value/enum.Value.html#variant.Null
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum NullOperator {
+    /// Replaces a `Null` with a fallback value (`a ?? b`).
+    ///
+    /// Pops the fallback value `b` first, and then the value `a`. If `a` is `Null`, `b` is pushed.
+    /// Otherwise, `a` is pushed back.
+    Coalesce,
+    /// Tells whether a value is `Null` (`a == null`).
+    IsNull,
+}
+
+/// A simple operator system that decides how each of the variants of [`NullOperator`][NullOperator]
+/// trigger push and pulls on the [`Stack`][Stack] inside a [`Machine`][Machine].
+///
+/// [NullOperator]: enum.NullOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn null_op_sys(
+    stack: &mut Stack,
+    operator: &NullOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    match operator {
+        NullOperator::Coalesce => {
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push(match a {
+                Value::Null => b,
+                a => a,
+            });
+        }
+        NullOperator::IsNull => {
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push(Value::Boolean(a == Value::Null));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::Value::*;
+    use crate::op_systems::null::{null_op_sys, NullOperator};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::prelude::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_is_null() {
+        let machine = &mut Machine::new(&null_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([Value(Null), Operator(NullOperator::IsNull)]))
+            .unwrap();
+        assert_eq!(result, Some(&Boolean(true)));
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Integer(0)),
+                Operator(NullOperator::IsNull),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&Boolean(false)));
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let machine = &mut Machine::new(&null_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Null),
+                Value(String("fallback".into())),
+                Operator(NullOperator::Coalesce),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&String("fallback".into())));
+        assert_eq!(machine.stack_length(), 1);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Integer(1)),
+                Value(Integer(2)),
+                Operator(NullOperator::Coalesce),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&Integer(1)));
+        assert_eq!(machine.stack_length(), 2);
+    }
+
+    #[test]
+    fn test_stack_underflow() {
+        let machine = &mut Machine::new(&null_op_sys);
+
+        let result = machine.run_script(&Vec::from([Operator(NullOperator::IsNull)]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 0,
+                error: OperatorError::StackUnderflow
+            })
+        );
+    }
+}
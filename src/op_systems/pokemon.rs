@@ -7,6 +7,7 @@
 //! [Creature]: enum.Creature.html
 //! [Command]: enum.Command.html
 
+use crate::op_systems::OperatorError;
 use crate::prelude::*;
 
 /// Simply the first nine Pokémon.
@@ -35,9 +36,13 @@ pub enum Command {
 }
 
 /// The main function that tells which creatures evolute and devolute into which other creatures.
-pub fn pokemon_op_sys(stack: &mut Stack<Creature>, operator: &Command) {
+pub fn pokemon_op_sys(
+    stack: &mut Stack<Creature>,
+    operator: &Command,
+    _if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
     use Creature::*;
-    let last_creature = stack.pop().unwrap();
+    let last_creature = stack.pop().ok_or(OperatorError::StackUnderflow)?;
     match operator {
         Command::Evolute => stack.push(match last_creature {
             Bulbasaur => Ivysaur,
@@ -59,6 +64,8 @@ pub fn pokemon_op_sys(stack: &mut Stack<Creature>, operator: &Command) {
         }),
         Command::Close => {}
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -78,7 +85,7 @@ mod tests {
         let result = machine.operate(&Item::Value(my_creature)).unwrap();
 
         // There should obviously be a Charmander in the machine
-        assert_eq!(result, &Charmander);
+        assert_eq!(result, Some(&Charmander));
         // And there should be nothing else in the machine
         assert_eq!(machine.stack_length(), 1);
 
@@ -86,7 +93,7 @@ mod tests {
         let result = machine.operate(&Item::Operator(Evolute)).unwrap();
 
         // Charmander should have turned into Charmaleon!
-        assert_eq!(result, &Charmaleon);
+        assert_eq!(result, Some(&Charmaleon));
         // And again there should be only 1 creature in the machine
         assert_eq!(machine.stack_length(), 1);
 
@@ -94,17 +101,17 @@ mod tests {
         let result = machine.operate(&Item::Operator(Evolute)).unwrap();
 
         // Meet our blazing Charizard!
-        assert_eq!(result, &Charizard);
+        assert_eq!(result, Some(&Charizard));
 
         // What if we try to evolute Charizard?
         let result = machine.operate(&Item::Operator(Evolute)).unwrap();
 
         // Good try... but it should still be a Charizard
-        assert_eq!(result, &Charizard);
+        assert_eq!(result, Some(&Charizard));
 
         // Ok, we already got Charizard, let's just close the machine and make sure we don't leave
         // any creature behind
-        machine.operate(&Item::Operator(Close));
+        machine.operate(&Item::Operator(Close)).unwrap();
         assert_eq!(machine.stack_length(), 0);
     }
 
@@ -120,7 +127,7 @@ mod tests {
         let result = machine.operate(&Item::Value(my_creature)).unwrap();
 
         // There should obviously be a Blastoise in the machine
-        assert_eq!(result, &Blastoise);
+        assert_eq!(result, Some(&Blastoise));
         // And there should be nothing else in the machine
         assert_eq!(machine.stack_length(), 1);
 
@@ -128,7 +135,7 @@ mod tests {
         let result = machine.operate(&Item::Operator(Devolute)).unwrap();
 
         // Blastoise should have turned into Wartortle!
-        assert_eq!(result, &Wartortle);
+        assert_eq!(result, Some(&Wartortle));
         // And again there should be only 1 creature in the machine
         assert_eq!(machine.stack_length(), 1);
 
@@ -136,17 +143,17 @@ mod tests {
         let result = machine.operate(&Item::Operator(Devolute)).unwrap();
 
         // Meet our lovely Squirtle!
-        assert_eq!(result, &Squirtle);
+        assert_eq!(result, Some(&Squirtle));
 
         // What if we try to devolute Squirtle?
         let result = machine.operate(&Item::Operator(Devolute)).unwrap();
 
         // Good try... but it should still be a Squirtle
-        assert_eq!(result, &Squirtle);
+        assert_eq!(result, Some(&Squirtle));
 
         // Ok, we already got Squirtle, let's just close the machine and make sure we don't leave
         // any creature behind
-        machine.operate(&Item::Operator(Close));
+        machine.operate(&Item::Operator(Close)).unwrap();
         assert_eq!(machine.stack_length(), 0);
     }
 }
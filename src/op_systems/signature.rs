@@ -0,0 +1,470 @@
+//! Signature verification operators, as found in Bitcoin Script.
+//!
+//! Unlike other operator systems, [`signature_op_sys`][signature_op_sys] takes an additional
+//! [`SignatureContext`][SignatureContext] argument, through which the host application provides
+//! the message that signatures are checked against (e.g. the transaction being spent), and the
+//! [`SignatureScheme`][SignatureScheme] in use. As a result, it needs to be wrapped into a closure
+//! before being handed to a [`Machine`][Machine]:
+//!
+//! ```rust
+//! use scriptful::prelude::*;
+//! use scriptful::core::value::Value::*;
+//! use scriptful::op_systems::signature::*;
+//!
+//! let context = SignatureContext::new(SignatureScheme::Ed25519, b"");
+//! let mut machine = Machine::new(|stack: &mut Stack, operator: &SignatureOperator, if_stack: &mut ConditionStack| {
+//!     signature_op_sys(stack, operator, if_stack, &context)
+//! });
+//!
+//! // An empty signature never verifies.
+//! let result = machine.run_script(&[
+//!     Item::Value(Bytes(Vec::new())),
+//!     Item::Value(Bytes(Vec::from([0u8; 32]))),
+//!     Item::Operator(SignatureOperator::CheckSig),
+//! ]);
+//!
+//! assert_eq!(result, Ok(Some(&Boolean(false))));
+//! ```
+//!
+//! Signatures and public keys must be `Bytes` values, otherwise the operators fail with an
+//! [`OperatorError::TypeMismatch`][TypeMismatch]. Malformed signatures and public keys, however,
+//! are not an error but simply fail to verify.
+//!
+//! This module is only available when the `signatures` feature is enabled. Signature verification
+//! is provided by the pure-Rust, `no_std` [`ed25519-dalek`][ed25519-dalek] and [`k256`][k256]
+//! crates.
+//!
+//! [signature_op_sys]: fn.signature_op_sys.html
+//! [SignatureContext]: struct.SignatureContext.html
+//! [SignatureScheme]: enum.SignatureScheme.html
+//! [Machine]: ../../core/machine/struct.Machine.html
+//! [TypeMismatch]: ../enum.OperatorError.html#variant.TypeMismatch
+//! [ed25519-dalek]: https://docs.rs/ed25519-dalek
+//! [k256]: https://docs.rs/k256
+
+use alloc::vec::Vec;
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// The maximum number of public keys that a `CheckMultiSig` operator can take, as in Bitcoin.
+pub const MAX_PUBKEYS_PER_MULTISIG: usize = 20;
+
+/// Signature verification operators.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SignatureOperator {
+    /// Checks `m` signatures against `n` public keys (`OP_CHECKMULTISIG`).
+    ///
+    /// `( sig_1 ... sig_m m pubkey_1 ... pubkey_n n -- boolean )`
+    ///
+    /// Signatures must appear in the same order as the public keys they were made with. Unlike in
+    /// Bitcoin, no extra dummy value is popped from the stack.
+    CheckMultiSig,
+    /// Checks a signature against a public key (`OP_CHECKSIG`).
+    ///
+    /// `( sig pubkey -- boolean )`
+    CheckSig,
+    /// Same as `CheckSig`, but fails with
+    /// [`OperatorError::VerificationFailed`][VerificationFailed] instead of pushing `false`
+    /// (`OP_CHECKSIGVERIFY`).
+    ///
+    /// `( sig pubkey -- )`
+    ///
+    /// [VerificationFailed]: ../enum.OperatorError.html#variant.VerificationFailed
+    CheckSigVerify,
+}
+
+/// The digital signature schemes supported by [`signature_op_sys`][signature_op_sys].
+///
+/// [signature_op_sys]: fn.signature_op_sys.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SignatureScheme {
+    /// Ed25519, as specified in [RFC 8032](https://www.rfc-editor.org/rfc/rfc8032).
+    ///
+    /// Public keys are 32 bytes long, and signatures are 64 bytes long.
+    Ed25519,
+    /// Schnorr signatures over secp256k1, as specified in
+    /// [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki).
+    ///
+    /// Public keys are 32 bytes long (x-only), and signatures are 64 bytes long.
+    Schnorr,
+}
+
+/// The information that the host application provides to
+/// [`signature_op_sys`][signature_op_sys] for checking signatures.
+///
+/// [signature_op_sys]: fn.signature_op_sys.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureContext<'a> {
+    /// The signature scheme in use.
+    pub scheme: SignatureScheme,
+    /// The message that signatures are checked against.
+    pub message: &'a [u8],
+}
+
+impl<'a> SignatureContext<'a> {
+    /// Creates a context for checking signatures made with `scheme` on `message`.
+    pub fn new(scheme: SignatureScheme, message: &'a [u8]) -> Self {
+        Self { scheme, message }
+    }
+
+    /// Tells whether `signature` is a valid signature of the message in this context by the owner
+    /// of `public_key`.
+    pub fn verify(&self, signature: &[u8], public_key: &[u8]) -> bool {
+        match self.scheme {
+            SignatureScheme::Ed25519 => {
+                use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+                let Ok(public_key) = VerifyingKey::try_from(public_key) else {
+                    return false;
+                };
+                let Ok(signature) = Signature::try_from(signature) else {
+                    return false;
+                };
+
+                public_key.verify(self.message, &signature).is_ok()
+            }
+            SignatureScheme::Schnorr => {
+                use k256::schnorr::{Signature, VerifyingKey};
+
+                let Ok(public_key) = VerifyingKey::from_bytes(public_key) else {
+                    return false;
+                };
+                let Ok(signature) = Signature::try_from(signature) else {
+                    return false;
+                };
+
+                public_key.verify_raw(self.message, &signature).is_ok()
+            }
+        }
+    }
+}
+
+/// An operator system that decides how each of the variants of
+/// [`SignatureOperator`][SignatureOperator] trigger push and pulls on the [`Stack`][Stack] inside a
+/// [`Machine`][Machine], checking signatures according to a [`SignatureContext`][SignatureContext].
+///
+/// [SignatureOperator]: enum.SignatureOperator.html
+/// [SignatureContext]: struct.SignatureContext.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn signature_op_sys(
+    stack: &mut Stack,
+    operator: &SignatureOperator,
+    if_stack: &mut ConditionStack,
+    context: &SignatureContext,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    match operator {
+        SignatureOperator::CheckMultiSig => {
+            let n = pop_count(stack, MAX_PUBKEYS_PER_MULTISIG)?;
+            let mut public_keys = (0..n)
+                .map(|_| pop_bytes(stack))
+                .collect::<Result<Vec<_>, _>>()?;
+            let m = pop_count(stack, n)?;
+            let mut signatures = (0..m)
+                .map(|_| pop_bytes(stack))
+                .collect::<Result<Vec<_>, _>>()?;
+            public_keys.reverse();
+            signatures.reverse();
+
+            // Every signature is matched against the first of the remaining public keys that
+            // verifies it, so keys that have been skipped cannot be used by later signatures.
+            let mut public_keys = public_keys.iter();
+            let success = signatures.iter().all(|signature| {
+                public_keys.any(|public_key| context.verify(signature, public_key))
+            });
+            stack.push(Value::Boolean(success));
+        }
+        SignatureOperator::CheckSig => {
+            let public_key = pop_bytes(stack)?;
+            let signature = pop_bytes(stack)?;
+            stack.push(Value::Boolean(context.verify(&signature, &public_key)));
+        }
+        SignatureOperator::CheckSigVerify => {
+            let public_key = pop_bytes(stack)?;
+            let signature = pop_bytes(stack)?;
+            if !context.verify(&signature, &public_key) {
+                return Err(OperatorError::VerificationFailed);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Pops a value from the stack, making sure it is `Bytes`.
+fn pop_bytes(stack: &mut Stack) -> Result<Vec<u8>, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::Bytes(bytes) => Ok(bytes),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "bytes",
+            found: other.type_name(),
+        }),
+    }
+}
+
+/// Pops a value from the stack, making sure it is an `Integer` in the `0..=max` range.
+fn pop_count(stack: &mut Stack, max: usize) -> Result<usize, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::Integer(count) => usize::try_from(count)
+            .ok()
+            .filter(|count| *count <= max)
+            .ok_or(OperatorError::OutOfRange),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "integer",
+            found: other.type_name(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::value::Value::*;
+    use crate::op_systems::signature::*;
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::test_utils::hex;
+
+    fn run(
+        context: &SignatureContext,
+        script: &[Item<SignatureOperator>],
+    ) -> Result<Option<crate::core::value::Value>, OperatorError> {
+        let mut machine = Machine::new(
+            |stack: &mut Stack, operator: &SignatureOperator, if_stack: &mut ConditionStack| {
+                signature_op_sys(stack, operator, if_stack, context)
+            },
+        );
+
+        machine
+            .run_script(script)
+            .map(|top| top.cloned())
+            .map_err(|failure| failure.error)
+    }
+
+    /// Test vectors from RFC 8032, section 7.1: (public key, message, signature).
+    const ED25519_VECTORS: [(&str, &str, &str); 3] = [
+        (
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+        (
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        ),
+    ];
+
+    /// Test vectors from BIP-340: (public key, message, signature, expected result).
+    const SCHNORR_VECTORS: [(&str, &str, &str, bool); 5] = [
+        (
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            true,
+        ),
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            true,
+        ),
+        (
+            "dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8",
+            "7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c",
+            "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1bab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7",
+            true,
+        ),
+        // Public key not on the curve.
+        (
+            "eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e17776969e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+            false,
+        ),
+        // `has_even_y(R)` is false.
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a14602975563cc27944640ac607cd107ae10923d9ef7a73c643e166be5ebeafa34b1ac553e2",
+            false,
+        ),
+    ];
+
+    #[test]
+    fn test_check_sig_ed25519() {
+        for (public_key, message, signature) in ED25519_VECTORS {
+            let message = hex(message);
+            let context = SignatureContext::new(SignatureScheme::Ed25519, &message);
+
+            let result = run(
+                &context,
+                &[
+                    Value(Bytes(hex(signature))),
+                    Value(Bytes(hex(public_key))),
+                    Operator(SignatureOperator::CheckSig),
+                ],
+            );
+            assert_eq!(result, Ok(Some(Boolean(true))));
+
+            // The same signature does not verify for a different message.
+            let context = SignatureContext::new(SignatureScheme::Ed25519, b"tampered");
+            let result = run(
+                &context,
+                &[
+                    Value(Bytes(hex(signature))),
+                    Value(Bytes(hex(public_key))),
+                    Operator(SignatureOperator::CheckSig),
+                ],
+            );
+            assert_eq!(result, Ok(Some(Boolean(false))));
+        }
+    }
+
+    #[test]
+    fn test_check_sig_schnorr() {
+        for (public_key, message, signature, expected) in SCHNORR_VECTORS {
+            let message = hex(message);
+            let context = SignatureContext::new(SignatureScheme::Schnorr, &message);
+
+            let result = run(
+                &context,
+                &[
+                    Value(Bytes(hex(signature))),
+                    Value(Bytes(hex(public_key))),
+                    Operator(SignatureOperator::CheckSig),
+                ],
+            );
+            assert_eq!(result, Ok(Some(Boolean(expected))));
+        }
+    }
+
+    #[test]
+    fn test_check_sig_verify() {
+        let (public_key, message, signature) = ED25519_VECTORS[1];
+        let message = hex(message);
+        let context = SignatureContext::new(SignatureScheme::Ed25519, &message);
+
+        let result = run(
+            &context,
+            &[
+                Value(Bytes(hex(signature))),
+                Value(Bytes(hex(public_key))),
+                Operator(SignatureOperator::CheckSigVerify),
+            ],
+        );
+        assert_eq!(result, Ok(None));
+
+        // Schnorr verification of an Ed25519 signature must fail.
+        let context = SignatureContext::new(SignatureScheme::Schnorr, &message);
+        let result = run(
+            &context,
+            &[
+                Value(Bytes(hex(signature))),
+                Value(Bytes(hex(public_key))),
+                Operator(SignatureOperator::CheckSigVerify),
+            ],
+        );
+        assert_eq!(result, Err(OperatorError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_check_multisig() {
+        // All the BIP-340 vectors with 32 bytes messages share the same message, so they can be
+        // combined into a multisig script.
+        let message = hex(SCHNORR_VECTORS[1].1);
+        let context = SignatureContext::new(SignatureScheme::Schnorr, &message);
+        let (alice, _, alice_signature, _) = SCHNORR_VECTORS[1];
+        let (bob, _, _, _) = SCHNORR_VECTORS[2];
+        let (carol, _, _, _) = SCHNORR_VECTORS[0];
+
+        let script = |signatures: &[&str], public_keys: &[&str]| {
+            let mut script = Vec::new();
+            script.extend(signatures.iter().map(|sig| Value(Bytes(hex(sig)))));
+            script.push(Value(Integer(signatures.len() as i128)));
+            script.extend(public_keys.iter().map(|key| Value(Bytes(hex(key)))));
+            script.push(Value(Integer(public_keys.len() as i128)));
+            script.push(Operator(SignatureOperator::CheckMultiSig));
+            script
+        };
+
+        // 1-of-3, with the signing key in any position.
+        for public_keys in [
+            [alice, bob, carol],
+            [bob, alice, carol],
+            [bob, carol, alice],
+        ] {
+            let result = run(&context, &script(&[alice_signature], &public_keys));
+            assert_eq!(result, Ok(Some(Boolean(true))));
+        }
+
+        // 1-of-2, without the signing key.
+        let result = run(&context, &script(&[alice_signature], &[bob, carol]));
+        assert_eq!(result, Ok(Some(Boolean(false))));
+
+        // 2-of-3, reusing the same signature twice.
+        let result = run(
+            &context,
+            &script(&[alice_signature, alice_signature], &[alice, bob, carol]),
+        );
+        assert_eq!(result, Ok(Some(Boolean(false))));
+
+        // 0-of-0 trivially succeeds.
+        let result = run(&context, &script(&[], &[]));
+        assert_eq!(result, Ok(Some(Boolean(true))));
+
+        // More signatures than public keys.
+        let result = run(
+            &context,
+            &script(&[alice_signature, alice_signature], &[alice]),
+        );
+        assert_eq!(result, Err(OperatorError::OutOfRange));
+    }
+
+    #[test]
+    fn test_malformed_inputs() {
+        let context = SignatureContext::new(SignatureScheme::Ed25519, b"");
+
+        // Wrong lengths simply fail to verify.
+        let result = run(
+            &context,
+            &[
+                Value(Bytes(Vec::from([0u8; 3]))),
+                Value(Bytes(Vec::from([0u8; 5]))),
+                Operator(SignatureOperator::CheckSig),
+            ],
+        );
+        assert_eq!(result, Ok(Some(Boolean(false))));
+
+        let result = run(
+            &context,
+            &[
+                Value(String("signature".into())),
+                Value(Bytes(Vec::from([0u8; 32]))),
+                Operator(SignatureOperator::CheckSig),
+            ],
+        );
+        assert_eq!(
+            result,
+            Err(OperatorError::TypeMismatch {
+                expected: "bytes",
+                found: "string"
+            })
+        );
+
+        let result = run(&context, &[Operator(SignatureOperator::CheckSig)]);
+        assert_eq!(result, Err(OperatorError::StackUnderflow));
+    }
+}
@@ -1,12 +1,16 @@
+use crate::op_systems::OperatorError;
 use crate::prelude::*;
 
 /// Frequently used mathematical operators.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum MathOperator {
     /// Addition of two numbers (`a + b`).
     Add,
-    /// Equivalence of two numbers (`a == b`).
+    /// Approximate equivalence of two values (`a == b`), as defined by the `PartialEq`
+    /// implementation for [`Value`][Value].
+    ///
+    /// [Value]: ../../core/value/enum.Value.html
     Equal,
     /// Multiplication of two numbers (`a * b`)
     Mul,
@@ -14,6 +18,11 @@ pub enum MathOperator {
     Not,
     /// Subtraction of two numbers (`a - b`).
     Sub,
+    /// Strict equivalence of two values (`a === b`), as defined by
+    /// [`CanonicalValue`][CanonicalValue].
+    ///
+    /// [CanonicalValue]: ../../core/value/struct.CanonicalValue.html
+    StrictEqual,
 }
 
 /// A simple operator system that decides how each of the variants of [`MathOperator`][MathOperator]
@@ -22,41 +31,58 @@ pub enum MathOperator {
 /// [MathOperator]: enum.MathOperator.html
 /// [Stack]: ../../core/stack/struct.Stack.html
 /// [Machine]: ../../core/machine/struct.Machine.html
-pub fn simple_math_op_sys(stack: &mut Stack, operator: &MathOperator) {
-    use crate::core::value::Value::*;
+pub fn simple_math_op_sys(
+    stack: &mut Stack,
+    operator: &MathOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    use crate::core::value::{CanonicalValue, Value::*};
 
     match operator {
         MathOperator::Add => {
-            let a = stack.pop().unwrap();
-            let b = stack.pop().unwrap();
-            stack.push(a + b);
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push((a + b)?);
         }
         MathOperator::Equal => {
-            let a = stack.pop().unwrap();
-            let b = stack.pop().unwrap();
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
             stack.push(Boolean(a == b));
         }
         MathOperator::Mul => {
-            let a = stack.pop().unwrap();
-            let b = stack.pop().unwrap();
-            stack.push(a * b);
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push((a * b)?);
         }
         MathOperator::Not => {
-            let x = stack.pop().unwrap();
-            stack.push(!x);
+            let x = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push((!x)?);
         }
         MathOperator::Sub => {
-            let a = stack.pop().unwrap();
-            let b = stack.pop().unwrap();
-            stack.push(a - b);
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push((a - b)?);
+        }
+        MathOperator::StrictEqual => {
+            let a = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            let b = stack.pop().ok_or(OperatorError::StackUnderflow)?;
+            stack.push(Boolean(CanonicalValue(a) == CanonicalValue(b)));
         }
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::core::machine::ScriptFailure;
     use crate::core::value::Value::*;
     use crate::op_systems::simple_math::{simple_math_op_sys, MathOperator};
+    use crate::op_systems::OperatorError;
     use crate::prelude::Item::*;
     use crate::prelude::*;
     use alloc::vec::Vec;
@@ -75,6 +101,72 @@ mod tests {
             ]))
             .unwrap();
 
-        assert_eq!(result, &Boolean(true));
+        assert_eq!(result, Some(&Boolean(true)));
+    }
+
+    #[test]
+    fn test_strict_equal() {
+        let machine = &mut Machine::new(&simple_math_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Integer(1)),
+                Value(Float(1.)),
+                Operator(MathOperator::Equal),
+                Value(Integer(1)),
+                Value(Float(1.)),
+                Operator(MathOperator::StrictEqual),
+            ]))
+            .unwrap();
+
+        assert_eq!(result, Some(&Boolean(false)));
+        assert_eq!(machine.stack_length(), 2);
+
+        let result = machine.operate(&Operator(MathOperator::Equal)).unwrap();
+        assert_eq!(result, Some(&Boolean(false)));
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(Float(f64::NAN)),
+                Value(Float(f64::NAN)),
+                Operator(MathOperator::StrictEqual),
+            ]))
+            .unwrap();
+
+        assert_eq!(result, Some(&Boolean(true)));
+    }
+
+    #[test]
+    fn test_arithmetic_errors() {
+        let machine = &mut Machine::new(&simple_math_op_sys);
+
+        let result = machine.run_script(&Vec::from([
+            Value(Integer(1)),
+            Value(String("foo".into())),
+            Operator(MathOperator::Add),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::UnsupportedOperands {
+                    first: "string",
+                    second: Some("integer")
+                }
+            })
+        );
+
+        let result = machine.run_script(&Vec::from([
+            Value(Integer(i128::MAX)),
+            Value(Integer(2)),
+            Operator(MathOperator::Mul),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::Overflow
+            })
+        );
     }
 }
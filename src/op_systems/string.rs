@@ -0,0 +1,418 @@
+//! Operators for manipulating [`String`][String] values.
+//!
+//! All the operators in this module are UTF-8 aware: every index and length, either taken as an
+//! argument or pushed as a result, is measured in `char`s (i.e. Unicode scalar values), and never in
+//! bytes. For example, the length of `"año"` is `3`, even if it takes 4 bytes.
+//!
+//! Operands are taken in the order in which they were pushed, so that `"foo" "bar" Concat` yields
+//! `"foobar"`. The documentation of each operator describes its effect on the stack using the
+//! `( before -- after )` notation from Forth, in which the topmost value is the rightmost one.
+//!
+//! Operating on values of any other type than the expected one results in an
+//! [`OperatorError::TypeMismatch`][TypeMismatch] instead of a panic.
+//!
+//! [String]: ../../core/value/enum.Value.html#variant.String
+//! [TypeMismatch]: ../enum.OperatorError.html#variant.TypeMismatch
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Operators for manipulating strings.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum StringOperator {
+    /// Joins two strings together: `( a b -- ab )`.
+    Concat,
+    /// Tells whether a string contains another one: `( haystack needle -- bool )`.
+    Contains,
+    /// Tells whether a string ends with another one: `( string suffix -- bool )`.
+    EndsWith,
+    /// Replaces every `{}` placeholder in a format string with the values that were pushed before
+    /// it, as formatted by their `Display` implementation: `( a b ... format -- string )`.
+    ///
+    /// The amount of values that are popped equals the amount of placeholders. Literal braces can
+    /// be written as `{{` and `}}`. Any other brace fails with
+    /// [`OperatorError::InvalidFormat`][InvalidFormat].
+    ///
+    /// [InvalidFormat]: ../enum.OperatorError.html#variant.InvalidFormat
+    Format,
+    /// Counts the `char`s in a string: `( string -- length )`.
+    Len,
+    /// Converts a string to lowercase: `( string -- lowercase )`.
+    Lower,
+    /// Replaces every occurrence of a pattern in a string: `( string from to -- replaced )`.
+    Replace,
+    /// Splits a string by a separator, pushing every part followed by the number of parts:
+    /// `( string separator -- part_1 ... part_n n )`.
+    ///
+    /// An empty separator splits the string into its individual `char`s.
+    Split,
+    /// Tells whether a string starts with another one: `( string prefix -- bool )`.
+    StartsWith,
+    /// Extracts `length` `char`s from a string, starting at the `char` index `start`:
+    /// `( string start length -- substring )`.
+    ///
+    /// Fails with [`OperatorError::OutOfRange`][OutOfRange] if the substring exceeds the bounds of
+    /// the string.
+    ///
+    /// [OutOfRange]: ../enum.OperatorError.html#variant.OutOfRange
+    Substr,
+    /// Removes leading and trailing whitespace from a string: `( string -- trimmed )`.
+    Trim,
+    /// Converts a string to uppercase: `( string -- uppercase )`.
+    Upper,
+}
+
+/// An operator system that decides how each of the variants of [`StringOperator`][StringOperator]
+/// trigger push and pulls on the [`Stack`][Stack] inside a [`Machine`][Machine].
+///
+/// [StringOperator]: enum.StringOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn string_op_sys(
+    stack: &mut Stack,
+    operator: &StringOperator,
+    if_stack: &mut ConditionStack,
+) -> Result<(), OperatorError> {
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    match operator {
+        StringOperator::Concat => {
+            let b = pop_string(stack)?;
+            let a = pop_string(stack)?;
+            stack.push(Value::String(a + &b));
+        }
+        StringOperator::Contains => {
+            let needle = pop_string(stack)?;
+            let haystack = pop_string(stack)?;
+            stack.push(Value::Boolean(haystack.contains(needle.as_str())));
+        }
+        StringOperator::EndsWith => {
+            let suffix = pop_string(stack)?;
+            let string = pop_string(stack)?;
+            stack.push(Value::Boolean(string.ends_with(suffix.as_str())));
+        }
+        StringOperator::Format => {
+            let format = pop_string(stack)?;
+            let pieces = parse_format(&format)?;
+            let mut arguments = Vec::with_capacity(pieces.len() - 1);
+            for _ in 1..pieces.len() {
+                arguments.push(stack.pop().ok_or(OperatorError::StackUnderflow)?);
+            }
+
+            let mut formatted = String::new();
+            for (piece, argument) in pieces.iter().zip(arguments.iter().rev()) {
+                formatted.push_str(piece);
+                formatted.push_str(&argument.to_string());
+            }
+            formatted.push_str(pieces.last().map(String::as_str).unwrap_or_default());
+
+            stack.push(Value::String(formatted));
+        }
+        StringOperator::Len => {
+            let string = pop_string(stack)?;
+            stack.push(Value::Integer(string.chars().count() as i128));
+        }
+        StringOperator::Lower => {
+            let string = pop_string(stack)?;
+            stack.push(Value::String(string.to_lowercase()));
+        }
+        StringOperator::Replace => {
+            let to = pop_string(stack)?;
+            let from = pop_string(stack)?;
+            let string = pop_string(stack)?;
+            stack.push(Value::String(string.replace(from.as_str(), &to)));
+        }
+        StringOperator::Split => {
+            let separator = pop_string(stack)?;
+            let string = pop_string(stack)?;
+            let parts: Vec<_> = if separator.is_empty() {
+                string.chars().map(|c| c.to_string()).collect()
+            } else {
+                string.split(separator.as_str()).map(Into::into).collect()
+            };
+            let count = parts.len();
+            for part in parts {
+                stack.push(Value::String(part));
+            }
+            stack.push(Value::Integer(count as i128));
+        }
+        StringOperator::StartsWith => {
+            let prefix = pop_string(stack)?;
+            let string = pop_string(stack)?;
+            stack.push(Value::Boolean(string.starts_with(prefix.as_str())));
+        }
+        StringOperator::Substr => {
+            let length = pop_index(stack)?;
+            let start = pop_index(stack)?;
+            let string = pop_string(stack)?;
+            let end = start.checked_add(length).ok_or(OperatorError::OutOfRange)?;
+            if end > string.chars().count() {
+                return Err(OperatorError::OutOfRange);
+            }
+            stack.push(Value::String(
+                string.chars().skip(start).take(length).collect(),
+            ));
+        }
+        StringOperator::Trim => {
+            let string = pop_string(stack)?;
+            stack.push(Value::String(string.trim().into()));
+        }
+        StringOperator::Upper => {
+            let string = pop_string(stack)?;
+            stack.push(Value::String(string.to_uppercase()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Pops a value from the stack, making sure it is a `String`.
+fn pop_string(stack: &mut Stack) -> Result<String, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::String(string) => Ok(string),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "string",
+            found: other.type_name(),
+        }),
+    }
+}
+
+/// Pops a value from the stack, making sure it is a non-negative `Integer`.
+fn pop_index(stack: &mut Stack) -> Result<usize, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::Integer(integer) => usize::try_from(integer).map_err(|_| OperatorError::OutOfRange),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "integer",
+            found: other.type_name(),
+        }),
+    }
+}
+
+/// Splits a format string by its `{}` placeholders, unescaping any `{{` and `}}` along the way.
+///
+/// The result always contains one more piece than the amount of placeholders.
+fn parse_format(format: &str) -> Result<Vec<String>, OperatorError> {
+    let mut pieces = Vec::from([String::new()]);
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                pieces.last_mut().unwrap().push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                pieces.push(String::new());
+            }
+            ('{', _) | ('}', _) => return Err(OperatorError::InvalidFormat),
+            (c, _) => pieces.last_mut().unwrap().push(c),
+        }
+    }
+
+    Ok(pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::Value::*;
+    use crate::op_systems::string::{string_op_sys, StringOperator::*};
+    use crate::op_systems::OperatorError;
+    use crate::prelude::Item::*;
+    use crate::prelude::*;
+    use alloc::vec::Vec;
+
+    fn s(input: &str) -> crate::core::value::Value {
+        String(input.into())
+    }
+
+    #[test]
+    fn test_concat_and_len() {
+        let machine = &mut Machine::new(&string_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(s("año")),
+                Value(s("🦀")),
+                Operator(Concat),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&s("año🦀")));
+
+        let result = machine.operate(&Operator(Len)).unwrap();
+        assert_eq!(result, Some(&Integer(4)));
+    }
+
+    #[test]
+    fn test_substr() {
+        let machine = &mut Machine::new(&string_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(s("¡Olé, señor!")),
+                Value(Integer(6)),
+                Value(Integer(5)),
+                Operator(Substr),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&s("señor")));
+
+        let result = machine.run_script(&Vec::from([
+            Value(s("año")),
+            Value(Integer(1)),
+            Value(Integer(3)),
+            Operator(Substr),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 3,
+                error: OperatorError::OutOfRange
+            })
+        );
+
+        let result = machine.run_script(&Vec::from([
+            Value(s("año")),
+            Value(Integer(-1)),
+            Value(Integer(1)),
+            Operator(Substr),
+        ]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 3,
+                error: OperatorError::OutOfRange
+            })
+        );
+    }
+
+    #[test]
+    fn test_case_and_trim() {
+        let machine = &mut Machine::new(&string_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([Value(s(" \tÑandú\n")), Operator(Trim)]))
+            .unwrap();
+        assert_eq!(result, Some(&s("Ñandú")));
+
+        let result = machine.operate(&Operator(Upper)).unwrap();
+        assert_eq!(result, Some(&s("ÑANDÚ")));
+
+        let result = machine.operate(&Operator(Lower)).unwrap();
+        assert_eq!(result, Some(&s("ñandú")));
+    }
+
+    #[test]
+    fn test_predicates() {
+        let machine = &mut Machine::new(&string_op_sys);
+
+        for (operator, pattern, expected) in [
+            (StartsWith, "foo", true),
+            (StartsWith, "bar", false),
+            (EndsWith, "bar", true),
+            (EndsWith, "foo", false),
+            (Contains, "ob", true),
+            (Contains, "bo", false),
+        ] {
+            let result = machine
+                .run_script(&Vec::from([
+                    Value(s("foobar")),
+                    Value(s(pattern)),
+                    Operator(operator),
+                ]))
+                .unwrap();
+            assert_eq!(result, Some(&Boolean(expected)));
+        }
+    }
+
+    #[test]
+    fn test_split_and_replace() {
+        let machine = &mut Machine::new(&string_op_sys);
+
+        machine
+            .run_script(&Vec::from([
+                Value(s("a,b,,c")),
+                Value(s(",")),
+                Operator(Split),
+            ]))
+            .unwrap();
+        assert_eq!(machine.stack_length(), 5);
+
+        let machine = &mut Machine::new(&string_op_sys);
+        let result = machine
+            .run_script(&Vec::from([Value(s("añ")), Value(s("")), Operator(Split)]))
+            .unwrap();
+        assert_eq!(result, Some(&Integer(2)));
+        assert_eq!(machine.stack_length(), 3);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(s("1-2-3")),
+                Value(s("-")),
+                Value(s(" + ")),
+                Operator(Replace),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&s("1 + 2 + 3")));
+    }
+
+    #[test]
+    fn test_format() {
+        let machine = &mut Machine::new(&string_op_sys);
+
+        let result = machine
+            .run_script(&Vec::from([
+                Value(s("Alice")),
+                Value(Integer(30)),
+                Value(s("{} is {} years old {{really}}")),
+                Operator(Format),
+            ]))
+            .unwrap();
+        assert_eq!(result, Some(&s("Alice is 30 years old {really}")));
+        assert_eq!(machine.stack_length(), 1);
+
+        for format in ["{", "}", "{ }", "a}b"] {
+            let result = machine.run_script(&Vec::from([Value(s(format)), Operator(Format)]));
+            assert_eq!(
+                result,
+                Err(ScriptFailure {
+                    index: 1,
+                    error: OperatorError::InvalidFormat
+                })
+            );
+        }
+
+        let result = machine.run_script(&Vec::from([Value(s("{} {} {}")), Operator(Format)]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 1,
+                error: OperatorError::StackUnderflow
+            })
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        let machine = &mut Machine::new(&string_op_sys);
+
+        let result = machine.run_script(&Vec::from([Value(Integer(1)), Operator(Upper)]));
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 1,
+                error: OperatorError::TypeMismatch {
+                    expected: "string",
+                    found: "integer"
+                }
+            })
+        );
+    }
+}
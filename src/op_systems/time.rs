@@ -0,0 +1,334 @@
+//! Operators for dealing with dates and times, such as checking whether a deadline has passed.
+//!
+//! Points in time are represented as `Timestamp` values, i.e. the number of seconds since the Unix
+//! epoch, and durations as `Integer` values in seconds.
+//!
+//! Scripts never read the current time from the operating system. Instead,
+//! [`time_op_sys`][time_op_sys] takes an additional [`Clock`][Clock] argument, through which the
+//! host application decides what `Now` is. This keeps scripts deterministic, e.g. a transaction
+//! can be validated against the time of the block it belongs to, and tests can use a
+//! [`FixedClock`][FixedClock]:
+//!
+//! ```rust
+//! use scriptful::prelude::*;
+//! use scriptful::core::value::Value::*;
+//! use scriptful::op_systems::time::*;
+//!
+//! // 2024-01-01T00:00:00Z
+//! let clock = FixedClock(1_704_067_200);
+//! let mut machine = Machine::new(|stack: &mut Stack, operator: &TimeOperator, if_stack: &mut ConditionStack| {
+//!     time_op_sys(stack, operator, if_stack, &clock)
+//! });
+//!
+//! // Is it later than 2023-12-25T00:00:00Z plus one week?
+//! let result = machine.run_script(&[
+//!     Item::Operator(TimeOperator::Now),
+//!     Item::Value(Timestamp(1_703_462_400)),
+//!     Item::Value(Integer(7 * 24 * 3600)),
+//!     Item::Operator(TimeOperator::AddDuration),
+//!     Item::Operator(TimeOperator::After),
+//! ]);
+//!
+//! assert_eq!(result, Ok(Some(&Boolean(false))));
+//! ```
+//!
+//! [time_op_sys]: fn.time_op_sys.html
+//! [Clock]: trait.Clock.html
+//! [FixedClock]: struct.FixedClock.html
+
+use crate::core::value::Value;
+use crate::op_systems::OperatorError;
+use crate::prelude::*;
+
+/// Operators for dealing with dates and times.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TimeOperator {
+    /// Moves a timestamp forward by a number of seconds, or backwards if negative.
+    ///
+    /// `( timestamp seconds -- timestamp )`
+    AddDuration,
+    /// Tells whether a timestamp is strictly later than another one.
+    ///
+    /// `( a b -- boolean )`
+    After,
+    /// Tells whether a timestamp is strictly earlier than another one.
+    ///
+    /// `( a b -- boolean )`
+    Before,
+    /// Tells the day of the week of a timestamp in UTC, as per ISO 8601, i.e. from `1` for Monday
+    /// to `7` for Sunday.
+    ///
+    /// `( timestamp -- integer )`
+    DayOfWeek,
+    /// Pushes the current time, as told by the [`Clock`][Clock] provided by the host application.
+    ///
+    /// `( -- timestamp )`
+    ///
+    /// [Clock]: trait.Clock.html
+    Now,
+}
+
+/// A source of the current time for [`time_op_sys`][time_op_sys].
+///
+/// It is implemented for any `Fn() -> i64` closure returning the number of seconds since the Unix
+/// epoch.
+///
+/// [time_op_sys]: fn.time_op_sys.html
+pub trait Clock {
+    /// The current time, as the number of seconds since the Unix epoch.
+    fn now(&self) -> i64;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> i64,
+{
+    fn now(&self) -> i64 {
+        self()
+    }
+}
+
+/// A [`Clock`][Clock] that is stopped at a given number of seconds since the Unix epoch.
+///
+/// [Clock]: trait.Clock.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedClock(pub i64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0
+    }
+}
+
+/// An operator system that decides how each of the variants of [`TimeOperator`][TimeOperator]
+/// trigger push and pulls on the [`Stack`][Stack] inside a [`Machine`][Machine], using `clock` for
+/// telling the current time.
+///
+/// [TimeOperator]: enum.TimeOperator.html
+/// [Stack]: ../../core/stack/struct.Stack.html
+/// [Machine]: ../../core/machine/struct.Machine.html
+pub fn time_op_sys<C>(
+    stack: &mut Stack,
+    operator: &TimeOperator,
+    if_stack: &mut ConditionStack,
+    clock: &C,
+) -> Result<(), OperatorError>
+where
+    C: Clock + ?Sized,
+{
+    if !if_stack.all_true() {
+        return Ok(());
+    }
+
+    match operator {
+        TimeOperator::AddDuration => {
+            let seconds = match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+                Value::Integer(x) => i64::try_from(x).map_err(|_| OperatorError::OutOfRange)?,
+                other => {
+                    return Err(OperatorError::TypeMismatch {
+                        expected: "integer",
+                        found: other.type_name(),
+                    })
+                }
+            };
+            let timestamp = pop_timestamp(stack)?;
+
+            stack.push(Value::Timestamp(
+                timestamp
+                    .checked_add(seconds)
+                    .ok_or(OperatorError::OutOfRange)?,
+            ));
+        }
+        TimeOperator::After => {
+            let b = pop_timestamp(stack)?;
+            let a = pop_timestamp(stack)?;
+            stack.push(Value::Boolean(a > b));
+        }
+        TimeOperator::Before => {
+            let b = pop_timestamp(stack)?;
+            let a = pop_timestamp(stack)?;
+            stack.push(Value::Boolean(a < b));
+        }
+        TimeOperator::DayOfWeek => {
+            let timestamp = pop_timestamp(stack)?;
+            // The Unix epoch was a Thursday.
+            let days = timestamp.div_euclid(86_400);
+            stack.push(Value::Integer(i128::from((days + 3).rem_euclid(7) + 1)));
+        }
+        TimeOperator::Now => stack.push(Value::Timestamp(clock.now())),
+    }
+
+    Ok(())
+}
+
+/// Pops a value from the stack, making sure it is a `Timestamp`.
+fn pop_timestamp(stack: &mut Stack) -> Result<i64, OperatorError> {
+    match stack.pop().ok_or(OperatorError::StackUnderflow)? {
+        Value::Timestamp(x) => Ok(x),
+        other => Err(OperatorError::TypeMismatch {
+            expected: "timestamp",
+            found: other.type_name(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::core::machine::ScriptFailure;
+    use crate::core::value::Value::{self, *};
+    use crate::op_systems::time::*;
+    use crate::op_systems::OperatorError;
+
+    // 2009-01-03T18:15:05Z, a Saturday.
+    const GENESIS: i64 = 1_231_006_505;
+
+    /// Runs a script on a new machine whose clock is stopped at `GENESIS`, returning the topmost
+    /// value.
+    fn run(script: &[Item<TimeOperator>]) -> Result<Option<Value>, ScriptFailure<OperatorError>> {
+        let mut machine = Machine::new(
+            |stack: &mut Stack, operator: &TimeOperator, if_stack: &mut ConditionStack| {
+                time_op_sys(stack, operator, if_stack, &FixedClock(GENESIS))
+            },
+        );
+
+        machine.run_script(script).map(|value| value.cloned())
+    }
+
+    #[test]
+    fn test_now() {
+        let result = run(&[Item::Operator(TimeOperator::Now)]);
+        assert_eq!(result, Ok(Some(Timestamp(GENESIS))));
+    }
+
+    #[test]
+    fn test_clock_is_read_on_every_now() {
+        let time = Cell::new(0);
+        let clock = || time.get();
+        let mut machine = Machine::new(
+            |stack: &mut Stack, operator: &TimeOperator, if_stack: &mut ConditionStack| {
+                time_op_sys(stack, operator, if_stack, &clock)
+            },
+        );
+
+        machine.operate(&Item::Operator(TimeOperator::Now)).unwrap();
+        time.set(10);
+        let result = machine.run_script(&[
+            Item::Operator(TimeOperator::Now),
+            Item::Operator(TimeOperator::Before),
+        ]);
+
+        assert_eq!(result, Ok(Some(&Boolean(true))));
+    }
+
+    #[test]
+    fn test_comparison() {
+        for (a, b, before, after) in [
+            (1, 2, true, false),
+            (2, 1, false, true),
+            (1, 1, false, false),
+        ] {
+            let result = run(&[
+                Item::Value(Timestamp(a)),
+                Item::Value(Timestamp(b)),
+                Item::Operator(TimeOperator::Before),
+            ]);
+            assert_eq!(result, Ok(Some(Boolean(before))));
+
+            let result = run(&[
+                Item::Value(Timestamp(a)),
+                Item::Value(Timestamp(b)),
+                Item::Operator(TimeOperator::After),
+            ]);
+            assert_eq!(result, Ok(Some(Boolean(after))));
+        }
+    }
+
+    #[test]
+    fn test_add_duration() {
+        let result = run(&[
+            Item::Value(Timestamp(GENESIS)),
+            Item::Value(Integer(-5)),
+            Item::Operator(TimeOperator::AddDuration),
+        ]);
+        assert_eq!(result, Ok(Some(Timestamp(GENESIS - 5))));
+
+        let result = run(&[
+            Item::Value(Timestamp(i64::MAX)),
+            Item::Value(Integer(1)),
+            Item::Operator(TimeOperator::AddDuration),
+        ]);
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::OutOfRange
+            })
+        );
+
+        let result = run(&[
+            Item::Value(Timestamp(0)),
+            Item::Value(Integer(i128::from(i64::MAX) + 1)),
+            Item::Operator(TimeOperator::AddDuration),
+        ]);
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::OutOfRange
+            })
+        );
+    }
+
+    #[test]
+    fn test_day_of_week() {
+        for (timestamp, expected) in [
+            // Thursday.
+            (0, 4),
+            // Wednesday, one second before the Unix epoch.
+            (-1, 3),
+            // Saturday.
+            (GENESIS, 6),
+            // 2024-01-01T00:00:00Z, Monday.
+            (1_704_067_200, 1),
+            // 2024-01-07T23:59:59Z, Sunday.
+            (1_704_671_999, 7),
+        ] {
+            let result = run(&[
+                Item::Value(Timestamp(timestamp)),
+                Item::Operator(TimeOperator::DayOfWeek),
+            ]);
+            assert_eq!(result, Ok(Some(Integer(expected))));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let result = run(&[
+            Item::Value(Integer(1)),
+            Item::Value(Timestamp(1)),
+            Item::Operator(TimeOperator::After),
+        ]);
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 2,
+                error: OperatorError::TypeMismatch {
+                    expected: "timestamp",
+                    found: "integer"
+                }
+            })
+        );
+
+        let result = run(&[Item::Operator(TimeOperator::DayOfWeek)]);
+        assert_eq!(
+            result,
+            Err(ScriptFailure {
+                index: 0,
+                error: OperatorError::StackUnderflow
+            })
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Helpers shared by the tests of several modules, most notably the property tests of the codecs.
+
+// Not every helper is needed by every combination of features.
+#![allow(dead_code)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use proptest::prelude::*;
+
+use crate::core::decimal::Decimal;
+use crate::core::value::{CanonicalValue, Value};
+use crate::op_systems::simple_math::MathOperator;
+use crate::prelude::*;
+
+/// Decodes a string of hexadecimal digits into bytes.
+pub(crate) fn hex(input: &str) -> Vec<u8> {
+    (0..input.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&input[index..index + 2], 16).unwrap())
+        .collect()
+}
+
+/// Values of every type, including big integers when the `bigint` feature is enabled.
+pub(crate) fn value() -> impl Strategy<Value = Value> {
+    let values = prop_oneof![
+        any::<bool>().prop_map(Value::Boolean),
+        any::<f64>().prop_map(Value::Float),
+        any::<i128>().prop_map(Value::Integer),
+        prop::collection::vec(any::<char>(), 0..64)
+            .prop_map(|chars| Value::String(chars.into_iter().collect::<String>())),
+        Just(Value::Null),
+        (any::<i128>(), 0..=Decimal::MAX_SCALE)
+            .prop_map(|(mantissa, scale)| Value::Decimal(Decimal::new(mantissa, scale).unwrap())),
+        prop::collection::vec(any::<u8>(), 0..300).prop_map(Value::Bytes),
+        any::<i64>().prop_map(Value::Timestamp),
+    ];
+
+    #[cfg(feature = "bigint")]
+    let values = prop_oneof![
+        values,
+        prop::collection::vec(any::<u8>(), 0..40)
+            .prop_map(|bytes| Value::BigInt(num_bigint::BigInt::from_signed_bytes_le(&bytes))),
+    ];
+
+    values
+}
+
+/// Scripts mixing any of the `values` with every `MathOperator`.
+pub(crate) fn script<S>(values: S) -> impl Strategy<Value = Script<MathOperator>>
+where
+    S: Strategy<Value = Value>,
+{
+    let operators = prop_oneof![
+        Just(MathOperator::Add),
+        Just(MathOperator::Equal),
+        Just(MathOperator::Mul),
+        Just(MathOperator::Not),
+        Just(MathOperator::Sub),
+        Just(MathOperator::StrictEqual),
+    ];
+
+    prop::collection::vec(
+        prop_oneof![
+            values.prop_map(Item::Value),
+            operators.prop_map(Item::Operator)
+        ],
+        0..32,
+    )
+}
+
+/// Compares items exactly, as the approximate comparison of floats in `Value` does not hold for
+/// `NaN` nor infinities.
+pub(crate) fn canonical<Op>(script: Script<Op>) -> Vec<Item<Op, CanonicalValue>>
+where
+    Op: core::fmt::Debug,
+{
+    script
+        .into_iter()
+        .map(|item| match item {
+            Item::Operator(operator) => Item::Operator(operator),
+            Item::Value(value) => Item::Value(CanonicalValue(value)),
+        })
+        .collect()
+}
@@ -0,0 +1,57 @@
+//! Replays the inputs in `tests/data/decoder_regressions` through `SimpleScriptCodec`.
+//!
+//! These are inputs that used to make the decoder panic or misbehave, as well as interesting seeds
+//! for the `decode_script` fuzz target. Files whose name starts with `valid_` must decode
+//! successfully, and all others must fail to decode with an error.
+
+#![cfg(feature = "codecs")]
+
+use std::fs;
+use std::path::Path;
+
+use scriptful::codecs::dec::{Decode, Decoder};
+use scriptful::codecs::simple::SimpleScriptCodec;
+use scriptful::core::value::Value;
+use scriptful::op_systems::bitwise::BitwiseOperator;
+use scriptful::op_systems::simple_math::MathOperator;
+
+#[derive(Debug, PartialEq)]
+enum AnyOperator {
+    Bitwise(BitwiseOperator),
+    Math(MathOperator),
+}
+
+impl Decode for AnyOperator {
+    fn decode<D>(decoder: &mut D) -> Result<Self, <D as Decoder>::Error>
+    where
+        D: Decoder,
+    {
+        if *decoder.peek_byte()? >= 0x90 {
+            BitwiseOperator::decode(decoder).map(AnyOperator::Bitwise)
+        } else {
+            MathOperator::decode(decoder).map(AnyOperator::Math)
+        }
+    }
+}
+
+#[test]
+fn test_decoder_regressions() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/decoder_regressions");
+    let mut count = 0;
+
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let bytes = fs::read(&path).unwrap();
+        let decoded = <&mut SimpleScriptCodec>::from_vec::<AnyOperator, Value>(bytes);
+
+        if name.starts_with("valid_") {
+            assert!(decoded.is_ok(), "{} failed to decode: {:?}", name, decoded);
+        } else {
+            assert!(decoded.is_err(), "{} decoded as {:?}", name, decoded);
+        }
+        count += 1;
+    }
+
+    assert!(count > 0);
+}
@@ -0,0 +1,213 @@
+//! Checks the `Encode` and `Decode` derive macros against `SimpleScriptCodec`, `CborCodec` and `JsonCodec`.
+
+#![cfg(feature = "derive")]
+
+use scriptful::codecs::cbor::CborCodec;
+use scriptful::codecs::dec::{Decode, Decoder};
+use scriptful::codecs::enc::{Encode, Encoder};
+use scriptful::codecs::json::JsonCodec;
+use scriptful::codecs::simple::SimpleScriptCodec;
+use scriptful::codecs::{DecodingError, EncodingError};
+use scriptful::core::value::Value;
+use scriptful::prelude::*;
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum Condition {
+    #[opcode = 0x00]
+    Always,
+    Never,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum Operator {
+    #[opcode = 0xa0]
+    Add,
+    Sub,
+    #[opcode = 0xb0]
+    Push(Value),
+    Jump {
+        condition: Condition,
+        offset: Value,
+    },
+    #[opcode = 0xff]
+    Pair(Value, Value),
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum Wrapper<T> {
+    #[opcode = 0xc0]
+    Wrap(T),
+}
+
+fn round_trip<Op>(script: Script<Op>) -> Vec<u8>
+where
+    Op: core::fmt::Debug + PartialEq + Encode + Decode,
+{
+    let encoded = <&mut SimpleScriptCodec>::to_vec(&script).unwrap();
+    let decoded: Script<Op> = <&mut SimpleScriptCodec>::from_vec(encoded.clone()).unwrap();
+    assert_eq!(decoded, script);
+
+    encoded
+}
+
+#[test]
+fn test_unit_variants() {
+    let encoded = round_trip(Vec::from([
+        Item::Value(Value::Integer(1)),
+        Item::Operator(Operator::Add),
+        Item::Operator(Operator::Sub),
+    ]));
+
+    assert_eq!(encoded, Vec::from([0x03, 0x01, 0xa0, 0xa1]));
+}
+
+#[test]
+fn test_data_variants() {
+    let encoded = round_trip(Vec::from([
+        Item::Operator(Operator::Push(Value::String("a".into()))),
+        Item::Operator(Operator::Jump {
+            condition: Condition::Never,
+            offset: Value::Integer(-1),
+        }),
+        Item::Operator(Operator::Pair(Value::Null, Value::Boolean(true))),
+    ]));
+
+    assert_eq!(
+        encoded,
+        Vec::from([0xb0, 0x14, 0x01, 0x61, 0xb1, 0x01, 0x03, 0xff, 0xff, 0x1c, 0x01])
+    );
+}
+
+#[test]
+fn test_cbor_data_variants() {
+    let script: Script<Operator> = Vec::from([
+        Item::Operator(Operator::Push(Value::Integer(1))),
+        Item::Operator(Operator::Jump {
+            condition: Condition::Never,
+            offset: Value::Integer(-1),
+        }),
+        Item::Operator(Operator::Sub),
+    ]);
+
+    let encoded = <&mut CborCodec>::to_vec(&script).unwrap();
+    assert_eq!(
+        encoded,
+        Vec::from([
+            0x83, 0xd9, 0xc0, 0xde, 0x82, 0x18, 0xb0, 0x01, 0xd9, 0xc0, 0xde, 0x83, 0x18, 0xb1,
+            0xd9, 0xc0, 0xde, 0x01, 0x20, 0xd9, 0xc0, 0xde, 0x18, 0xa1,
+        ])
+    );
+
+    let decoded: Script<Operator> = <&mut CborCodec>::from_vec(encoded).unwrap();
+    assert_eq!(decoded, script);
+
+    // The array wrapping an operator must hold exactly its opcode and fields.
+    for (encoded, byte) in [
+        (
+            Vec::from([0x81, 0xd9, 0xc0, 0xde, 0x83, 0x18, 0xb0, 0x01, 0x02]),
+            0x83,
+        ),
+        (Vec::from([0x81, 0xd9, 0xc0, 0xde, 0x18, 0xb0, 0x01]), 0x18),
+    ] {
+        assert_eq!(
+            <&mut CborCodec>::from_vec::<Operator, Value>(encoded),
+            Err(DecodingError::InvalidDiscriminant { offset: 4, byte })
+        );
+    }
+}
+
+#[test]
+fn test_json_data_variants() {
+    let script: Script<Operator> = Vec::from([
+        Item::Operator(Operator::Push(Value::Integer(1))),
+        Item::Operator(Operator::Jump {
+            condition: Condition::Never,
+            offset: Value::Integer(-1),
+        }),
+        Item::Operator(Operator::Sub),
+    ]);
+
+    let encoded = <&mut JsonCodec>::to_vec(&script).unwrap();
+    assert_eq!(
+        String::from_utf8(encoded.clone()).unwrap(),
+        concat!(
+            r#"[{"op":"Push","args":[{"int":"1"}]},"#,
+            r#"{"op":"Jump","args":[{"op":"Never"},{"int":"-1"}]},"#,
+            r#"{"op":"Sub"}]"#
+        )
+    );
+
+    let decoded: Script<Operator> = <&mut JsonCodec>::from_vec(encoded).unwrap();
+    assert_eq!(decoded, script);
+
+    // Operators must be given exactly as many fields as they have.
+    let decoded = <&mut JsonCodec>::from_vec::<Operator, Value>(Vec::from(
+        r#"[{"op":"Pair","args":[{"null":null}]}]"#,
+    ));
+    assert_eq!(decoded, Err(DecodingError::InvalidToken { offset: 35 }));
+}
+
+#[test]
+fn test_generic_variants() {
+    let encoded = round_trip(Vec::from([Item::Operator(Wrapper::Wrap(Operator::Sub))]));
+
+    assert_eq!(encoded, Vec::from([0xc0, 0xa1]));
+}
+
+#[test]
+fn test_unknown_opcode() {
+    let decoded = <&mut SimpleScriptCodec>::from_vec::<Operator, Value>(Vec::from([0xa0, 0xa2]));
+    assert_eq!(
+        decoded,
+        Err(DecodingError::InvalidDiscriminant {
+            offset: 1,
+            byte: 0xa2
+        })
+    );
+
+    let decoded = <&mut SimpleScriptCodec>::from_vec::<Operator, Value>(Vec::from([0xb1, 0x02]));
+    assert_eq!(
+        decoded,
+        Err(DecodingError::InvalidDiscriminant {
+            offset: 1,
+            byte: 0x02
+        })
+    );
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum Defaulted {
+    Add,
+    Sub,
+}
+
+#[test]
+fn test_value_range_opcodes() {
+    // Without `#[opcode = ...]`, the opcodes start at `0x00` and would be decoded as values.
+    let script = Vec::from([
+        Item::<Defaulted, Value>::Operator(Defaulted::Add),
+        Item::Operator(Defaulted::Sub),
+    ]);
+    assert_eq!(
+        <&mut SimpleScriptCodec>::to_vec(&script),
+        Err(EncodingError::InvalidOpcode {
+            index: 0,
+            opcode: 0x00
+        })
+    );
+
+    let script = Vec::from([
+        Item::Value(Value::Integer(1)),
+        Item::Operator(Defaulted::Sub),
+    ]);
+    assert_eq!(
+        <&mut SimpleScriptCodec>::to_vec(&script),
+        Err(EncodingError::InvalidOpcode {
+            index: 1,
+            opcode: 0x01
+        })
+    );
+
+    // Nested in other operators, they are fine.
+    round_trip(Vec::from([Item::Operator(Wrapper::Wrap(Defaulted::Sub))]));
+}